@@ -0,0 +1,83 @@
+use grid::Grid;
+
+use crate::CellState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub fn turn_right(self) -> Self {
+        match self {
+            Self::Up => Self::Right,
+            Self::Right => Self::Down,
+            Self::Down => Self::Left,
+            Self::Left => Self::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Self::Up => Self::Left,
+            Self::Left => Self::Down,
+            Self::Down => Self::Right,
+            Self::Right => Self::Up,
+        }
+    }
+
+    /// Rotation in degrees, clockwise from `Up`
+    pub fn angle(self) -> f32 {
+        match self {
+            Self::Up => 0.0,
+            Self::Right => 90.0,
+            Self::Down => 180.0,
+            Self::Left => 270.0,
+        }
+    }
+}
+
+/// A Langton's ant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ant {
+    pub row: usize,
+    pub col: usize,
+    pub direction: Direction,
+}
+
+impl Ant {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self {
+            row,
+            col,
+            direction: Direction::Up,
+        }
+    }
+
+    /// On a dead cell the ant turns right, on any other cell it turns left.
+    /// It then flips the cell and moves forward, wrapping around the edges.
+    pub fn step(&mut self, grid: &mut Grid<CellState>) {
+        let cell = &mut grid[self.row][self.col];
+
+        if *cell == CellState::DEAD {
+            self.direction = self.direction.turn_right();
+            *cell = CellState::ALIVE;
+        } else {
+            self.direction = self.direction.turn_left();
+            *cell = CellState::DEAD;
+        }
+
+        let (rows, cols) = (grid.rows(), grid.cols());
+        match self.direction {
+            Direction::Up => self.row = (self.row + rows - 1) % rows,
+            Direction::Down => self.row = (self.row + 1) % rows,
+            Direction::Left => self.col = (self.col + cols - 1) % cols,
+            Direction::Right => self.col = (self.col + 1) % cols,
+        }
+    }
+}
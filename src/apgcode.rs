@@ -0,0 +1,479 @@
+//! Apgcodes, the names Catagolue files the objects of a census under
+//!
+//! An apgcode is `xs<population>_` for still lifes, `xp<period>_` for
+//! oscillators and `xq<period>_` for spaceships, followed by the extended
+//! Wechsler format of the object: its rows in strips of five, every column of
+//! a strip a character of the five bits, strips separated by `z` and runs of
+//! empty columns shortened. Of all phases and orientations the shortest and
+//! then alphabetically first code is the object's.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::cluster::{self, Cluster};
+use crate::engine::naive::Stepper;
+use crate::rule::{Neighbourhood, Rule};
+use crate::topology::Topology;
+use crate::CellState;
+
+/// Objects repeating after more generations than this are unidentified
+const MAX_PERIOD: u64 = 64;
+
+/// Characters of the five bit column values
+const DIGITS: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+/// Row and column of a live cell
+type Cell = (i64, i64);
+type Cells = Vec<Cell>;
+
+/// Number of objects with every apgcode
+#[derive(Default)]
+pub struct Objects {
+    counts: HashMap<String, u64>,
+
+    /// Objects which didn't repeat within [`MAX_PERIOD`] generations, still
+    /// growing or about to collide with their neighbours
+    pub unidentified: u64,
+}
+
+impl Objects {
+    pub fn add(&mut self, other: &Objects) {
+        for (code, count) in &other.counts {
+            *self.counts.entry(code.clone()).or_default() += count;
+        }
+        self.unidentified += other.unidentified;
+    }
+}
+
+/// Something like `xs4_33 x12, xp2_7 x5, xq4_153, 1 unidentified`, the most
+/// common objects first
+impl fmt::Display for Objects {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut counts = self.counts.iter().collect::<Vec<_>>();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+        let mut parts = counts
+            .into_iter()
+            .map(|(code, count)| match count {
+                1 => code.clone(),
+                count => format!("{code} x{count}"),
+            })
+            .collect::<Vec<_>>();
+
+        if self.unidentified > 0 {
+            parts.push(format!("{} unidentified", self.unidentified));
+        }
+
+        if parts.is_empty() {
+            f.write_str("empty")
+        } else {
+            f.write_str(&parts.join(", "))
+        }
+    }
+}
+
+/// Names the objects of grids, remembering the apgcodes every cluster shape
+/// splits into
+pub struct Classifier {
+    automaton: Automaton,
+    codes: HashMap<u64, Vec<Option<String>>>,
+}
+
+impl Classifier {
+    /// Catagolue's codes need two state Moore rules of radius 1, `None` for
+    /// any other rule
+    pub fn new(rule: Rule) -> Option<Self> {
+        (rule.states == 2 && rule.neighbourhood == Neighbourhood::Moore(1)).then(|| Self {
+            automaton: Automaton::Life(rule),
+            codes: HashMap::new(),
+        })
+    }
+
+    /// Apgcodes of the objects on the grid
+    pub fn census(&mut self, grid: &Grid<CellState>) -> Objects {
+        let mut objects = Objects::default();
+
+        for cluster in cluster::clusters(grid) {
+            let codes = self
+                .codes
+                .entry(cluster.shape_hash())
+                .or_insert_with(|| split(&self.automaton, &cluster));
+
+            for code in codes {
+                match code {
+                    Some(code) => *objects.counts.entry(code.clone()).or_default() += 1,
+                    None => objects.unidentified += 1,
+                }
+            }
+        }
+
+        objects
+    }
+}
+
+/// Apgcodes of the objects of the cluster. Like apgsearch, the cells touching
+/// each other start out as separate objects and the objects are merged where
+/// evolving them on their own gives other cells than evolving them together,
+/// so objects only count as one while they interact.
+fn split(automaton: &Automaton, cluster: &Cluster) -> Vec<Option<String>> {
+    let mut cells = cluster
+        .cells
+        .iter()
+        .filter(|(_, _, cell)| cell.is_alive())
+        .map(|(row, col, _)| ((row - cluster.top) as i64, (col - cluster.left) as i64))
+        .collect::<Cells>();
+
+    let mut whole = evolve(automaton, &cells);
+
+    // soups stopped early by escaping gliders can still be settling, split
+    // what they settle into instead
+    if let Some((start, _)) = cycle(&shapes(&whole)).filter(|(start, _)| *start > 0) {
+        cells = whole.swap_remove(start);
+        whole = evolve(automaton, &cells);
+    }
+
+    let mut parts = components(&cells)
+        .into_iter()
+        .map(|part| {
+            let evolution = evolve(automaton, &part);
+            (part, evolution)
+        })
+        .collect::<Vec<_>>();
+
+    while let Some(interacting) = first_interaction(&whole, &parts) {
+        let mut merged = Cells::new();
+        for idx in interacting.into_iter().rev() {
+            merged.extend(parts.swap_remove(idx).0);
+        }
+
+        let evolution = evolve(automaton, &merged);
+        parts.push((merged, evolution));
+    }
+
+    // cells dying out on their own without changing the rest were no object
+    parts
+        .iter()
+        .filter(|(_, evolution)| !evolution[evolution.len() - 1].is_empty())
+        .map(|(_, evolution)| apgcode(evolution))
+        .collect()
+}
+
+/// Groups of cells connected through their neighbours
+fn components(cells: &Cells) -> Vec<Cells> {
+    let mut unvisited = cells.iter().copied().collect::<HashSet<_>>();
+    let mut components = Vec::new();
+
+    for &cell in cells {
+        if !unvisited.remove(&cell) {
+            continue;
+        }
+
+        let mut stack = vec![cell];
+        let mut component = Cells::new();
+
+        while let Some((row, col)) = stack.pop() {
+            component.push((row, col));
+
+            for dr in -1..=1 {
+                for dc in -1..=1 {
+                    if unvisited.remove(&(row + dr, col + dc)) {
+                        stack.push((row + dr, col + dc));
+                    }
+                }
+            }
+        }
+
+        components.push(component);
+    }
+
+    components
+}
+
+/// The live cells of every generation up to [`MAX_PERIOD`] of the cells on
+/// their own, sorted and in the coordinates of the given cells
+fn evolve(automaton: &Automaton, cells: &Cells) -> Vec<Cells> {
+    // room for the object to move or grow by a cell every generation
+    let padding = MAX_PERIOD as i64 + 2;
+    let top = cells.iter().map(|cell| cell.0).min().unwrap_or(0) - padding;
+    let left = cells.iter().map(|cell| cell.1).min().unwrap_or(0) - padding;
+    let bottom = cells.iter().map(|cell| cell.0).max().unwrap_or(0) + padding;
+    let right = cells.iter().map(|cell| cell.1).max().unwrap_or(0) + padding;
+
+    let mut grid = Grid::new((bottom - top + 1) as usize, (right - left + 1) as usize);
+    for &(row, col) in cells {
+        grid[(row - top) as usize][(col - left) as usize] = CellState::ALIVE;
+    }
+
+    let live_cells = |grid: &Grid<CellState>| {
+        grid.indexed_iter()
+            .filter(|(_, cell)| cell.is_alive())
+            .map(|((row, col), _)| (row as i64 + top, col as i64 + left))
+            .collect::<Cells>()
+    };
+
+    let mut evolution = vec![live_cells(&grid)];
+    let mut stepper = Stepper::new();
+    for _ in 0..MAX_PERIOD {
+        stepper.step(automaton, Topology::Plane, &mut grid);
+        evolution.push(live_cells(&grid));
+    }
+
+    evolution
+}
+
+/// Indices of the parts which evolve differently together than on their own
+/// at the first generation they do, `None` if they never do
+fn first_interaction(whole: &[Cells], parts: &[(Cells, Vec<Cells>)]) -> Option<Vec<usize>> {
+    for generation in 1..whole.len() {
+        let mut together = parts
+            .iter()
+            .flat_map(|(_, evolution)| evolution[generation].iter().copied())
+            .collect::<Cells>();
+        together.sort_unstable();
+
+        if together == whole[generation] {
+            continue;
+        }
+
+        let expected = whole[generation].iter().collect::<HashSet<_>>();
+        let actual = together.iter().collect::<HashSet<_>>();
+        let different = expected.symmetric_difference(&actual).collect::<Vec<_>>();
+
+        // the parts still matched a generation earlier, so only the parts
+        // neighbouring the cells which differ now can have caused it
+        let interacting = parts
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, evolution))| {
+                evolution[generation - 1].iter().any(|(row, col)| {
+                    different
+                        .iter()
+                        .any(|cell| (cell.0 - row).abs() <= 1 && (cell.1 - col).abs() <= 1)
+                })
+            })
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        return Some(if interacting.len() >= 2 {
+            interacting
+        } else {
+            (0..parts.len()).collect()
+        });
+    }
+
+    None
+}
+
+/// Code of the evolution of an object, `None` if it doesn't repeat in time
+fn apgcode(evolution: &[Cells]) -> Option<String> {
+    let shapes = shapes(evolution);
+    let (start, end) = cycle(&shapes)?;
+
+    let period = end - start;
+    let prefix = if evolution[start] != evolution[end] {
+        format!("xq{period}")
+    } else if period == 1 {
+        format!("xs{}", evolution[start].len())
+    } else {
+        format!("xp{period}")
+    };
+
+    let wechsler = shapes[start..end]
+        .iter()
+        .flat_map(orientations)
+        .map(|cells| wechsler(&cells))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))?;
+
+    Some(format!("{prefix}_{wechsler}"))
+}
+
+fn shapes(evolution: &[Cells]) -> Vec<Cells> {
+    evolution
+        .iter()
+        .map(|cells| normalized(cells.clone()))
+        .collect()
+}
+
+/// First and last generation of the first repeating phases of the shapes,
+/// objects can take a few generations to join the phases they repeat
+fn cycle(shapes: &[Cells]) -> Option<(usize, usize)> {
+    (1..shapes.len()).find_map(|end| {
+        let start = shapes[..end]
+            .iter()
+            .position(|shape| *shape == shapes[end])?;
+        Some((start, end))
+    })
+}
+
+/// The cells moved to the top left corner, sorted
+fn normalized(mut cells: Cells) -> Cells {
+    let top = cells.iter().map(|cell| cell.0).min().unwrap_or(0);
+    let left = cells.iter().map(|cell| cell.1).min().unwrap_or(0);
+
+    for cell in &mut cells {
+        *cell = (cell.0 - top, cell.1 - left);
+    }
+    cells.sort_unstable();
+    cells
+}
+
+/// All four rotations of the cells and of their mirror image
+fn orientations(cells: &Cells) -> Vec<Cells> {
+    let transforms: [fn(Cell) -> Cell; 8] = [
+        |(r, c)| (r, c),
+        |(r, c)| (c, -r),
+        |(r, c)| (-r, -c),
+        |(r, c)| (-c, r),
+        |(r, c)| (c, r),
+        |(r, c)| (-r, c),
+        |(r, c)| (-c, -r),
+        |(r, c)| (r, -c),
+    ];
+
+    transforms
+        .into_iter()
+        .map(|transform| normalized(cells.iter().map(|cell| transform(*cell)).collect()))
+        .collect()
+}
+
+/// Extended Wechsler format of normalized cells
+fn wechsler(cells: &Cells) -> String {
+    let rows = cells.iter().map(|cell| cell.0 + 1).max().unwrap_or(0) as usize;
+    let cols = cells.iter().map(|cell| cell.1 + 1).max().unwrap_or(0) as usize;
+
+    let mut strips = vec![vec![0u8; cols]; rows.div_ceil(5)];
+    for &(row, col) in cells {
+        strips[row as usize / 5][col as usize] |= 1 << (row % 5);
+    }
+
+    let strips = strips
+        .iter()
+        .map(|strip| {
+            let mut code = String::new();
+            let mut empty = 0;
+
+            for &column in strip {
+                if column == 0 {
+                    empty += 1;
+                    continue;
+                }
+
+                shorten_empty(&mut code, empty);
+                empty = 0;
+                code.push(DIGITS[column as usize] as char);
+            }
+
+            // empty columns at the end of a strip are left out
+            code
+        })
+        .collect::<Vec<_>>();
+
+    strips.join("z")
+}
+
+/// `0`, `w` and `x` for one to three empty columns, `y` followed by a digit
+/// for four to 39
+fn shorten_empty(code: &mut String, mut empty: usize) {
+    while empty > 39 {
+        code.push_str("yz");
+        empty -= 39;
+    }
+
+    match empty {
+        0 => {}
+        1 => code.push('0'),
+        2 => code.push('w'),
+        3 => code.push('x'),
+        empty => {
+            code.push('y');
+            code.push(char::from_digit(empty as u32 - 4, 36).unwrap());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The census of a plaintext pattern in the middle of an empty grid
+    fn census(rows: &[&str]) -> String {
+        let mut grid = Grid::new(rows.len() + 8, rows[0].len() + 8);
+        for (row, line) in rows.iter().enumerate() {
+            for (col, cell) in line.chars().enumerate() {
+                if cell == 'O' {
+                    grid[row + 4][col + 4] = CellState::ALIVE;
+                }
+            }
+        }
+
+        Classifier::new(Rule::CONWAY)
+            .unwrap()
+            .census(&grid)
+            .to_string()
+    }
+
+    #[test]
+    fn names_well_known_objects() {
+        assert_eq!(census(&["OO", "OO"]), "xs4_33");
+        assert_eq!(census(&["OO..", "OO..", "..OO", "..OO"]), "xp2_318c");
+        assert_eq!(census(&[".O.", "..O", "OOO"]), "xq4_153");
+        assert_eq!(census(&[".O..O", "O....", "O...O", "OOOO."]), "xq4_6frc");
+    }
+
+    #[test]
+    fn names_every_phase_and_orientation_the_same() {
+        assert_eq!(census(&["OOO"]), "xp2_7");
+        assert_eq!(census(&["O", "O", "O"]), "xp2_7");
+        assert_eq!(census(&["OO.", "O.O", ".O."]), "xs5_253");
+        assert_eq!(census(&[".O.", "O.O", ".OO"]), "xs5_253");
+        assert_eq!(census(&["O.O", ".OO", ".O."]), "xq4_153");
+    }
+
+    #[test]
+    fn splits_objects_which_do_not_interact() {
+        assert_eq!(census(&["OOO.OOO"]), "xp2_7 x2");
+        assert_eq!(census(&["OO.OO", "OO.OO"]), "xs4_33 x2");
+        assert_eq!(census(&["OO..OOO", "OO....."]), "xp2_7, xs4_33");
+        assert_eq!(
+            census(&["O..", "O..", "O..", "...", "..O", "..O", "..O"]),
+            "xp2_7 x2"
+        );
+        assert_eq!(
+            census(&["OO...", "OO...", ".....", "...OO", "...OO"]),
+            "xs4_33 x2"
+        );
+    }
+
+    #[test]
+    fn splits_what_the_cells_settle_into() {
+        // the T-tetromino settles into the four blinkers of a traffic light
+        assert_eq!(census(&["OOO", ".O."]), "xp2_7 x4");
+    }
+
+    #[test]
+    fn keeps_interacting_objects_together() {
+        let pulsar = [
+            "..OOO...OOO..",
+            ".............",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            "..OOO...OOO..",
+            ".............",
+            "..OOO...OOO..",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            ".............",
+            "..OOO...OOO..",
+        ];
+        assert_eq!(
+            census(&pulsar),
+            "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401"
+        );
+        assert_eq!(census(&[".OO", "OO.", ".O."]), "1 unidentified");
+    }
+}
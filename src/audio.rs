@@ -0,0 +1,93 @@
+//! Sound effects, synthesized when the window opens so there are no files
+//! to ship
+//!
+//! Every step ticks and every toggled cell clicks. While sonifying, the
+//! tick is replaced by a note which rises with the share of the grid alive.
+
+use std::f32::consts::TAU;
+
+use sfml::audio::{Sound, SoundBuffer, SoundSource};
+use sfml::SfBox;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Sounds which can play at the same time, the oldest one is cut off
+const VOICES: usize = 8;
+
+/// Semitones the note of sonified steps rises from an empty grid to one
+/// which is half alive
+const SONIFY_RANGE: f32 = 24.0;
+
+pub struct Sounds {
+    tick: SfBox<SoundBuffer>,
+    click: SfBox<SoundBuffer>,
+    note: SfBox<SoundBuffer>,
+}
+
+impl Sounds {
+    pub fn new() -> Self {
+        let buffer = |samples: Vec<i16>| {
+            SoundBuffer::from_samples(&samples, 1, SAMPLE_RATE)
+                .expect("Couldn't create the sound effects")
+        };
+
+        Self {
+            tick: buffer(tone(1800.0, 0.025, 0.15, 200.0)),
+            click: buffer(tone(900.0, 0.015, 0.3, 300.0)),
+            note: buffer(tone(220.0, 0.15, 0.25, 12.0)),
+        }
+    }
+}
+
+pub struct Audio<'a> {
+    sounds: &'a Sounds,
+    voices: Vec<Sound<'a>>,
+    next_voice: usize,
+}
+
+impl<'a> Audio<'a> {
+    pub fn new(sounds: &'a Sounds) -> Self {
+        Self {
+            sounds,
+            voices: (0..VOICES).map(|_| Sound::new()).collect(),
+            next_voice: 0,
+        }
+    }
+
+    pub fn tick(&mut self) {
+        self.play(&self.sounds.tick, 1.0);
+    }
+
+    pub fn click(&mut self) {
+        self.play(&self.sounds.click, 1.0);
+    }
+
+    /// Plays the note for a grid with this share of its cells alive
+    pub fn sonify(&mut self, alive: f32) {
+        let semitones = (alive.clamp(0.0, 0.5) * 2.0 * SONIFY_RANGE).round();
+        self.play(&self.sounds.note, 2f32.powf(semitones / 12.0));
+    }
+
+    fn play(&mut self, buffer: &'a SoundBuffer, pitch: f32) {
+        let voice = &mut self.voices[self.next_voice];
+        self.next_voice = (self.next_voice + 1) % VOICES;
+
+        voice.stop();
+        voice.set_buffer(buffer);
+        voice.set_pitch(pitch);
+        voice.play();
+    }
+}
+
+/// Sine wave fading out exponentially, `decay` is how fast per second
+fn tone(frequency: f32, duration: f32, volume: f32, decay: f32) -> Vec<i16> {
+    let samples = (duration * SAMPLE_RATE as f32) as usize;
+
+    (0..samples)
+        .map(|idx| {
+            let t = idx as f32 / SAMPLE_RATE as f32;
+            let sample = (TAU * frequency * t).sin() * (-t * decay).exp() * volume;
+            (sample * i16::MAX as f32) as i16
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+use crate::hrot::Hrot;
+use crate::ltl::LtlRule;
+use crate::palette::Palette;
+use crate::rule::{Neighbourhood, Rule};
+use crate::CellState;
+
+/// Built-in automata which can be cycled through at runtime
+pub const PRESETS: &[(&str, Automaton)] = &[
+    ("Conway's life", Automaton::Life(Rule::CONWAY)),
+    ("Brian's brain", Automaton::Life(Rule::BRIANS_BRAIN)),
+    ("Hex life", Automaton::Life(Rule::HEX_LIFE)),
+    ("Bugs", Automaton::LargerThanLife(LtlRule::BUGS)),
+    ("Wireworld", Automaton::Wireworld),
+    ("Immigration", Automaton::Immigration),
+    ("Rule 30", Automaton::Elementary(30)),
+    ("Rule 90", Automaton::Elementary(90)),
+    ("Rule 110", Automaton::Elementary(110)),
+];
+
+/// The transition function used to compute the next generation
+///
+/// Every two dimensional automaton counts the neighbours in state `1` and
+/// decides the next state of a cell purely from its current state and that
+/// count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Automaton {
+    /// Life-like and Generations rules
+    Life(Rule),
+
+    /// Larger than Life rules with a large radius and ranges of counts
+    LargerThanLife(LtlRule),
+
+    /// Brian Silverman's Wireworld, the states are empty, electron head,
+    /// electron tail and conductor in that order
+    Wireworld,
+
+    /// Two player variant of Conway's life, the live cells are in state `1`
+    /// or `2` depending on their player and every birth takes the one most of
+    /// its three parents belong to
+    Immigration,
+
+    /// One of Wolfram's elementary one dimensional automata, only the bottom
+    /// row of the grid is the current generation and the rows above it are
+    /// its history
+    Elementary(u8),
+}
+
+impl Automaton {
+    /// Total number of states, including the dead (empty) state
+    pub fn states(&self) -> u8 {
+        match self {
+            Self::Life(rule) => rule.states,
+            Self::LargerThanLife(rule) => rule.states,
+            Self::Wireworld => 4,
+            Self::Immigration => 3,
+            Self::Elementary(_) => 2,
+        }
+    }
+
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        match self {
+            Self::Life(rule) => rule.neighbourhood,
+            Self::LargerThanLife(rule) => Neighbourhood::Moore(rule.radius),
+            Self::Wireworld | Self::Immigration | Self::Elementary(_) => Neighbourhood::Moore(1),
+        }
+    }
+
+    /// Whether the cell counts as alive, the cells of both players do in
+    /// Immigration but only state `1` does in the other automata
+    pub fn is_alive(&self, cell: CellState) -> bool {
+        match self {
+            Self::Immigration => cell != CellState::DEAD,
+            _ => cell.is_alive(),
+        }
+    }
+
+    pub fn next_state(&self, cell: CellState, alive_neighbours: usize) -> CellState {
+        match self {
+            Self::Life(rule) => rule.next_state(cell, alive_neighbours),
+            Self::LargerThanLife(rule) => rule.next_state(cell, alive_neighbours),
+            Self::Wireworld => match cell.0 {
+                0 => CellState::DEAD,
+                1 => CellState(2),
+                2 => CellState(3),
+                _ if alive_neighbours == 1 || alive_neighbours == 2 => CellState(1),
+                _ => CellState(3),
+            },
+            // elementary automata are stepped a whole row at a time by
+            // `step_row` and immigration needs the states of the neighbours
+            Self::Elementary(_) | Self::Immigration => cell,
+        }
+    }
+
+    /// Computes the next generation of an elementary automaton, cells beyond
+    /// the edges of the row are considered dead
+    pub fn step_row(rule: u8, row: &[CellState]) -> Vec<CellState> {
+        let alive = |idx: Option<usize>| {
+            idx.and_then(|idx| row.get(idx))
+                .map_or(0, |cell| cell.is_alive() as u8)
+        };
+
+        (0..row.len())
+            .map(|idx| {
+                let pattern =
+                    alive(idx.checked_sub(1)) << 2 | alive(Some(idx)) << 1 | alive(Some(idx + 1));
+
+                if rule & 1 << pattern != 0 {
+                    CellState::ALIVE
+                } else {
+                    CellState::DEAD
+                }
+            })
+            .collect()
+    }
+
+    pub fn state_name(&self, cell: CellState) -> &'static str {
+        match (self, cell.0) {
+            (Self::Wireworld, 0) => "empty",
+            (Self::Wireworld, 1) => "electron head",
+            (Self::Wireworld, 2) => "electron tail",
+            (Self::Wireworld, _) => "conductor",
+            (Self::Immigration, 1) => "player 1",
+            (Self::Immigration, 2) => "player 2",
+            (_, 0) => "dead",
+            (_, 1) => "alive",
+            (_, _) => "dying",
+        }
+    }
+
+    /// Fill color of a cell in the given state, in the classic palette
+    pub fn color(&self, cell: CellState) -> Color {
+        Palette::Classic.color(self, cell)
+    }
+}
+
+impl Default for Automaton {
+    fn default() -> Self {
+        Self::Life(Rule::default())
+    }
+}
+
+impl fmt::Display for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Life(rule) => rule.fmt(f),
+            Self::LargerThanLife(rule) => rule.fmt(f),
+            Self::Wireworld => f.write_str("Wireworld"),
+            Self::Immigration => f.write_str("Immigration"),
+            Self::Elementary(rule) => write!(f, "W{rule}"),
+        }
+    }
+}
+
+impl FromStr for Automaton {
+    type Err = String;
+
+    /// Any rulestring accepted by [`Rule`] or [`Hrot`], `Wireworld`,
+    /// `Immigration` or `W` followed by the number of an elementary rule
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.eq_ignore_ascii_case("wireworld") {
+            return Ok(Self::Wireworld);
+        }
+
+        if s.eq_ignore_ascii_case("immigration") {
+            return Ok(Self::Immigration);
+        }
+
+        if let Some(rule) = s.strip_prefix(['W', 'w']).and_then(|n| n.parse().ok()) {
+            return Ok(Self::Elementary(rule));
+        }
+
+        if s.starts_with(['R', 'r']) {
+            return s.parse::<Hrot>()?.automaton();
+        }
+
+        s.parse().map(Self::Life)
+    }
+}
@@ -0,0 +1,74 @@
+//! Periodically saves the grid so it can be restored after a crash
+//!
+//! A marker file exists for as long as the game is running, if it is still
+//! there on the next launch the game didn't exit cleanly and the autosave is
+//! offered for restoring.
+
+use std::fs;
+use std::path::PathBuf;
+
+use sfml::system::Time;
+
+use crate::rle::{self, Pattern};
+
+const INTERVAL: Time = Time::milliseconds(30_000);
+
+pub struct Autosave {
+    path: PathBuf,
+    marker: PathBuf,
+    elapsed: Time,
+
+    /// What was written last, nothing is written while it doesn't change
+    last_saved: Option<String>,
+}
+
+impl Autosave {
+    /// Starts a new session, returning the autosave of the previous one if
+    /// it didn't exit cleanly
+    pub fn start() -> (Self, Option<Pattern>) {
+        let dir = std::env::temp_dir();
+        let autosave = Self {
+            path: dir.join("game-of-life-autosave.rle"),
+            marker: dir.join("game-of-life.running"),
+            elapsed: Time::ZERO,
+            last_saved: None,
+        };
+
+        let recovered = autosave
+            .marker
+            .exists()
+            .then(|| fs::read_to_string(&autosave.path).ok())
+            .flatten()
+            .and_then(|text| rle::parse(&text).ok());
+
+        let _ = fs::write(&autosave.marker, "");
+        (autosave, recovered)
+    }
+
+    /// Writes the RLE returned by `rle` once the interval has passed
+    pub fn update(&mut self, dt: Time, rle: impl FnOnce() -> String) {
+        self.elapsed += dt;
+        if self.elapsed < INTERVAL {
+            return;
+        }
+        self.elapsed = Time::ZERO;
+
+        let text = rle();
+        if self.last_saved.as_ref() == Some(&text) {
+            return;
+        }
+
+        // written next to the autosave first so a crash while writing can't
+        // leave half a pattern behind
+        let tmp = self.path.with_extension("rle.tmp");
+        if fs::write(&tmp, &text).is_ok() && fs::rename(&tmp, &self.path).is_ok() {
+            self.last_saved = Some(text);
+        }
+    }
+
+    /// Called on a clean exit, there is nothing to recover afterwards
+    pub fn finish(self) {
+        let _ = fs::remove_file(&self.path);
+        let _ = fs::remove_file(&self.marker);
+    }
+}
@@ -0,0 +1,85 @@
+//! The headless `bench` subcommand
+
+use std::fs;
+use std::time::Instant;
+
+use crate::cli::BenchOptions;
+use crate::engine;
+use crate::period;
+use crate::rng::Rng;
+
+pub fn run(options: &BenchOptions) -> Result<(), String> {
+    let grid = engine::soup(
+        options.rows,
+        options.cols,
+        options.density,
+        &mut Rng::new(options.seed),
+    );
+    let mut engine = options.engine.create(&grid, options.rule)?;
+    drop(grid);
+
+    let start = Instant::now();
+    for _ in 0..options.generations {
+        engine.step();
+    }
+    let seconds = start.elapsed().as_secs_f64();
+
+    let generations_per_second = options.generations as f64 / seconds;
+    let population = engine.population();
+
+    // engines which end up with the same cells print the same hash
+    let cells = period::hash(&engine.grid());
+    let memory = engine.memory_usage();
+    let peak_rss = peak_rss();
+
+    if options.json {
+        println!(
+            "{{\"engine\":\"{}\",\"rule\":\"{}\",\"seed\":{},\"rows\":{},\"cols\":{},\"generations\":{},\
+             \"seconds\":{seconds},\"generations_per_second\":{generations_per_second},\
+             \"population\":{population},\"cells\":\"{cells:016x}\",\"memory_bytes\":{memory},\
+             \"peak_rss_bytes\":{}}}",
+            options.engine,
+            options.rule,
+            options.seed,
+            options.rows,
+            options.cols,
+            options.generations,
+            peak_rss.map_or(String::from("null"), |rss| rss.to_string()),
+        );
+    } else {
+        println!("engine:      {}", options.engine);
+        println!("rule:        {}", options.rule);
+        println!("seed:        {}", options.seed);
+        println!("universe:    {}x{}", options.rows, options.cols);
+        println!(
+            "generations: {} in {seconds:.3}s, {generations_per_second:.1} gen/s",
+            options.generations
+        );
+        println!("population:  {population}");
+        println!("cells:       {cells:016x}");
+        println!("memory:      {}", format_bytes(memory));
+
+        if let Some(rss) = peak_rss {
+            println!("peak RSS:    {}", format_bytes(rss));
+        }
+    }
+
+    Ok(())
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes < 1024 * 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// Peak resident memory of the whole process, only known on Linux
+fn peak_rss() -> Option<usize> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kib: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some(kib * 1024)
+}
@@ -0,0 +1,184 @@
+//! Zooming into and panning over the grid
+//!
+//! The cells are laid out in world coordinates as if the whole grid filled
+//! the window, the camera maps those onto the window.
+
+use crate::render::{Cell, Shape};
+use crate::{HEIGHT, WIDTH};
+
+pub const MAX_ZOOM: f32 = 64.0;
+
+/// Zoom change of a single step of the mouse wheel
+const ZOOM_FACTOR: f32 = 1.25;
+
+/// Seconds following takes to catch up most of the way with its target
+const FOLLOW_SMOOTHING: f32 = 0.4;
+
+/// Space kept around the followed cells on each side, as a fraction of
+/// their size
+const FOLLOW_MARGIN: f32 = 0.25;
+
+/// Following doesn't zoom in further, so a lone still life doesn't fill the
+/// whole window
+const MAX_FOLLOW_ZOOM: f32 = 8.0;
+
+/// A rectangle as left, top, width and height
+pub type Rect = (f32, f32, f32, f32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Camera {
+    /// World coordinates shown in the middle of the window
+    pub center: (f32, f32),
+
+    /// `1` shows the whole grid
+    pub zoom: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            center: (WIDTH as f32 / 2.0, HEIGHT as f32 / 2.0),
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Camera {
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > 1.0
+    }
+
+    pub fn to_screen(self, (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            (x - self.center.0) * self.zoom + WIDTH as f32 / 2.0,
+            (y - self.center.1) * self.zoom + HEIGHT as f32 / 2.0,
+        )
+    }
+
+    pub fn to_world(self, (x, y): (f32, f32)) -> (f32, f32) {
+        (
+            (x - WIDTH as f32 / 2.0) / self.zoom + self.center.0,
+            (y - HEIGHT as f32 / 2.0) / self.zoom + self.center.1,
+        )
+    }
+
+    /// Part of the world shown in the window
+    pub fn viewport(&self) -> Rect {
+        let (width, height) = (WIDTH as f32 / self.zoom, HEIGHT as f32 / self.zoom);
+        (
+            self.center.0 - width / 2.0,
+            self.center.1 - height / 2.0,
+            width,
+            height,
+        )
+    }
+
+    /// Zooms in by the number of wheel steps, or out for negative ones,
+    /// keeping the world under the window position in place
+    pub fn zoom_at(&mut self, position: (f32, f32), steps: f32) {
+        let before = self.to_world(position);
+        self.zoom = (self.zoom * ZOOM_FACTOR.powf(steps)).clamp(1.0, MAX_ZOOM);
+        let after = self.to_world(position);
+
+        self.center.0 += before.0 - after.0;
+        self.center.1 += before.1 - after.1;
+        self.clamp();
+    }
+
+    /// Drags the world along by a distance in window pixels
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.center.0 -= dx / self.zoom;
+        self.center.1 -= dy / self.zoom;
+        self.clamp();
+    }
+
+    pub fn look_at(&mut self, center: (f32, f32)) {
+        self.center = center;
+        self.clamp();
+    }
+
+    /// Pans and zooms part of the way towards showing all of the target,
+    /// how far depends on the time passed so it moves as smoothly at any
+    /// frame rate
+    pub fn follow(&mut self, (left, top, width, height): Rect, seconds: f32) {
+        let scale = 1.0 + 2.0 * FOLLOW_MARGIN;
+        let zoom = (WIDTH as f32 / (width * scale))
+            .min(HEIGHT as f32 / (height * scale))
+            .clamp(1.0, MAX_FOLLOW_ZOOM);
+        let center = (left + width / 2.0, top + height / 2.0);
+        let weight = 1.0 - (-seconds / FOLLOW_SMOOTHING).exp();
+
+        self.zoom += (zoom - self.zoom) * weight;
+        self.center.0 += (center.0 - self.center.0) * weight;
+        self.center.1 += (center.1 - self.center.1) * weight;
+        self.clamp();
+    }
+
+    /// Brings a camera read from a file back into range, one that isn't
+    /// even a number shows the whole grid again
+    #[cfg(feature = "serde")]
+    pub fn sanitize(&mut self) {
+        if !(self.zoom.is_finite() && self.center.0.is_finite() && self.center.1.is_finite()) {
+            *self = Self::default();
+        }
+
+        self.zoom = self.zoom.clamp(1.0, MAX_ZOOM);
+        self.clamp();
+    }
+
+    /// Keeps the viewport inside the world
+    fn clamp(&mut self) {
+        let (_, _, width, height) = self.viewport();
+        self.center.0 = self.center.0.clamp(width / 2.0, WIDTH as f32 - width / 2.0);
+        self.center.1 = self
+            .center
+            .1
+            .clamp(height / 2.0, HEIGHT as f32 - height / 2.0);
+    }
+
+    /// Moves a cell from world into window coordinates, `None` if it ends up
+    /// outside the grid area of the window
+    pub fn project(&self, cell: Cell) -> Option<Cell> {
+        let position = self.to_screen(cell.position);
+
+        let (shape, (left, top, width, height)) = match cell.shape {
+            Shape::Rect { width, height } => {
+                let (width, height) = (width * self.zoom, height * self.zoom);
+                (
+                    Shape::Rect { width, height },
+                    (position.0, position.1, width, height),
+                )
+            }
+            Shape::Polygon {
+                radius,
+                sides,
+                rotation,
+            } => {
+                let radius = radius * self.zoom;
+                (
+                    Shape::Polygon {
+                        radius,
+                        sides,
+                        rotation,
+                    },
+                    (
+                        position.0 - radius,
+                        position.1 - radius,
+                        2.0 * radius,
+                        2.0 * radius,
+                    ),
+                )
+            }
+        };
+
+        let visible =
+            left < WIDTH as f32 && top < HEIGHT as f32 && left + width > 0.0 && top + height > 0.0;
+
+        visible.then_some(Cell {
+            shape,
+            position,
+            ..cell
+        })
+    }
+}
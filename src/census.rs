@@ -0,0 +1,136 @@
+//! Counts the well known objects on the grid
+
+use std::collections::HashMap;
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::cluster::{self, Cluster};
+use crate::engine;
+use crate::topology::Topology;
+use crate::CellState;
+
+/// Singular and plural name, plaintext pattern and period of the objects
+/// which are recognised
+const OBJECTS: &[(&str, &str, &str, u32)] = &[
+    ("block", "blocks", "OO\nOO", 1),
+    ("beehive", "beehives", ".OO.\nO..O\n.OO.", 1),
+    ("loaf", "loaves", ".OO.\nO..O\n.O.O\n..O.", 1),
+    ("boat", "boats", "OO.\nO.O\n.O.", 1),
+    ("ship", "ships", "OO.\nO.O\n.OO", 1),
+    ("tub", "tubs", ".O.\nO.O\n.O.", 1),
+    ("pond", "ponds", ".OO.\nO..O\nO..O\n.OO.", 1),
+    ("blinker", "blinkers", "OOO", 2),
+    ("toad", "toads", ".OOO\nOOO.", 2),
+    ("beacon", "beacons", "OO..\nOO..\n..OO\n..OO", 2),
+    ("glider", "gliders", ".O.\n..O\nOOO", 4),
+    ("LWSS", "LWSSes", ".O..O\nO....\nO...O\nOOOO.", 4),
+];
+
+pub struct Census {
+    /// Index into [`OBJECTS`] of every phase and orientation of every object
+    shapes: HashMap<u64, usize>,
+}
+
+impl Census {
+    /// Only keeps the objects which really are still lifes, oscillators or
+    /// spaceships of that period under the given automaton
+    pub fn new(automaton: &Automaton) -> Self {
+        let mut shapes = HashMap::new();
+
+        for (idx, (_, _, pattern, period)) in OBJECTS.iter().enumerate() {
+            let mut grid = from_plaintext(pattern, *period as usize + 2);
+            let first = single_cluster(&grid).map(|cluster| cluster.shape_hash());
+            let mut phases = Vec::new();
+
+            for _ in 0..*period {
+                phases.push(grid.clone());
+                grid = engine::naive::step(automaton, Topology::Plane, &grid);
+            }
+
+            if first.is_none() || single_cluster(&grid).map(|c| c.shape_hash()) != first {
+                continue;
+            }
+
+            for phase in phases {
+                for orientation in orientations(phase) {
+                    if let Some(cluster) = single_cluster(&orientation) {
+                        shapes.insert(cluster.shape_hash(), idx);
+                    }
+                }
+            }
+        }
+
+        Self { shapes }
+    }
+
+    /// Something like `12 blocks, 5 blinkers, 1 glider, 3 other`, objects
+    /// which aren't recognised are counted as other
+    pub fn count(&self, grid: &Grid<CellState>) -> String {
+        let mut counts = vec![0; OBJECTS.len()];
+        let mut other = 0;
+
+        for cluster in cluster::clusters(grid) {
+            match self.shapes.get(&cluster.shape_hash()) {
+                Some(&idx) => counts[idx] += 1,
+                None => other += 1,
+            }
+        }
+
+        let mut parts = OBJECTS
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|((singular, plural, ..), count)| match count {
+                1 => format!("1 {singular}"),
+                count => format!("{count} {plural}"),
+            })
+            .collect::<Vec<_>>();
+
+        if other > 0 {
+            parts.push(format!("{other} other"));
+        }
+
+        if parts.is_empty() {
+            String::from("empty")
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// The pattern with `padding` dead cells around it
+fn from_plaintext(pattern: &str, padding: usize) -> Grid<CellState> {
+    let lines = pattern.lines().collect::<Vec<_>>();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let mut grid = Grid::new(lines.len() + padding * 2, width + padding * 2);
+
+    for (row, line) in lines.iter().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            if c == 'O' {
+                grid[row + padding][col + padding] = CellState::ALIVE;
+            }
+        }
+    }
+
+    grid
+}
+
+fn single_cluster(grid: &Grid<CellState>) -> Option<Cluster> {
+    let mut clusters = cluster::clusters(grid);
+    (clusters.len() == 1).then(|| clusters.remove(0))
+}
+
+/// All four rotations of the grid and of its mirror image
+fn orientations(grid: Grid<CellState>) -> Vec<Grid<CellState>> {
+    let mut orientations = Vec::with_capacity(8);
+
+    for mut grid in [grid.transpose(), grid] {
+        for _ in 0..4 {
+            grid = grid.rotate_left();
+            orientations.push(grid.clone());
+        }
+    }
+
+    orientations
+}
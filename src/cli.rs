@@ -0,0 +1,519 @@
+//! Command line arguments, without any arguments the window is opened
+
+use std::path::PathBuf;
+
+use crate::automaton::Automaton;
+use crate::command::{self, parse_value, Command as ConsoleCommand};
+use crate::engine::EngineKind;
+use crate::locale::Locale;
+use crate::rng;
+use crate::rule::Rule;
+use crate::topology::Topology;
+
+/// Largest cell of a timelapse frame, in pixels
+const MAX_SCALE: u32 = 64;
+
+/// Largest width and height of a timelapse frame, in pixels
+const MAX_FRAME_SIZE: usize = 16_384;
+
+pub const USAGE: &str = "\
+Usage: game-of-life [--seed <N>] [--command <COMMAND>]... [--script <FILE>] [--tui]
+                    [--fps-cap <N>] [--host <ADDR> | --join <ADDR>]
+                    [--record <FILE> | --replay <FILE>] [--lang <LANG>]
+                    [--palette <NAME>] [--high-contrast] [--font-scale <N>]
+       game-of-life bench [OPTIONS]
+       game-of-life search [OPTIONS]
+       game-of-life timelapse [OPTIONS]
+
+Without a subcommand the game opens in a window.
+
+options:
+    --seed <N>           Seed of the random soups [default: based on the time]
+    -c, --command <CMD>  Command to run as if typed into the : command line once
+                         the window opens, e.g. \"load glider\"
+    --script <FILE>      Rhai script to run on the grid once it opens, F5 runs it
+                         again (needs the scripting feature)
+    --tui                Play in the terminal instead of a window (needs the tui
+                         feature)
+    --fps-cap <N>        Turn vsync off and draw at most N frames a second, 0 for
+                         no limit [default: vsync]
+    --host <ADDR>        Share the board with the players joining this address,
+                         e.g. 0.0.0.0:7777
+    --join <ADDR>        Play on the board of the host at this address
+    --record <FILE>      Record every input of the session into the file
+    --replay <FILE>      Play a recorded session back, replacing --seed and
+                         --command with the ones it was recorded with
+    --lang <LANG>        Language of the messages, en or de [default: from
+                         LC_ALL, LC_MESSAGES or LANG]
+    --palette <NAME>     Colors of the cells, classic, deuteranopia or protanopia
+                         [default: classic]
+    --high-contrast      Draw thick lines between the cells and larger status
+                         text
+    --font-scale <N>     Size of the status text, 0.5 to 2 [default: 1]
+
+bench options:
+    --rows <N>           Rows of the universe [default: 1024]
+    --cols <N>           Columns of the universe [default: 1024]
+    --generations <N>    Generations to run [default: 1000]
+    --engine <ENGINE>    naive, simd, bitpacked, hashlife or gpu (needs the gpu
+                         feature) [default: naive]
+    --rule <RULE>        Rule to run, only naive runs the ones which aren't
+                         Life-like [default: B3/S23]
+    --density <P>        Probability of a cell starting alive [default: 0.5]
+    --seed <N>           Seed of the random soup [default: 24301]
+    --json               Print the results as JSON
+
+search options:
+    --soups <N>          Number of soups to run [default: 1000]
+    --soup-size <N>      Width and height of every soup [default: 16]
+    --universe <N>       Width and height of the universe around it [default: 96]
+    --generations <N>    Generations to run every soup for [default: 1000]
+    --min-lifespan <N>   Soups living at least this long are reported [default: 500]
+    --rule <RULE>        Rule to run, any but the elementary ones [default: B3/S23]
+    --density <P>        Probability of a cell starting alive [default: 0.5]
+    --seed <N>           Seed of the first soup [default: based on the time]
+    --output <FILE>      Save the reported soups to this file
+
+timelapse options:
+    --pattern <PATTERN>  Built-in pattern or pattern file placed in the middle
+                         [default: a random soup]
+    --rows <N>           Rows of the grid [default: 128]
+    --cols <N>           Columns of the grid [default: 128]
+    --generations <N>    Generations to run [default: 100]
+    --every <K>          Only write every K-th generation [default: 1]
+    --scale <N>          Width and height of a cell in pixels, at most 64
+                         [default: 4]
+    --output <DIR>       Directory the numbered frames are written to
+                         [default: frames]
+    --rule <RULE>        Rule to run [default: the pattern's or B3/S23]
+    --topology <NAME>    plane, torus, klein, cross or mirror [default: plane]
+    --density <P>        Probability of a cell of the soup starting alive
+                         [default: 0.5]
+    --noise <P>          Probability of births and survivals failing [default: 0]
+    --seed <N>           Seed of the soup and the noise [default: based on the
+                         time]";
+
+pub enum Command {
+    Gui(GuiOptions),
+    Help,
+    Bench(BenchOptions),
+    Search(SearchOptions),
+    Timelapse(TimelapseOptions),
+}
+
+/// Options of the game itself
+#[derive(Default)]
+pub struct GuiOptions {
+    pub seed: Option<u64>,
+    pub commands: Vec<ConsoleCommand>,
+
+    /// The commands as they were given, for recordings
+    pub command_lines: Vec<String>,
+
+    /// Frame limit replacing vsync, `0` draws as fast as possible
+    pub fps_cap: Option<u32>,
+
+    /// Shared board to host or join
+    pub session: Option<SessionOption>,
+
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+
+    /// Language of the messages, picked from the environment without one
+    pub lang: Option<Locale>,
+
+    #[cfg(feature = "scripting")]
+    pub script: Option<PathBuf>,
+
+    #[cfg(feature = "tui")]
+    pub tui: bool,
+}
+
+impl GuiOptions {
+    /// Runs the command once the game starts. The appearance options are
+    /// commands as well, so replays record them and the terminal gets the
+    /// palette too
+    fn push_command(&mut self, line: String) -> Result<(), String> {
+        self.commands.push(command::parse(&line)?);
+        self.command_lines.push(line);
+        Ok(())
+    }
+}
+
+pub enum SessionOption {
+    Host(String),
+    Join(String),
+}
+
+/// Options of the headless `bench` subcommand
+pub struct BenchOptions {
+    pub rows: usize,
+    pub cols: usize,
+    pub generations: u64,
+    pub engine: EngineKind,
+
+    /// Engines other than the naive one refuse the automata they can't run
+    pub rule: Automaton,
+    pub density: f64,
+    pub seed: u64,
+    pub json: bool,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            rows: 1024,
+            cols: 1024,
+            generations: 1000,
+            engine: EngineKind::Naive,
+            rule: Automaton::Life(Rule::CONWAY),
+            density: 0.5,
+            seed: 0x5eed,
+            json: false,
+        }
+    }
+}
+
+/// Options of the headless `search` subcommand
+pub struct SearchOptions {
+    pub soups: u64,
+    pub soup_size: usize,
+    pub universe_size: usize,
+    pub generations: u64,
+    pub min_lifespan: u64,
+
+    /// Any two dimensional automaton, the objects are only named in some
+    pub rule: Automaton,
+    pub density: f64,
+    pub seed: u64,
+    pub output: Option<String>,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            soups: 1000,
+            soup_size: 16,
+            universe_size: 96,
+            generations: 1000,
+            min_lifespan: 500,
+            rule: Automaton::Life(Rule::CONWAY),
+            density: 0.5,
+            seed: rng::seed_from_time(),
+            output: None,
+        }
+    }
+}
+
+/// Options of the headless `timelapse` subcommand
+pub struct TimelapseOptions {
+    pub pattern: Option<String>,
+    pub rows: usize,
+    pub cols: usize,
+    pub generations: u64,
+    pub every: u64,
+    pub scale: u32,
+    pub output: PathBuf,
+
+    /// `None` runs the rule of the pattern
+    pub rule: Option<Automaton>,
+    pub topology: Topology,
+    pub density: f64,
+    pub noise: f64,
+    pub seed: u64,
+}
+
+impl Default for TimelapseOptions {
+    fn default() -> Self {
+        Self {
+            pattern: None,
+            rows: 128,
+            cols: 128,
+            generations: 100,
+            every: 1,
+            scale: 4,
+            output: PathBuf::from("frames"),
+            rule: None,
+            topology: Topology::Plane,
+            density: 0.5,
+            noise: 0.0,
+            seed: rng::seed_from_time(),
+        }
+    }
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("-h" | "--help" | "help") => Ok(Command::Help),
+        Some("bench") => parse_bench(args.skip(1)).map(Command::Bench),
+        Some("search") => parse_search(args.skip(1)).map(Command::Search),
+        Some("timelapse") => parse_timelapse(args.skip(1)).map(Command::Timelapse),
+        _ => parse_gui(args).map(Command::Gui),
+    }
+}
+
+fn parse_gui(mut args: impl Iterator<Item = String>) -> Result<GuiOptions, String> {
+    let mut options = GuiOptions::default();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => options.seed = Some(parse_value(&arg, &next_value(&mut args, &arg)?)?),
+            "-c" | "--command" => {
+                let line = next_value(&mut args, &arg)?;
+                options.push_command(line)?;
+            }
+            "--palette" => {
+                options.push_command(format!("palette {}", next_value(&mut args, &arg)?))?
+            }
+            "--high-contrast" => options.push_command(String::from("contrast on"))?,
+            "--font-scale" => {
+                options.push_command(format!("font {}", next_value(&mut args, &arg)?))?
+            }
+            "--fps-cap" => {
+                options.fps_cap = Some(parse_value(&arg, &next_value(&mut args, &arg)?)?)
+            }
+            "--host" | "--join" if options.session.is_some() => {
+                return Err(String::from("Only one of --host and --join can be given"))
+            }
+            "--host" => options.session = Some(SessionOption::Host(next_value(&mut args, &arg)?)),
+            "--join" => options.session = Some(SessionOption::Join(next_value(&mut args, &arg)?)),
+            "--record" | "--replay" if options.record.is_some() || options.replay.is_some() => {
+                return Err(String::from(
+                    "Only one of --record and --replay can be given",
+                ))
+            }
+            "--record" => options.record = Some(next_value(&mut args, &arg)?.into()),
+            "--replay" => options.replay = Some(next_value(&mut args, &arg)?.into()),
+            "--lang" => options.lang = Some(next_value(&mut args, &arg)?.parse()?),
+            #[cfg(feature = "scripting")]
+            "--script" => options.script = Some(next_value(&mut args, &arg)?.into()),
+            #[cfg(not(feature = "scripting"))]
+            "--script" => {
+                return Err(String::from(
+                    "Built without scripting, rebuild with `--features scripting`",
+                ))
+            }
+            #[cfg(feature = "tui")]
+            "--tui" => options.tui = true,
+            #[cfg(not(feature = "tui"))]
+            "--tui" => {
+                return Err(String::from(
+                    "Built without the terminal frontend, rebuild with `--features tui`",
+                ))
+            }
+            _ => return Err(format!("Unknown argument '{arg}'")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<BenchOptions, String> {
+    let mut options = BenchOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || next_value(&mut args, &arg);
+
+        match arg.as_str() {
+            "--rows" => options.rows = parse_value(&arg, &value()?)?,
+            "--cols" => options.cols = parse_value(&arg, &value()?)?,
+            "--generations" => options.generations = parse_value(&arg, &value()?)?,
+            "--engine" => options.engine = value()?.parse()?,
+            "--rule" => options.rule = value()?.parse()?,
+            "--density" => options.density = parse_value(&arg, &value()?)?,
+            "--seed" => options.seed = parse_value(&arg, &value()?)?,
+            "--json" => options.json = true,
+            _ => return Err(format!("Unknown argument '{arg}'")),
+        }
+    }
+
+    if options.rows == 0 || options.cols == 0 {
+        return Err(String::from("The universe must have at least one cell"));
+    }
+
+    if !(0.0..=1.0).contains(&options.density) {
+        return Err(String::from("The density must be between 0 and 1"));
+    }
+
+    Ok(options)
+}
+
+fn parse_search(mut args: impl Iterator<Item = String>) -> Result<SearchOptions, String> {
+    let mut options = SearchOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || next_value(&mut args, &arg);
+
+        match arg.as_str() {
+            "--soups" => options.soups = parse_value(&arg, &value()?)?,
+            "--soup-size" => options.soup_size = parse_value(&arg, &value()?)?,
+            "--universe" => options.universe_size = parse_value(&arg, &value()?)?,
+            "--generations" => options.generations = parse_value(&arg, &value()?)?,
+            "--min-lifespan" => options.min_lifespan = parse_value(&arg, &value()?)?,
+            "--rule" => options.rule = value()?.parse()?,
+            "--density" => options.density = parse_value(&arg, &value()?)?,
+            "--seed" => options.seed = parse_value(&arg, &value()?)?,
+            "--output" => options.output = Some(value()?),
+            _ => return Err(format!("Unknown argument '{arg}'")),
+        }
+    }
+
+    if options.soups == 0 || options.soup_size == 0 {
+        return Err(String::from(
+            "There must be at least one soup with one cell",
+        ));
+    }
+
+    if matches!(options.rule, Automaton::Elementary(_)) {
+        return Err(String::from(
+            "Soups only grow in two dimensional automata, not elementary ones",
+        ));
+    }
+
+    if options.universe_size < options.soup_size + 8 {
+        return Err(String::from(
+            "The universe must be at least 8 cells larger than the soups",
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&options.density) {
+        return Err(String::from("The density must be between 0 and 1"));
+    }
+
+    Ok(options)
+}
+
+fn parse_timelapse(mut args: impl Iterator<Item = String>) -> Result<TimelapseOptions, String> {
+    let mut options = TimelapseOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || next_value(&mut args, &arg);
+
+        match arg.as_str() {
+            "--pattern" => options.pattern = Some(value()?),
+            "--rows" => options.rows = parse_value(&arg, &value()?)?,
+            "--cols" => options.cols = parse_value(&arg, &value()?)?,
+            "--generations" => options.generations = parse_value(&arg, &value()?)?,
+            "--every" => options.every = parse_value(&arg, &value()?)?,
+            "--scale" => options.scale = parse_value(&arg, &value()?)?,
+            "--output" => options.output = value()?.into(),
+            "--rule" => options.rule = Some(value()?.parse()?),
+            "--topology" => options.topology = value()?.parse()?,
+            "--density" => options.density = parse_value(&arg, &value()?)?,
+            "--noise" => options.noise = parse_value(&arg, &value()?)?,
+            "--seed" => options.seed = parse_value(&arg, &value()?)?,
+            _ => return Err(format!("Unknown argument '{arg}'")),
+        }
+    }
+
+    if options.rows == 0 || options.cols == 0 {
+        return Err(String::from("The grid must have at least one cell"));
+    }
+
+    if options.every == 0 || options.scale == 0 {
+        return Err(String::from("--every and --scale must be at least 1"));
+    }
+
+    if options.scale > MAX_SCALE {
+        return Err(format!("--scale must be at most {MAX_SCALE}"));
+    }
+
+    let fits = |cells: usize| {
+        cells
+            .checked_mul(options.scale as usize)
+            .is_some_and(|pixels| pixels <= MAX_FRAME_SIZE)
+    };
+    if !fits(options.rows) || !fits(options.cols) {
+        return Err(format!(
+            "The frames can be at most {MAX_FRAME_SIZE} pixels wide and high, \
+             use fewer rows and columns or a smaller --scale"
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&options.density) || !(0.0..=1.0).contains(&options.noise) {
+        return Err(String::from(
+            "The density and the noise must be between 0 and 1",
+        ));
+    }
+
+    Ok(options)
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, arg: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("Missing value for '{arg}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &str) -> Result<Command, String> {
+        parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn reads_the_subcommands() {
+        assert!(matches!(parse_args("--help"), Ok(Command::Help)));
+        assert!(matches!(
+            parse_args("--seed 7 -c clear --join 127.0.0.1:7777"),
+            Ok(Command::Gui(GuiOptions {
+                seed: Some(7),
+                session: Some(SessionOption::Join(_)),
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_args("bench --rows 16 --cols 64 --engine hashlife --json"),
+            Ok(Command::Bench(BenchOptions {
+                rows: 16,
+                cols: 64,
+                engine: EngineKind::HashLife,
+                json: true,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_args("bench --rule wireworld"),
+            Ok(Command::Bench(BenchOptions {
+                rule: Automaton::Wireworld,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            parse_args("timelapse --rows 64 --cols 32 --scale 8 --topology torus"),
+            Ok(Command::Timelapse(TimelapseOptions {
+                rows: 64,
+                cols: 32,
+                scale: 8,
+                topology: Topology::Torus,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn refuses_options_out_of_range() {
+        for args in [
+            "--host 0.0.0.0:7777 --join 127.0.0.1:7777",
+            "--record a --replay b",
+            "-c jump",
+            "--seed",
+            "--unknown",
+            "bench --rows 0",
+            "bench --density 2",
+            "search --soups 0",
+            "search --soup-size 100 --universe 100",
+            "search --rule W30",
+            "timelapse --every 0",
+            "timelapse --scale 65",
+            "timelapse --cols 4097 --scale 4",
+            "timelapse --rows 18446744073709551615 --scale 2",
+            "timelapse --noise -1",
+        ] {
+            assert!(parse_args(args).is_err(), "{args}");
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Splits the grid into separate objects
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use grid::Grid;
+
+use crate::CellState;
+
+/// Cells which aren't dead and are at most [`Cluster::GAP`] cells apart
+pub struct Cluster {
+    /// Row, column and state of every cell
+    pub cells: Vec<(usize, usize, CellState)>,
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+
+impl Cluster {
+    /// Largest distance between two cells of the same cluster, `2` keeps
+    /// the small spaceships which have gaps in some phases together
+    pub const GAP: usize = 2;
+
+    /// Hash of the cells relative to the top left corner, the same object
+    /// anywhere in the grid hashes the same
+    pub fn shape_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.bottom - self.top, self.right - self.left).hash(&mut hasher);
+
+        for (row, col, cell) in &self.cells {
+            (row - self.top, col - self.left, cell).hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+}
+
+pub fn clusters(grid: &Grid<CellState>) -> Vec<Cluster> {
+    let gap = Cluster::GAP as i32;
+    let mut visited = Grid::init(grid.rows(), grid.cols(), false);
+    let mut clusters = Vec::new();
+
+    for ((row, col), cell) in grid.indexed_iter() {
+        if *cell == CellState::DEAD || visited[row][col] {
+            continue;
+        }
+
+        visited[row][col] = true;
+        let mut stack = vec![(row, col)];
+        let mut cells = Vec::new();
+
+        while let Some((row, col)) = stack.pop() {
+            cells.push((row, col, grid[row][col]));
+
+            for dr in -gap..=gap {
+                for dc in -gap..=gap {
+                    let (r, c) = (row as i32 + dr, col as i32 + dc);
+                    if r < 0 || c < 0 {
+                        continue;
+                    }
+
+                    let (r, c) = (r as usize, c as usize);
+                    match grid.get(r, c) {
+                        Some(cell) if *cell != CellState::DEAD && !visited[r][c] => {
+                            visited[r][c] = true;
+                            stack.push((r, c));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // sorted so the hash doesn't depend on the order cells were found in
+        cells.sort_unstable_by_key(|(row, col, _)| (*row, *col));
+
+        clusters.push(Cluster {
+            top: cells.iter().map(|cell| cell.0).min().unwrap(),
+            bottom: cells.iter().map(|cell| cell.0).max().unwrap(),
+            left: cells.iter().map(|cell| cell.1).min().unwrap(),
+            right: cells.iter().map(|cell| cell.1).max().unwrap(),
+            cells,
+        });
+    }
+
+    clusters
+}
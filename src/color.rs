@@ -0,0 +1,28 @@
+//! Colors of the cells, SFML's where SFML is available
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use sfml::graphics::Color;
+
+/// Stand-in for SFML's color on the web, which SFML doesn't build for
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Color {
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    pub const WHITE: Self = Self::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+}
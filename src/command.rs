@@ -0,0 +1,260 @@
+//! Commands typed into the `:` command line, also accepted on the command
+//! line with `--command`
+
+use std::str::FromStr;
+
+use crate::automaton::Automaton;
+use crate::locale::{fill, text, tr};
+use crate::palette::Palette;
+use crate::topology::Topology;
+
+/// Largest grid `resize` accepts on either side
+pub const MAX_GRID_SIZE: usize = 1024;
+
+/// Longest time between steps `speed` accepts, in milliseconds
+pub const MAX_SPEED: u32 = 60_000;
+
+/// Most generations a single `step` advances
+pub const MAX_STEPS: u64 = 100_000;
+
+pub const HELP: &str = "\
+load <PATTERN>, fetch <URL>, rule <RULE>, speed <MS>, resize <ROWS> <COLS>, \
+seed <N> [density <P>], density <P>, step <N>, save <FILE>, restore <FILE>, \
+name [TEXT], author [TEXT], comment [TEXT], export <FILE>, predecessor, \
+topology <plane|torus|klein|cross|mirror>, noise <P>, \
+palette <classic|deuteranopia|protanopia>, contrast <on|off>, font <SCALE>, clear";
+
+pub enum Command {
+    /// A built-in pattern or an RLE file, placed in the middle of the grid
+    Load(String),
+
+    /// Same as `Load` but downloads the pattern
+    #[cfg(feature = "net")]
+    Fetch(String),
+    Rule(Automaton),
+
+    /// Milliseconds between steps
+    Speed(u32),
+    Resize(usize, usize),
+
+    /// Restarts the random soups from the seed, filling the grid with the
+    /// first one
+    Seed {
+        seed: u64,
+        density: Option<f64>,
+    },
+
+    /// Probability of a cell starting alive in the random soups
+    Density(f64),
+    Step(u64),
+
+    /// Snapshots the whole session into a JSON or RON file
+    #[cfg(feature = "serde")]
+    Save(String),
+    #[cfg(feature = "serde")]
+    Restore(String),
+
+    /// Name, author or another comment line of the pattern on the grid, an
+    /// empty name or author removes it and an empty comment all comments
+    Name(String),
+    Author(String),
+    Comment(String),
+
+    /// Writes the grid with its name and comments into an RLE file
+    Export(String),
+
+    /// Replaces the grid with one stepping into it, if there is any
+    Predecessor,
+
+    /// How the edges of the grid are glued together
+    Topology(Topology),
+
+    /// Probability of every birth and survival failing
+    Noise(f64),
+
+    /// Colors of the cells, high contrast and the size of the status text
+    Palette(Palette),
+    Contrast(bool),
+    FontScale(f32),
+    Clear,
+    Help,
+}
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or_else(|| text("No command"))?;
+    let mut arg = |what: &'static str| {
+        words
+            .next()
+            .ok_or_else(|| fill("'{}' needs {}, try 'help'", &[&name, &tr(what)]))
+    };
+
+    // everything after the name of the command
+    let rest = || {
+        line.trim()
+            .split_once(char::is_whitespace)
+            .map_or("", |(_, text)| text.trim())
+            .to_string()
+    };
+
+    let command = match name {
+        "name" => return Ok(Command::Name(rest())),
+        "author" => return Ok(Command::Author(rest())),
+        "comment" => return Ok(Command::Comment(rest())),
+        "load" | "l" => Command::Load(arg("a pattern")?.to_string()),
+        #[cfg(feature = "net")]
+        "fetch" => Command::Fetch(arg("a URL")?.to_string()),
+        #[cfg(not(feature = "net"))]
+        "fetch" => {
+            return Err(text(
+                "Built without networking, rebuild with `--features net`",
+            ))
+        }
+        "rule" => Command::Rule(arg("a rule")?.parse()?),
+        "speed" => match parse_value(name, arg("the milliseconds between steps")?)? {
+            ms if ms <= MAX_SPEED => Command::Speed(ms),
+            _ => {
+                return Err(fill(
+                    "The time between steps must be at most {} milliseconds",
+                    &[&MAX_SPEED],
+                ))
+            }
+        },
+        "resize" => {
+            let rows = parse_value(name, arg("the rows")?)?;
+            let cols = parse_value(name, arg("the columns")?)?;
+
+            if !(1..=MAX_GRID_SIZE).contains(&rows) || !(1..=MAX_GRID_SIZE).contains(&cols) {
+                return Err(fill(
+                    "The grid must be between 1 and {} cells on each side",
+                    &[&MAX_GRID_SIZE],
+                ));
+            }
+            Command::Resize(rows, cols)
+        }
+        "seed" => {
+            let seed = parse_value(name, arg("a seed")?)?;
+            let density = match words.next() {
+                Some("density") => Some(density(words.next())?),
+                Some(word) => return Err(fill("Unexpected '{}' after the seed", &[&word])),
+                None => None,
+            };
+
+            Command::Seed { seed, density }
+        }
+        "density" => Command::Density(density(words.next())?),
+        "step" => match words
+            .next()
+            .map_or(Ok(1), |count| parse_value(name, count))?
+        {
+            count if count <= MAX_STEPS => Command::Step(count),
+            _ => {
+                return Err(fill(
+                    "At most {} generations can be stepped at once",
+                    &[&MAX_STEPS],
+                ))
+            }
+        },
+        #[cfg(feature = "serde")]
+        "save" => Command::Save(arg("a file")?.to_string()),
+        #[cfg(feature = "serde")]
+        "restore" => Command::Restore(arg("a file")?.to_string()),
+        #[cfg(not(feature = "serde"))]
+        "save" | "restore" => {
+            return Err(text(
+                "Built without snapshots, rebuild with `--features serde`",
+            ))
+        }
+        "export" => Command::Export(arg("a file")?.to_string()),
+        "predecessor" => Command::Predecessor,
+        "topology" => Command::Topology(arg("a topology")?.parse()?),
+        "noise" => match parse_value(name, arg("a probability")?)? {
+            level if (0.0..=1.0).contains(&level) => Command::Noise(level),
+            _ => return Err(text("The noise must be between 0 and 1")),
+        },
+        "palette" => Command::Palette(arg("a palette")?.parse()?),
+        "contrast" => Command::Contrast(match arg("on or off")? {
+            "on" => true,
+            "off" => false,
+            word => return Err(fill("Expected on or off, not '{}'", &[&word])),
+        }),
+        "font" => match parse_value(name, arg("a scale")?)? {
+            scale if (0.5..=2.0).contains(&scale) => Command::FontScale(scale),
+            _ => return Err(text("The font scale must be between 0.5 and 2")),
+        },
+        "clear" => Command::Clear,
+        "help" | "h" => Command::Help,
+        _ => return Err(fill("Unknown command '{}', try 'help'", &[&name])),
+    };
+
+    match words.next() {
+        Some(word) => Err(fill("Unexpected '{}' after '{}'", &[&word, &name])),
+        None => Ok(command),
+    }
+}
+
+fn density(value: Option<&str>) -> Result<f64, String> {
+    let value = value.ok_or_else(|| text("'density' needs a probability"))?;
+
+    match parse_value("density", value)? {
+        density if (0.0..=1.0).contains(&density) => Ok(density),
+        _ => Err(text("The density must be between 0 and 1")),
+    }
+}
+
+pub fn parse_value<T: FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| fill("Invalid value '{}' for '{}'", &[&value, &arg]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_commands_and_their_arguments() {
+        assert!(matches!(parse("resize 16 64"), Ok(Command::Resize(16, 64))));
+        assert!(matches!(parse("step"), Ok(Command::Step(1))));
+        assert!(matches!(parse("  step 10 "), Ok(Command::Step(10))));
+        assert!(matches!(
+            parse("seed 7 density 0.25"),
+            Ok(Command::Seed {
+                seed: 7,
+                density: Some(density),
+            }) if density == 0.25
+        ));
+        assert!(
+            matches!(parse("name  The  glider "), Ok(Command::Name(name)) if name == "The  glider")
+        );
+        assert!(matches!(parse("comment"), Ok(Command::Comment(comment)) if comment.is_empty()));
+        assert!(matches!(parse("contrast on"), Ok(Command::Contrast(true))));
+        assert!(matches!(
+            parse("topology torus"),
+            Ok(Command::Topology(Topology::Torus))
+        ));
+    }
+
+    #[test]
+    fn refuses_values_out_of_range() {
+        for line in [
+            "",
+            "resize 0 16",
+            "resize 16 1025",
+            "resize 16",
+            "speed 60001",
+            "step 100001",
+            "step -1",
+            "density 1.5",
+            "seed 7 density",
+            "seed 7 noise 0.5",
+            "noise -0.1",
+            "font 3",
+            "contrast maybe",
+            "clear now",
+            "jump",
+        ] {
+            assert!(parse(line).is_err(), "{line}");
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Stores 64 cells per word and counts the neighbours of a whole word at once
+
+use std::mem;
+
+use grid::Grid;
+
+use super::Engine;
+use crate::automaton::Automaton;
+use crate::rule::{Neighbourhood, Rule};
+use crate::CellState;
+
+/// Two state Moore radius 1 rules only, cells beyond the edges are dead
+pub struct BitPacked {
+    rows: usize,
+    cols: usize,
+    words_per_row: usize,
+    cells: Vec<u64>,
+    next: Vec<u64>,
+    rule: Rule,
+}
+
+impl BitPacked {
+    pub fn new(grid: &Grid<CellState>, automaton: Automaton) -> Result<Self, String> {
+        let rule = match automaton {
+            Automaton::Life(
+                rule @ Rule {
+                    states: 2,
+                    neighbourhood: Neighbourhood::Moore(1),
+                    ..
+                },
+            ) => rule,
+            _ => return Err(format!("The bitpacked engine can't run {automaton}")),
+        };
+
+        let words_per_row = grid.cols().div_ceil(64);
+        let mut cells = vec![0; grid.rows() * words_per_row];
+
+        for ((row, col), cell) in grid.indexed_iter() {
+            if cell.is_alive() {
+                cells[row * words_per_row + col / 64] |= 1 << (col % 64);
+            }
+        }
+
+        Ok(Self {
+            rows: grid.rows(),
+            cols: grid.cols(),
+            words_per_row,
+            next: vec![0; cells.len()],
+            cells,
+            rule,
+        })
+    }
+
+    fn word(&self, row: isize, word: isize) -> u64 {
+        if row < 0 || row as usize >= self.rows || word < 0 || word as usize >= self.words_per_row {
+            return 0;
+        }
+
+        self.cells[row as usize * self.words_per_row + word as usize]
+    }
+}
+
+/// Adds one to the bit-sliced counters wherever `bits` is set, `counts[i]`
+/// holds bit `i` of the count of every column
+fn add(counts: &mut [u64; 4], mut bits: u64) {
+    for count in counts {
+        let carry = *count & bits;
+        *count ^= bits;
+        bits = carry;
+    }
+}
+
+impl Engine for BitPacked {
+    fn step(&mut self) {
+        // bits past the last column of a row must stay dead
+        let last_mask = match self.cols % 64 {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        };
+
+        for row in 0..self.rows as isize {
+            for word in 0..self.words_per_row as isize {
+                let mut counts = [0; 4];
+
+                for dr in -1..=1 {
+                    let center = self.word(row + dr, word);
+
+                    // bit `i` is column `word * 64 + i`, so the west neighbour
+                    // comes from the next lower bit
+                    add(
+                        &mut counts,
+                        center << 1 | self.word(row + dr, word - 1) >> 63,
+                    );
+                    add(
+                        &mut counts,
+                        center >> 1 | self.word(row + dr, word + 1) << 63,
+                    );
+
+                    if dr != 0 {
+                        add(&mut counts, center);
+                    }
+                }
+
+                let alive = self.word(row, word);
+                let mut next = 0;
+
+                for n in 0..=8 {
+                    let bit = |i: usize| {
+                        if n >> i & 1 == 1 {
+                            counts[i]
+                        } else {
+                            !counts[i]
+                        }
+                    };
+                    let equal = bit(0) & bit(1) & bit(2) & bit(3);
+
+                    if self.rule.birth & 1 << n != 0 {
+                        next |= !alive & equal;
+                    }
+                    if self.rule.survival & 1 << n != 0 {
+                        next |= alive & equal;
+                    }
+                }
+
+                if word as usize == self.words_per_row - 1 {
+                    next &= last_mask;
+                }
+
+                self.next[row as usize * self.words_per_row + word as usize] = next;
+            }
+        }
+
+        mem::swap(&mut self.cells, &mut self.next);
+    }
+
+    fn population(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn grid(&self) -> Grid<CellState> {
+        let mut grid = Grid::new(self.rows, self.cols);
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                if self.cells[row * self.words_per_row + col / 64] & 1 << (col % 64) != 0 {
+                    grid[row][col] = CellState::ALIVE;
+                }
+            }
+        }
+
+        grid
+    }
+
+    fn memory_usage(&self) -> usize {
+        (self.cells.len() + self.next.len()) * mem::size_of::<u64>()
+    }
+}
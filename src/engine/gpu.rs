@@ -0,0 +1,233 @@
+//! Steps the universe in a compute shader, one invocation per cell
+//!
+//! The cells live in two storage buffers on the GPU which swap roles every
+//! generation, only the population is copied back after a step and the
+//! cells only when they are asked for. That makes it an engine for `bench`
+//! only, the window draws with SFML, which can't read wgpu's buffers, so it
+//! keeps stepping the grid on the CPU.
+
+use grid::Grid;
+use wgpu::util::DeviceExt;
+
+use super::Engine;
+use crate::automaton::Automaton;
+use crate::rule::{Neighbourhood, Rule};
+use crate::CellState;
+
+const SHADER: &str = include_str!("gpu.wgsl");
+
+/// Cells along each side of a workgroup, has to match the shader
+const WORKGROUP_SIZE: u32 = 8;
+
+/// Two state Moore and von Neumann rules only, cells beyond the edges are
+/// dead
+pub struct Gpu {
+    rows: usize,
+    cols: usize,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+
+    /// One `u32` per cell, the current generation is in `cells[current]`
+    cells: [wgpu::Buffer; 2],
+
+    /// The first one steps from `cells[0]` into `cells[1]`, the second one
+    /// back again
+    bind_groups: [wgpu::BindGroup; 2],
+
+    /// Index into `bind_groups` of the next step
+    current: usize,
+
+    /// Alive cells of the current generation, counted by the shader
+    population: wgpu::Buffer,
+}
+
+impl Gpu {
+    pub fn new(grid: &Grid<CellState>, automaton: Automaton) -> Result<Self, String> {
+        let rule = match automaton {
+            Automaton::Life(
+                rule @ Rule {
+                    states: 2,
+                    neighbourhood: Neighbourhood::Moore(_) | Neighbourhood::VonNeumann(_),
+                    ..
+                },
+            ) => rule,
+            _ => return Err(format!("The gpu engine can't run {automaton}")),
+        };
+
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        }))
+        .ok_or_else(|| String::from("No GPU found"))?;
+
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+                .map_err(|err| format!("Couldn't open the GPU: {err}"))?;
+
+        let (rows, cols) = grid.size();
+        let radius = rule.neighbourhood.radius() as u32;
+        let von_neumann = matches!(rule.neighbourhood, Neighbourhood::VonNeumann(_)) as u32;
+        let params = [
+            rows as u32,
+            cols as u32,
+            rule.birth,
+            rule.survival,
+            radius,
+            von_neumann,
+            0,
+            0,
+        ];
+
+        let buffer = |label, contents: &[u8], usage| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage,
+            })
+        };
+
+        let cells = grid
+            .iter()
+            .flat_map(|cell| (cell.is_alive() as u32).to_le_bytes())
+            .collect::<Vec<_>>();
+        let population = grid.iter().filter(|cell| cell.is_alive()).count() as u32;
+
+        let params = buffer(
+            "params",
+            &params.map(u32::to_le_bytes).concat(),
+            wgpu::BufferUsages::UNIFORM,
+        );
+        let cells = [0, 1].map(|_| {
+            buffer(
+                "cells",
+                &cells,
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            )
+        });
+        let population = buffer(
+            "population",
+            &population.to_le_bytes(),
+            wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::COPY_DST,
+        );
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("step"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("step"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        let layout = pipeline.get_bind_group_layout(0);
+        let bind_groups = [(0, 1), (1, 0)].map(|(from, to)| {
+            let entries = [&params, &cells[from], &cells[to], &population]
+                .into_iter()
+                .enumerate()
+                .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                    binding: binding as u32,
+                    resource: buffer.as_entire_binding(),
+                })
+                .collect::<Vec<_>>();
+
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("step"),
+                layout: &layout,
+                entries: &entries,
+            })
+        });
+
+        Ok(Self {
+            rows,
+            cols,
+            device,
+            queue,
+            pipeline,
+            cells,
+            bind_groups,
+            current: 0,
+            population,
+        })
+    }
+
+    /// Copies the first `size` bytes of the buffer back from the GPU
+    fn read(&self, buffer: &wgpu::Buffer, size: u64) -> Vec<u8> {
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(buffer, 0, &readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let bytes = slice.get_mapped_range().to_vec();
+        readback.unmap();
+        bytes
+    }
+}
+
+impl Engine for Gpu {
+    fn step(&mut self) {
+        self.queue
+            .write_buffer(&self.population, 0, &0u32.to_le_bytes());
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &self.bind_groups[self.current], &[]);
+            pass.dispatch_workgroups(
+                (self.cols as u32).div_ceil(WORKGROUP_SIZE),
+                (self.rows as u32).div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+        self.queue.submit([encoder.finish()]);
+        self.current ^= 1;
+
+        // wait for the step, otherwise the benchmark only measures how fast
+        // the steps are queued
+        self.device.poll(wgpu::Maintain::Wait);
+    }
+
+    fn population(&self) -> usize {
+        let bytes = self.read(&self.population, 4);
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+    }
+
+    fn grid(&self) -> Grid<CellState> {
+        let bytes = self.read(
+            &self.cells[self.current],
+            (self.rows * self.cols * 4) as u64,
+        );
+        let cells = bytes
+            .chunks_exact(4)
+            .map(|cell| match cell {
+                [0, 0, 0, 0] => CellState::DEAD,
+                _ => CellState::ALIVE,
+            })
+            .collect();
+
+        Grid::from_vec(cells, self.cols)
+    }
+
+    fn memory_usage(&self) -> usize {
+        // two buffers of one u32 per cell, they live in video memory
+        2 * self.rows * self.cols * 4
+    }
+}
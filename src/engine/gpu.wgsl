@@ -0,0 +1,51 @@
+// One invocation per cell, reading the current generation from `cells` and
+// writing the next one into `next`. Cells beyond the edges are dead.
+
+struct Params {
+    rows: u32,
+    cols: u32,
+    birth: u32,
+    survival: u32,
+    radius: u32,
+    von_neumann: u32,
+    padding: vec2<u32>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> cells: array<u32>;
+@group(0) @binding(2) var<storage, read_write> next: array<u32>;
+@group(0) @binding(3) var<storage, read_write> population: atomic<u32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.y >= params.rows || id.x >= params.cols {
+        return;
+    }
+
+    let row = i32(id.y);
+    let col = i32(id.x);
+    let radius = i32(params.radius);
+    var neighbours = 0u;
+
+    for (var dr = -radius; dr <= radius; dr++) {
+        for (var dc = -radius; dc <= radius; dc++) {
+            let r = row + dr;
+            let c = col + dc;
+            let in_range = params.von_neumann == 0u || abs(dr) + abs(dc) <= radius;
+            let inside = r >= 0 && c >= 0 && r < i32(params.rows) && c < i32(params.cols);
+
+            if in_range && inside && (dr != 0 || dc != 0) {
+                neighbours += cells[u32(r) * params.cols + u32(c)];
+            }
+        }
+    }
+
+    let idx = id.y * params.cols + id.x;
+    let mask = select(params.birth, params.survival, cells[idx] == 1u);
+    let alive = (mask >> neighbours) & 1u;
+    next[idx] = alive;
+
+    if alive == 1u {
+        atomicAdd(&population, 1u);
+    }
+}
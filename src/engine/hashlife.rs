@@ -0,0 +1,321 @@
+//! Gosper's hashlife, the universe is a quadtree where identical subtrees are
+//! stored once and the next generation of every subtree is memoized
+//!
+//! This only ever advances a single generation per step so the speedup comes
+//! from the memoization alone, which pays off for large but repetitive
+//! patterns and hurts for chaotic soups.
+
+use std::collections::HashMap;
+use std::mem;
+
+use grid::Grid;
+
+use super::Engine;
+use crate::automaton::Automaton;
+use crate::rule::{Neighbourhood, Rule};
+use crate::CellState;
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// A square of `2^level` cells, leaves are single cells and have no children
+struct Node {
+    level: u8,
+
+    /// North west, north east, south west and south east quadrants
+    children: [NodeId; 4],
+    population: u64,
+}
+
+/// Two state Moore radius 1 rules only
+///
+/// The universe is the smallest power of two square containing the grid,
+/// cells beyond the grid are dead like on the plane and are cleared again
+/// after every step.
+pub struct HashLife {
+    nodes: Vec<Node>,
+    interned: HashMap<[NodeId; 4], NodeId>,
+    next: HashMap<NodeId, NodeId>,
+
+    /// The empty node of every level, indexed by level
+    empty: Vec<NodeId>,
+    root: NodeId,
+    rule: Rule,
+
+    /// Size of the grid in the top left corner of the universe
+    rows: usize,
+    cols: usize,
+}
+
+impl HashLife {
+    pub fn new(grid: &Grid<CellState>, automaton: Automaton) -> Result<Self, String> {
+        let rule = match automaton {
+            Automaton::Life(
+                rule @ Rule {
+                    states: 2,
+                    neighbourhood: Neighbourhood::Moore(1),
+                    ..
+                },
+            ) => rule,
+            _ => return Err(format!("The hashlife engine can't run {automaton}")),
+        };
+
+        let leaf = |population| Node {
+            level: 0,
+            children: [DEAD; 4],
+            population,
+        };
+
+        let mut hashlife = Self {
+            nodes: vec![leaf(0), leaf(1)],
+            interned: HashMap::new(),
+            next: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            rule,
+            rows: grid.rows(),
+            cols: grid.cols(),
+        };
+
+        // stepping needs at least a 4x4 universe
+        let size = grid.rows().max(grid.cols()).max(4);
+        let level = size.next_power_of_two().trailing_zeros() as u8;
+        hashlife.root = hashlife.build(grid, level, 0, 0);
+
+        Ok(hashlife)
+    }
+
+    fn build(&mut self, grid: &Grid<CellState>, level: u8, row: usize, col: usize) -> NodeId {
+        if level == 0 {
+            return match grid.get(row, col) {
+                Some(cell) if cell.is_alive() => ALIVE,
+                _ => DEAD,
+            };
+        }
+
+        // skip the recursion for quadrants entirely outside the grid
+        if row >= grid.rows() || col >= grid.cols() {
+            return self.empty(level);
+        }
+
+        let half = 1 << (level - 1);
+        let children = [
+            self.build(grid, level - 1, row, col),
+            self.build(grid, level - 1, row, col + half),
+            self.build(grid, level - 1, row + half, col),
+            self.build(grid, level - 1, row + half, col + half),
+        ];
+
+        self.join(children)
+    }
+
+    /// Kills the cells of the node outside the grid, `row` and `col` are
+    /// where its top left cell lies in the universe
+    fn clip(&mut self, node: NodeId, row: usize, col: usize) -> NodeId {
+        let level = self.nodes[node as usize].level;
+        let size = 1 << level;
+
+        if row + size <= self.rows && col + size <= self.cols {
+            return node;
+        }
+
+        if row >= self.rows || col >= self.cols || node == self.empty(level) {
+            return self.empty(level);
+        }
+
+        let half = size / 2;
+        let [nw, ne, sw, se] = self.children(node);
+        let children = [
+            self.clip(nw, row, col),
+            self.clip(ne, row, col + half),
+            self.clip(sw, row + half, col),
+            self.clip(se, row + half, col + half),
+        ];
+
+        self.join(children)
+    }
+
+    /// Sets the live cells of the node in the grid, `row` and `col` are
+    /// where its top left cell lies
+    fn fill(&self, grid: &mut Grid<CellState>, node: NodeId, row: usize, col: usize) {
+        let node = &self.nodes[node as usize];
+        if node.population == 0 {
+            return;
+        }
+
+        if node.level == 0 {
+            if let Some(cell) = grid.get_mut(row, col) {
+                *cell = CellState::ALIVE;
+            }
+            return;
+        }
+
+        let half = 1 << (node.level - 1);
+        let [nw, ne, sw, se] = node.children;
+        self.fill(grid, nw, row, col);
+        self.fill(grid, ne, row, col + half);
+        self.fill(grid, sw, row + half, col);
+        self.fill(grid, se, row + half, col + half);
+    }
+
+    fn join(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.interned.get(&children) {
+            return id;
+        }
+
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            level: self.nodes[children[0] as usize].level + 1,
+            children,
+            population: children
+                .iter()
+                .map(|&child| self.nodes[child as usize].population)
+                .sum(),
+        });
+        self.interned.insert(children, id);
+
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let below = *self.empty.last().unwrap();
+            let node = self.join([below; 4]);
+            self.empty.push(node);
+        }
+
+        self.empty[level as usize]
+    }
+
+    fn children(&self, node: NodeId) -> [NodeId; 4] {
+        self.nodes[node as usize].children
+    }
+
+    /// The centered square of half the size
+    fn center(&mut self, node: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(node);
+
+        self.join([
+            self.children(nw)[3],
+            self.children(ne)[2],
+            self.children(sw)[1],
+            self.children(se)[0],
+        ])
+    }
+
+    /// The centered square of half the size, one generation later
+    fn next(&mut self, node: NodeId) -> NodeId {
+        if let Some(&next) = self.next.get(&node) {
+            return next;
+        }
+
+        let next = if self.nodes[node as usize].level == 2 {
+            self.next_4x4(node)
+        } else {
+            let [nw, ne, sw, se] = self.children(node);
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+
+            // nine overlapping squares of half the size covering the node
+            let squares = [
+                nw,
+                self.join([nw_ne, ne_nw, nw_se, ne_sw]),
+                ne,
+                self.join([nw_sw, nw_se, sw_nw, sw_ne]),
+                self.join([nw_se, ne_sw, sw_ne, se_nw]),
+                self.join([ne_sw, ne_se, se_nw, se_ne]),
+                sw,
+                self.join([sw_ne, se_nw, sw_se, se_sw]),
+                se,
+            ];
+            let c = squares.map(|square| self.center(square));
+
+            let quadrants = [
+                self.join([c[0], c[1], c[3], c[4]]),
+                self.join([c[1], c[2], c[4], c[5]]),
+                self.join([c[3], c[4], c[6], c[7]]),
+                self.join([c[4], c[5], c[7], c[8]]),
+            ];
+
+            let next = quadrants.map(|quadrant| self.next(quadrant));
+            self.join(next)
+        };
+
+        self.next.insert(node, next);
+        next
+    }
+
+    /// Steps the inner 2x2 cells of a 4x4 node by brute force
+    fn next_4x4(&mut self, node: NodeId) -> NodeId {
+        let alive = |row: usize, col: usize| {
+            let quadrant = self.children(node)[(row / 2) * 2 + col / 2];
+            let leaf = self.children(quadrant)[(row % 2) * 2 + col % 2];
+            leaf == ALIVE
+        };
+
+        let mut cells = [DEAD; 4];
+        for (idx, (row, col)) in [(1, 1), (1, 2), (2, 1), (2, 2)].into_iter().enumerate() {
+            let mut neighbours = 0;
+            for dr in 0..3 {
+                for dc in 0..3 {
+                    if (dr, dc) != (1, 1) && alive(row + dr - 1, col + dc - 1) {
+                        neighbours += 1;
+                    }
+                }
+            }
+
+            let cell = if alive(row, col) {
+                CellState::ALIVE
+            } else {
+                CellState::DEAD
+            };
+
+            if self.rule.next_state(cell, neighbours).is_alive() {
+                cells[idx] = ALIVE;
+            }
+        }
+
+        self.join(cells)
+    }
+}
+
+impl Engine for HashLife {
+    fn step(&mut self) {
+        // surround the root with empty space so its next generation is the
+        // whole universe again
+        let level = self.nodes[self.root as usize].level;
+        let empty = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.children(self.root);
+
+        let expanded = [
+            self.join([empty, empty, empty, nw]),
+            self.join([empty, empty, ne, empty]),
+            self.join([empty, sw, empty, empty]),
+            self.join([se, empty, empty, empty]),
+        ];
+        let expanded = self.join(expanded);
+
+        let next = self.next(expanded);
+        self.root = self.clip(next, 0, 0);
+    }
+
+    fn population(&self) -> usize {
+        self.nodes[self.root as usize].population as usize
+    }
+
+    fn grid(&self) -> Grid<CellState> {
+        let mut grid = Grid::new(self.rows, self.cols);
+        self.fill(&mut grid, self.root, 0, 0);
+        grid
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.nodes.capacity() * mem::size_of::<Node>()
+            + self.interned.capacity() * mem::size_of::<([NodeId; 4], NodeId)>()
+            + self.next.capacity() * mem::size_of::<(NodeId, NodeId)>()
+    }
+}
@@ -0,0 +1,114 @@
+//! Headless stepping of the universe, independent of the window
+//!
+//! The GUI always steps with the naive engine since it supports every
+//! automaton, the other engines trade generality for speed and are used by
+//! the `bench` subcommand.
+
+mod bitpacked;
+#[cfg(feature = "gpu")]
+mod gpu;
+mod hashlife;
+pub mod naive;
+mod simd;
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::str::FromStr;
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::rng::Rng;
+use crate::CellState;
+
+pub use bitpacked::BitPacked;
+#[cfg(feature = "gpu")]
+pub use gpu::Gpu;
+pub use hashlife::HashLife;
+pub use naive::Naive;
+pub use simd::Simd;
+
+pub trait Engine {
+    /// Advances the universe by one generation
+    fn step(&mut self);
+
+    /// Number of cells which aren't dead
+    fn population(&self) -> usize;
+
+    /// The current generation, copied out of the engine's own layout
+    fn grid(&self) -> Grid<CellState>;
+
+    /// Approximate number of bytes used by the universe and any caches
+    fn memory_usage(&self) -> usize;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineKind {
+    Naive,
+    Simd,
+    BitPacked,
+    HashLife,
+    #[cfg(feature = "gpu")]
+    Gpu,
+}
+
+impl EngineKind {
+    pub fn create(
+        self,
+        grid: &Grid<CellState>,
+        automaton: Automaton,
+    ) -> Result<Box<dyn Engine>, String> {
+        Ok(match self {
+            Self::Naive => Box::new(Naive::new(grid.clone(), automaton)),
+            Self::Simd => Box::new(Simd::new(grid, automaton)?),
+            Self::BitPacked => Box::new(BitPacked::new(grid, automaton)?),
+            Self::HashLife => Box::new(HashLife::new(grid, automaton)?),
+            #[cfg(feature = "gpu")]
+            Self::Gpu => Box::new(Gpu::new(grid, automaton)?),
+        })
+    }
+}
+
+impl fmt::Display for EngineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Naive => "naive",
+            Self::Simd => "simd",
+            Self::BitPacked => "bitpacked",
+            Self::HashLife => "hashlife",
+            #[cfg(feature = "gpu")]
+            Self::Gpu => "gpu",
+        })
+    }
+}
+
+impl FromStr for EngineKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "naive" => Ok(Self::Naive),
+            "simd" => Ok(Self::Simd),
+            "bitpacked" => Ok(Self::BitPacked),
+            "hashlife" => Ok(Self::HashLife),
+            #[cfg(feature = "gpu")]
+            "gpu" => Ok(Self::Gpu),
+            #[cfg(not(feature = "gpu"))]
+            "gpu" => Err(String::from(
+                "Built without the gpu engine, rebuild with `--features gpu`",
+            )),
+            _ => Err(format!("Unknown engine '{s}'")),
+        }
+    }
+}
+
+/// A grid where every cell is alive with the given probability
+pub fn soup(rows: usize, cols: usize, density: f64, rng: &mut Rng) -> Grid<CellState> {
+    let mut grid = Grid::new(rows, cols);
+    grid.iter_mut()
+        .filter(|_| rng.chance(density))
+        .for_each(|cell| *cell = CellState::ALIVE);
+
+    grid
+}
@@ -0,0 +1,473 @@
+//! Visits every cell and every neighbour of it, supports every automaton
+
+use std::mem;
+
+use grid::Grid;
+
+use super::Engine;
+use crate::automaton::Automaton;
+use crate::topology::Topology;
+use crate::CellState;
+
+/// Neighbour offsets of the even and the odd rows
+type Offsets = [Vec<(i32, i32)>; 2];
+
+pub struct Naive {
+    pub grid: Grid<CellState>,
+    pub automaton: Automaton,
+}
+
+impl Naive {
+    pub fn new(grid: Grid<CellState>, automaton: Automaton) -> Self {
+        Self { grid, automaton }
+    }
+}
+
+impl Engine for Naive {
+    fn step(&mut self) {
+        self.grid = step(&self.automaton, Topology::Plane, &self.grid);
+    }
+
+    fn population(&self) -> usize {
+        self.grid
+            .iter()
+            .filter(|cell| **cell != CellState::DEAD)
+            .count()
+    }
+
+    fn grid(&self) -> Grid<CellState> {
+        self.grid.clone()
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.grid.rows() * self.grid.cols() * mem::size_of::<CellState>()
+    }
+}
+
+/// Computes the next generation of the grid, the elementary automata always
+/// have dead cells beyond the edges
+pub fn step(automaton: &Automaton, topology: Topology, grid: &Grid<CellState>) -> Grid<CellState> {
+    let mut new_grid = Grid::new(grid.rows(), grid.cols());
+    step_into(automaton, topology, grid, &mut new_grid);
+    new_grid
+}
+
+/// Writes the next generation of the grid into `new_grid`, which is only
+/// reallocated if its size differs
+pub fn step_into(
+    automaton: &Automaton,
+    topology: Topology,
+    grid: &Grid<CellState>,
+    new_grid: &mut Grid<CellState>,
+) {
+    if new_grid.size() != grid.size() {
+        *new_grid = Grid::new(grid.rows(), grid.cols());
+    }
+
+    match automaton {
+        Automaton::Elementary(rule) => step_elementary(*rule, grid, new_grid),
+        Automaton::LargerThanLife(rule) => rule.step_into(topology, grid, new_grid),
+        _ => {
+            let offsets = offsets(automaton);
+
+            for row_idx in 0..grid.rows() {
+                for col_idx in 0..grid.cols() {
+                    new_grid[row_idx][col_idx] =
+                        next_cell(automaton, topology, grid, row_idx, col_idx, &offsets);
+                }
+            }
+        }
+    }
+}
+
+/// Steps a grid in place, keeping the previous generation in a second buffer
+/// which the next one is written over
+///
+/// Only the cells around the ones which changed in the previous step (or were
+/// edited since) are evaluated again, the neighbourhoods of all the others
+/// are the same as last time so they can't change either. Neither are the
+/// cells further from the live ones than the neighbourhood reaches, unless
+/// the rule lets cells be born without any neighbours.
+#[derive(Clone)]
+pub struct Stepper {
+    /// The previous generation
+    back: Grid<CellState>,
+
+    /// Cells of `back` which may be alive
+    back_live: Option<Bounds>,
+
+    /// Automaton which stepped the previous generation, `None` if it wasn't
+    /// stepped
+    automaton: Option<Automaton>,
+    offsets: Offsets,
+
+    /// Flattened indices of the cells the previous step changed
+    changed: Vec<usize>,
+
+    /// Cells which have to be evaluated during a step
+    dirty: Vec<bool>,
+
+    /// Cells the last step looked at
+    active: Option<Bounds>,
+}
+
+/// Rectangle of cells, both corners included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+
+impl Bounds {
+    /// Every cell of a grid of that size
+    fn whole(rows: usize, cols: usize) -> Option<Self> {
+        (rows > 0 && cols > 0).then(|| Self {
+            top: 0,
+            left: 0,
+            bottom: rows - 1,
+            right: cols - 1,
+        })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.bottom - self.top + 1
+    }
+
+    pub fn cols(&self) -> usize {
+        self.right - self.left + 1
+    }
+
+    /// Smallest bounds around the alive cells, `None` if there are none
+    pub fn live(grid: &Grid<CellState>) -> Option<Self> {
+        grid.indexed_iter()
+            .filter(|(_, cell)| cell.is_alive())
+            .fold(None, |bounds: Option<Self>, ((row, col), _)| {
+                Some(match bounds {
+                    Some(bounds) => Self {
+                        top: bounds.top.min(row),
+                        left: bounds.left.min(col),
+                        bottom: bounds.bottom.max(row),
+                        right: bounds.right.max(col),
+                    },
+                    None => Self {
+                        top: row,
+                        left: col,
+                        bottom: row,
+                        right: col,
+                    },
+                })
+            })
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        (self.top..=self.bottom).contains(&row) && (self.left..=self.right).contains(&col)
+    }
+
+    /// Grows the bounds by the margin on every side, without leaving the grid
+    pub fn expanded(self, margin: usize, rows: usize, cols: usize) -> Self {
+        Self {
+            top: self.top.saturating_sub(margin),
+            left: self.left.saturating_sub(margin),
+            bottom: (self.bottom + margin).min(rows - 1),
+            right: (self.right + margin).min(cols - 1),
+        }
+    }
+}
+
+impl Stepper {
+    pub fn new() -> Self {
+        Self {
+            back: Grid::new(0, 0),
+            back_live: None,
+            automaton: None,
+            offsets: Default::default(),
+            changed: Vec::new(),
+            dirty: Vec::new(),
+            active: None,
+        }
+    }
+
+    /// The grid the last call to [`Stepper::step`] or [`Stepper::keep`] was
+    /// given
+    pub fn previous(&self) -> &Grid<CellState> {
+        &self.back
+    }
+
+    /// Cells the last step evaluated, `None` if nothing was alive or the
+    /// grid wasn't stepped
+    pub fn active(&self) -> Option<Bounds> {
+        self.active
+    }
+
+    /// Remembers the grid as the previous generation without stepping it
+    pub fn keep(&mut self, grid: &Grid<CellState>) {
+        self.back.clone_from(grid);
+        self.back_live = Bounds::whole(grid.rows(), grid.cols());
+        self.automaton = None;
+        self.active = None;
+    }
+
+    /// Only steps the cells near the live ones on the plane, other
+    /// topologies step the whole grid every time
+    pub fn step(&mut self, automaton: &Automaton, topology: Topology, grid: &mut Grid<CellState>) {
+        let (rows, cols) = grid.size();
+
+        if self.back.size() != grid.size() {
+            self.back = Grid::new(rows, cols);
+            self.back_live = None;
+            self.automaton = None;
+        }
+
+        let incremental = self.automaton == Some(*automaton);
+        if !incremental {
+            self.offsets = offsets(automaton);
+        }
+
+        if topology != Topology::Plane
+            || !matches!(
+                automaton,
+                Automaton::Life(_) | Automaton::Wireworld | Automaton::Immigration
+            )
+        {
+            // what this step changed isn't recorded, so the next one can't
+            // be incremental
+            self.automaton = None;
+            step_into(automaton, topology, grid, &mut self.back);
+            self.back_live = Bounds::whole(rows, cols);
+            self.active = self.back_live;
+            self.changed.clear();
+            mem::swap(grid, &mut self.back);
+            return;
+        }
+
+        self.automaton = Some(*automaton);
+        if !incremental {
+            self.changed.clear();
+        }
+
+        // edits since the previous step count as changes as well, and give
+        // the live cells their bounds
+        let mut live = None::<Bounds>;
+        for row in 0..rows {
+            let cells = &grid.flatten()[row * cols..][..cols];
+            let before = &self.back.flatten()[row * cols..][..cols];
+
+            if let Some(first) = cells.iter().position(|cell| *cell != CellState::DEAD) {
+                let last = cells
+                    .iter()
+                    .rposition(|cell| *cell != CellState::DEAD)
+                    .unwrap_or(first);
+
+                live = Some(match live {
+                    Some(live) => Bounds {
+                        bottom: row,
+                        left: live.left.min(first),
+                        right: live.right.max(last),
+                        ..live
+                    },
+                    None => Bounds {
+                        top: row,
+                        left: first,
+                        bottom: row,
+                        right: last,
+                    },
+                });
+            }
+
+            if incremental && cells != before {
+                self.changed.extend(
+                    (0..cols)
+                        .filter(|&col| cells[col] != before[col])
+                        .map(|col| row * cols + col),
+                );
+            }
+        }
+
+        // unless dead cells can come alive on their own only the ones
+        // within reach of a live cell can change
+        let radius = automaton.neighbourhood().radius() as usize;
+        let region = if automaton.next_state(CellState::DEAD, 0) == CellState::DEAD {
+            live.map(|live| live.expanded(radius, rows, cols))
+        } else {
+            Bounds::whole(rows, cols)
+        };
+
+        if let Some(back_live) = self.back_live {
+            for row in back_live.top..=back_live.bottom {
+                for col in back_live.left..=back_live.right {
+                    if !region.is_some_and(|region| region.contains(row, col)) {
+                        self.back[row][col] = CellState::DEAD;
+                    }
+                }
+            }
+        }
+
+        if let Some(region) = region {
+            let area = region.rows() * region.cols();
+            let evaluate_all = !incremental || self.changed.len() * (2 * radius + 1).pow(2) >= area;
+
+            if !evaluate_all {
+                self.dirty.clear();
+                self.dirty.resize(rows * cols, false);
+
+                for &idx in &self.changed {
+                    let (row, col) = (idx / cols, idx % cols);
+                    let (left, right) = (col.saturating_sub(radius), (col + radius).min(cols - 1));
+
+                    for row in row.saturating_sub(radius)..=(row + radius).min(rows - 1) {
+                        self.dirty[row * cols + left..=row * cols + right].fill(true);
+                    }
+                }
+            }
+
+            self.changed.clear();
+            for row in region.top..=region.bottom {
+                for col in region.left..=region.right {
+                    let cell = if evaluate_all || self.dirty[row * cols + col] {
+                        next_cell(automaton, topology, grid, row, col, &self.offsets)
+                    } else {
+                        grid[row][col]
+                    };
+
+                    self.back[row][col] = cell;
+                    if cell != grid[row][col] {
+                        self.changed.push(row * cols + col);
+                    }
+                }
+            }
+        } else {
+            self.changed.clear();
+        }
+
+        self.back_live = live;
+        self.active = region;
+        mem::swap(grid, &mut self.back);
+    }
+}
+
+impl Default for Stepper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn offsets(automaton: &Automaton) -> Offsets {
+    let neighbourhood = automaton.neighbourhood();
+    [neighbourhood.offsets(0), neighbourhood.offsets(1)]
+}
+
+/// Next state of a cell of any automaton which only looks at the
+/// neighbourhood of each cell
+fn next_cell(
+    automaton: &Automaton,
+    topology: Topology,
+    grid: &Grid<CellState>,
+    row: usize,
+    col: usize,
+    offsets: &Offsets,
+) -> CellState {
+    match automaton {
+        Automaton::Immigration => next_immigration_cell(grid, topology, row, col, &offsets[0]),
+        _ => {
+            let n = alive_neighbours(grid, topology, row, col, &offsets[row & 1]);
+            automaton.next_state(grid[row][col], n)
+        }
+    }
+}
+
+/// Conway's life with both players' cells alive, a born cell belongs to the
+/// player with at least two of its three neighbours
+fn next_immigration_cell(
+    grid: &Grid<CellState>,
+    topology: Topology,
+    row: usize,
+    col: usize,
+    offsets: &[(i32, i32)],
+) -> CellState {
+    let (mut first, mut second) = (0, 0);
+    for &offset in offsets {
+        match neighbour(grid, topology, row, col, offset) {
+            CellState(1) => first += 1,
+            CellState(2) => second += 1,
+            _ => {}
+        }
+    }
+
+    let cell = grid[row][col];
+    match (cell, first + second) {
+        (CellState::DEAD, 3) if first > second => CellState(1),
+        (CellState::DEAD, 3) => CellState(2),
+        (CellState::DEAD, _) => CellState::DEAD,
+        (_, 2 | 3) => cell,
+        _ => CellState::DEAD,
+    }
+}
+
+/// Scrolls the grid up by one row and writes the next generation of the
+/// bottom row into the freed up row
+fn step_elementary(rule: u8, grid: &Grid<CellState>, new_grid: &mut Grid<CellState>) {
+    let last_row = grid.rows() - 1;
+    let next = Automaton::step_row(rule, &grid.iter_row(last_row).copied().collect::<Vec<_>>());
+
+    for row_idx in 0..last_row {
+        for col_idx in 0..grid.cols() {
+            new_grid[row_idx][col_idx] = grid[row_idx + 1][col_idx];
+        }
+    }
+
+    new_grid
+        .iter_row_mut(last_row)
+        .zip(next)
+        .for_each(|(cell, next)| *cell = next);
+}
+
+pub fn alive_neighbours(
+    grid: &Grid<CellState>,
+    topology: Topology,
+    row: usize,
+    col: usize,
+    offsets: &[(i32, i32)],
+) -> usize {
+    offsets
+        .iter()
+        .filter(|&&offset| neighbour(grid, topology, row, col, offset).is_alive())
+        .count()
+}
+
+/// Neighbours which count as alive in the automaton, unlike
+/// [`alive_neighbours`] these include both players' cells in Immigration
+pub fn live_neighbours(
+    automaton: &Automaton,
+    grid: &Grid<CellState>,
+    topology: Topology,
+    row: usize,
+    col: usize,
+    offsets: &[(i32, i32)],
+) -> usize {
+    offsets
+        .iter()
+        .filter(|&&offset| automaton.is_alive(neighbour(grid, topology, row, col, offset)))
+        .count()
+}
+
+/// State of the cell at the offset from the given one
+fn neighbour(
+    grid: &Grid<CellState>,
+    topology: Topology,
+    row: usize,
+    col: usize,
+    (dr, dc): (i32, i32),
+) -> CellState {
+    let (row, col) = (row as i32 + dr, col as i32 + dc);
+
+    // most neighbours are inside the grid, they don't need the topology
+    if let Some(cell) = grid.get(row as usize, col as usize) {
+        return *cell;
+    }
+
+    topology
+        .cell(row, col, grid.rows(), grid.cols())
+        .map_or(CellState::DEAD, |(row, col)| grid[row][col])
+}
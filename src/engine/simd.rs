@@ -0,0 +1,154 @@
+//! Counts the neighbours a row at a time, eight cells per word
+//!
+//! Every alive cell is a byte set to 1, so adding the rows above, at and
+//! below a row as `u64`s sums eight columns at once without a sum ever
+//! carrying into the next byte. Adding those column sums shifted by one cell
+//! either way gives the neighbour counts of eight cells, which are looked up
+//! in a table of the rule.
+
+use std::mem;
+
+use grid::Grid;
+
+use super::Engine;
+use crate::automaton::Automaton;
+use crate::rule::{Neighbourhood, Rule};
+use crate::CellState;
+
+/// Moore radius 1 rules with any number of states, cells beyond the edges
+/// are dead
+pub struct Simd {
+    rows: usize,
+    cols: usize,
+
+    /// Bytes per row of `alive`, there is a dead column left of the grid and
+    /// enough of them right of it to read a whole word at the last cell
+    stride: usize,
+
+    /// 1 for every alive cell, with a dead row above and below the grid
+    alive: Vec<u8>,
+    next_alive: Vec<u8>,
+
+    states: Vec<u8>,
+
+    /// Alive cells in the column of every cell of the row being stepped,
+    /// counting the rows above and below it as well
+    column_sums: Vec<u8>,
+
+    /// Next state by the current state and the neighbour count
+    table: Vec<[u8; 9]>,
+}
+
+impl Simd {
+    pub fn new(grid: &Grid<CellState>, automaton: Automaton) -> Result<Self, String> {
+        let rule = match automaton {
+            Automaton::Life(
+                rule @ Rule {
+                    neighbourhood: Neighbourhood::Moore(1),
+                    ..
+                },
+            ) => rule,
+            _ => return Err(format!("The simd engine can't run {automaton}")),
+        };
+
+        let (rows, cols) = grid.size();
+        let stride = (cols + 10).next_multiple_of(8);
+        let mut alive = vec![0; (rows + 2) * stride];
+
+        for ((row, col), cell) in grid.indexed_iter() {
+            alive[(row + 1) * stride + col + 1] = cell.is_alive() as u8;
+        }
+
+        let table = (0..rule.states)
+            .map(|state| {
+                let mut next = [0; 9];
+                for (n, next) in next.iter_mut().enumerate() {
+                    *next = rule.next_state(CellState(state), n).0;
+                }
+                next
+            })
+            .collect();
+
+        Ok(Self {
+            rows,
+            cols,
+            stride,
+            next_alive: alive.clone(),
+            alive,
+            states: grid
+                .iter()
+                .map(|cell| cell.0.min(rule.states - 1))
+                .collect(),
+            column_sums: vec![0; stride],
+            table,
+        })
+    }
+}
+
+/// The eight bytes at the start of the slice, the first one in the lowest
+/// byte
+fn word(bytes: &[u8]) -> u64 {
+    let mut word = [0; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+impl Engine for Simd {
+    fn step(&mut self) {
+        let (cols, stride) = (self.cols, self.stride);
+
+        for row in 0..self.rows {
+            let above = &self.alive[row * stride..][..stride];
+            let at = &self.alive[(row + 1) * stride..][..stride];
+            let below = &self.alive[(row + 2) * stride..][..stride];
+
+            for (idx, sum) in self.column_sums.chunks_exact_mut(8).enumerate() {
+                let offset = idx * 8;
+                let total = word(&above[offset..]) + word(&at[offset..]) + word(&below[offset..]);
+                sum.copy_from_slice(&total.to_le_bytes());
+            }
+
+            let states = &mut self.states[row * cols..][..cols];
+            let next_row = &mut self.next_alive[(row + 1) * stride..][..stride];
+
+            // the cell in column `col` is byte `col + 1` of the padded rows
+            for col in (0..cols).step_by(8) {
+                let sums = &self.column_sums[col..];
+                let counts =
+                    word(sums) + word(&sums[1..]) + word(&sums[2..]) - word(&at[col + 1..]);
+
+                for (idx, n) in counts
+                    .to_le_bytes()
+                    .into_iter()
+                    .enumerate()
+                    .take(cols - col)
+                {
+                    let state = &mut states[col + idx];
+                    *state = self.table[*state as usize][n as usize];
+                    next_row[col + 1 + idx] = (*state == CellState::ALIVE.0) as u8;
+                }
+            }
+        }
+
+        mem::swap(&mut self.alive, &mut self.next_alive);
+    }
+
+    fn population(&self) -> usize {
+        self.states.iter().filter(|state| **state != 0).count()
+    }
+
+    fn grid(&self) -> Grid<CellState> {
+        Grid::from_vec(
+            self.states.iter().map(|&state| CellState(state)).collect(),
+            self.cols,
+        )
+    }
+
+    fn memory_usage(&self) -> usize {
+        self.alive.len()
+            + self.next_alive.len()
+            + self.states.len()
+            + self.column_sums.len()
+            + self.table.len() * mem::size_of::<[u8; 9]>()
+    }
+}
@@ -0,0 +1,192 @@
+//! Golden generations of well known patterns and invariants every engine has
+//! to keep, so optimizing an engine can't quietly change what it computes
+//!
+//! They run with the tests of the binary for now and move to `tests/` once
+//! the engine is a library.
+
+use grid::Grid;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use super::naive::{self, Stepper};
+use super::EngineKind;
+use crate::automaton::{Automaton, PRESETS};
+use crate::topology::{Topology, TOPOLOGIES};
+use crate::{patterns, CellState};
+
+/// The built-in pattern in the middle of an empty grid
+fn placed(name: &str, rows: usize, cols: usize) -> Grid<CellState> {
+    let pattern = patterns::load(name).unwrap();
+    let mut grid = Grid::new(rows, cols);
+    pattern.stamp(
+        &mut grid,
+        (rows - pattern.cells.rows()) as i64 / 2,
+        (cols - pattern.cells.cols()) as i64 / 2,
+        2,
+    );
+    grid
+}
+
+/// The grid the given number of generations of Conway's life later
+fn run(mut grid: Grid<CellState>, generations: u64) -> Grid<CellState> {
+    let mut stepper = Stepper::new();
+    for _ in 0..generations {
+        stepper.step(&Automaton::default(), Topology::Plane, &mut grid);
+    }
+    grid
+}
+
+fn live_cells(grid: &Grid<CellState>) -> Vec<(usize, usize)> {
+    grid.indexed_iter()
+        .filter(|(_, cell)| **cell != CellState::DEAD)
+        .map(|(position, _)| position)
+        .collect()
+}
+
+fn population(grid: &Grid<CellState>) -> usize {
+    live_cells(grid).len()
+}
+
+#[test]
+fn blinker_has_period_2() {
+    let blinker = placed("blinker", 9, 9);
+
+    assert_ne!(run(blinker.clone(), 1), blinker);
+    assert_eq!(run(blinker.clone(), 2), blinker);
+    assert_eq!(run(blinker.clone(), 100), blinker);
+}
+
+#[test]
+fn glider_moves_diagonally_by_one_cell_every_4_steps() {
+    let glider = placed("glider", 32, 32);
+    let moved = |distance: usize| {
+        live_cells(&glider)
+            .into_iter()
+            .map(|(row, col)| (row + distance, col + distance))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(live_cells(&run(glider.clone(), 4)), moved(1));
+    assert_eq!(live_cells(&run(glider.clone(), 40)), moved(10));
+}
+
+#[test]
+fn r_pentomino_settles_with_116_cells_at_generation_1103() {
+    // large enough for its gliders not to reach the edges in time
+    let grid = run(placed("r-pentomino", 640, 640), 1102);
+    assert_ne!(population(&grid), 116);
+
+    let settled = run(grid, 1);
+    assert_eq!(population(&settled), 116);
+    assert_eq!(population(&run(settled, 100)), 116);
+}
+
+fn grid_from(cols: usize, alive: &[bool]) -> Grid<CellState> {
+    let cells = alive
+        .iter()
+        .map(|alive| {
+            if *alive {
+                CellState::ALIVE
+            } else {
+                CellState::DEAD
+            }
+        })
+        .collect();
+    Grid::from_vec(cells, cols)
+}
+
+/// Random grids of the sizes, every cell alive or dead
+fn soups(sizes: impl Strategy<Value = (usize, usize)>) -> impl Strategy<Value = Grid<CellState>> {
+    sizes.prop_flat_map(|(rows, cols)| {
+        vec(any::<bool>(), rows * cols).prop_map(move |alive| grid_from(cols, &alive))
+    })
+}
+
+/// Up to 40 by 40 cells
+fn small_soups() -> impl Strategy<Value = Grid<CellState>> {
+    soups((1..40usize, 1..40usize))
+}
+
+/// Squares with a power of two side, which fill hashlife's whole universe
+fn square_soups() -> impl Strategy<Value = Grid<CellState>> {
+    soups(select(vec![4usize, 8, 16, 32]).prop_map(|size| (size, size)))
+}
+
+fn automata() -> impl Strategy<Value = Automaton> {
+    select(
+        PRESETS
+            .iter()
+            .map(|(_, automaton)| *automaton)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Stepping the same grid twice gives the same grid, and stepping only near
+/// the live cells gives the grid stepping every cell does
+fn check_deterministic(automaton: &Automaton, topology: Topology, grid: &Grid<CellState>) {
+    let next = naive::step(automaton, topology, grid);
+    assert_eq!(naive::step(automaton, topology, grid), next);
+
+    let mut stepped = grid.clone();
+    Stepper::new().step(automaton, topology, &mut stepped);
+    assert_eq!(stepped, next);
+}
+
+/// None of the built-in rules give birth without live neighbours
+fn check_dead_stays_dead(automaton: &Automaton, topology: Topology, size: (usize, usize)) {
+    let mut grid = Grid::new(size.0, size.1);
+    let mut stepper = Stepper::new();
+
+    for _ in 0..10 {
+        stepper.step(automaton, topology, &mut grid);
+        assert_eq!(population(&grid), 0);
+    }
+}
+
+/// Every engine running Conway's life ends up with the same cells as the
+/// naive one, and so with its population
+fn check_engines_agree(grid: &Grid<CellState>, generations: u64) {
+    let expected = run(grid.clone(), generations);
+
+    for kind in [
+        EngineKind::Simd,
+        EngineKind::BitPacked,
+        EngineKind::HashLife,
+    ] {
+        let mut engine = kind.create(grid, Automaton::default()).unwrap();
+        for _ in 0..generations {
+            engine.step();
+        }
+        assert_eq!(engine.grid(), expected, "{kind} engine");
+        assert_eq!(engine.population(), population(&expected), "{kind} engine");
+    }
+}
+
+proptest! {
+    #[test]
+    fn stepping_is_deterministic(
+        automaton in automata(),
+        topology in select(TOPOLOGIES.to_vec()),
+        grid in small_soups(),
+    ) {
+        check_deterministic(&automaton, topology, &grid);
+    }
+
+    #[test]
+    fn dead_universe_stays_dead(
+        automaton in automata(),
+        topology in select(TOPOLOGIES.to_vec()),
+        size in (1..64usize, 1..64usize),
+    ) {
+        check_dead_stays_dead(&automaton, topology, size);
+    }
+
+    #[test]
+    fn engines_agree_with_naive(
+        grid in prop_oneof![small_soups(), square_soups()],
+        generations in 0..32u64,
+    ) {
+        check_engines_agree(&grid, generations);
+    }
+}
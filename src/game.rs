@@ -0,0 +1,836 @@
+//! The game without any frontend, shared by the window, the terminal and the
+//! web page
+//!
+//! Frontends only draw the grid and turn their input into [`Game::key`],
+//! [`Game::run`] and the other methods, so all of them behave the same. The
+//! window keeps what only it shows next to the game, like the ages of the
+//! cells and the tracked spaceships.
+
+use std::fs;
+use std::time::Duration;
+
+use grid::Grid;
+
+use crate::ant::Ant;
+use crate::automaton::{Automaton, PRESETS};
+use crate::command::{self, Command};
+use crate::engine::naive::{Bounds, Stepper};
+use crate::history::{self, Fingerprint, History, Snapshot};
+use crate::lenia::Lenia;
+use crate::locale::{fill, text, tr};
+#[cfg(feature = "net")]
+use crate::net;
+use crate::noise::Noise;
+use crate::observer::Observers;
+use crate::palette::Palette;
+use crate::predecessor::{self, Predecessor};
+use crate::rle::{self, Info, Pattern};
+use crate::rng::Rng;
+#[cfg(feature = "scripting")]
+use crate::script;
+use crate::topology::Topology;
+#[cfg(feature = "serde")]
+use crate::{camera::Camera, snapshot};
+use crate::{engine, patterns, recentered, CellState};
+use crate::{CELL_COLS, CELL_ROWS, GROWTH_FACTOR, SOUP_DENSITY, STEPS_PER_FRAME};
+
+const TIME_BETWEEN_STEPS: Duration = Duration::from_millis(200);
+
+/// Shortest time between steps
+const SPEED_FACTOR: Duration = Duration::from_millis(10);
+
+/// Longest time between steps
+const MAX_SPEED: Duration = Duration::from_millis(command::MAX_SPEED as u64);
+
+/// Most layers stepped alongside each other
+pub const MAX_LAYERS: usize = 4;
+
+pub struct Game {
+    pub grid: Grid<CellState>,
+    pub automaton: Automaton,
+
+    pub ants: Vec<Ant>,
+
+    /// When disabled only the ants change the grid
+    pub automaton_enabled: bool,
+
+    /// How the edges of the grid are glued together
+    pub topology: Topology,
+
+    /// Births and survivals failing at random, seeded by the seed of the run
+    pub noise: Noise,
+
+    /// Continuous universe which replaces the grid while it is active
+    pub lenia: Option<Lenia>,
+
+    pub palette: Palette,
+
+    /// Number of steps taken since the last reset
+    pub generation: u64,
+
+    /// Recorded generations which can be jumped back and forth between
+    history: History,
+
+    /// Steps the grid in place and keeps the generation before
+    stepper: Stepper,
+
+    /// Buffer shared by the inactive layers while stepping
+    layer_stepper: Stepper,
+
+    /// Independent universes stepped alongside each other, the active layer
+    /// is the one in `grid` and its slot here is left empty
+    pub layers: Vec<Grid<CellState>>,
+    pub active_layer: usize,
+
+    /// Told about every step of the grid, not the steps of Lenia
+    pub observers: Observers,
+
+    /// Name and comments of the pattern on the grid, written into exports
+    pub info: Info,
+
+    /// Name and pattern of the last pattern loaded onto the grid, taken by
+    /// the window for its shelf
+    pub loaded: Option<(String, Pattern)>,
+
+    /// Source of all randomness, so runs with the same seed are reproducible
+    rng: Rng,
+
+    /// Seed `rng` started from
+    pub seed: u64,
+
+    /// Probability of a cell starting alive in the random soups
+    soup_density: f64,
+
+    pub playing: bool,
+    pub time_bw_steps: Duration,
+
+    /// Index into [`STEPS_PER_FRAME`] of how many generations autoplay
+    /// advances every time it steps
+    steps_per_frame: usize,
+
+    /// Number of cells toggled by hand
+    pub edits: u64,
+
+    /// Counted up whenever the grid is replaced other than by a step, so
+    /// frontends know to forget what they derived from the old one
+    pub epoch: u64,
+}
+
+impl Game {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            grid: Grid::new(CELL_ROWS as _, CELL_COLS as _),
+            automaton: Automaton::default(),
+            ants: Vec::new(),
+            automaton_enabled: true,
+            topology: Topology::default(),
+            noise: Noise { level: 0.0, seed },
+            lenia: None,
+            palette: Palette::default(),
+            generation: 0,
+            history: History::new(),
+            stepper: Stepper::new(),
+            layer_stepper: Stepper::new(),
+            layers: vec![Grid::new(0, 0)],
+            active_layer: 0,
+            observers: Observers::new(),
+            info: Info::default(),
+            loaded: None,
+            rng: Rng::new(seed),
+            seed,
+            soup_density: SOUP_DENSITY,
+            playing: false,
+            time_bw_steps: TIME_BETWEEN_STEPS,
+            steps_per_frame: 0,
+            edits: 0,
+            epoch: 0,
+        }
+    }
+
+    /// Handles the keys which mean the same in every frontend, optionally
+    /// returns a message to show
+    pub fn key(&mut self, key: char) -> Option<String> {
+        let (rows, cols) = self.grid.size();
+
+        match key {
+            ' ' => self.step(),
+            'p' => self.playing = !self.playing,
+            'r' => self.reset(),
+            'f' => self.random_fill(),
+            'm' => {
+                let idx = PRESETS
+                    .iter()
+                    .position(|(_, automaton)| *automaton == self.automaton)
+                    .map_or(0, |idx| (idx + 1) % PRESETS.len());
+
+                let (name, automaton) = PRESETS[idx];
+                self.set_automaton(automaton);
+                return Some(fill("Rule: {}", &[&tr(name)]));
+            }
+            'k' => {
+                self.topology = self.topology.next();
+                return Some(fill("Topology: {}", &[&tr(self.topology.name())]));
+            }
+
+            '.' => {
+                self.steps_per_frame = (self.steps_per_frame + 1).min(STEPS_PER_FRAME.len() - 1);
+            }
+            ',' => self.steps_per_frame = self.steps_per_frame.saturating_sub(1),
+            '=' => return self.change_speed(true),
+            '-' => return self.change_speed(false),
+
+            '+' | '_' if self.lenia.is_some() => return Some(text("Leave continuous mode first")),
+            '+' => {
+                if rows.max(cols) >= 40 {
+                    return Some(text("Max grid size reached"));
+                }
+
+                self.resize(rows + GROWTH_FACTOR, cols + GROWTH_FACTOR);
+            }
+            '_' => {
+                if rows.min(cols) <= 4 {
+                    return Some(text("Min grid size reached"));
+                }
+
+                self.resize(rows - GROWTH_FACTOR, cols - GROWTH_FACTOR);
+            }
+
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Lengthens or shortens the time between steps by a step which grows
+    /// with it, a millisecond at a time near the fastest speed and a tenth
+    /// of a second near a second
+    pub fn change_speed(&mut self, slower: bool) -> Option<String> {
+        let step = Duration::from_millis((self.time_bw_steps.as_millis() as u64 / 10).max(1));
+
+        if slower {
+            if self.time_bw_steps >= MAX_SPEED {
+                return Some(text("Cannot increase further"));
+            }
+
+            self.set_speed(self.time_bw_steps + step);
+        } else if self.time_bw_steps <= SPEED_FACTOR {
+            return Some(text("Cannot decrease further"));
+        } else {
+            self.set_speed(self.time_bw_steps.saturating_sub(step));
+        }
+
+        None
+    }
+
+    pub fn set_speed(&mut self, time_bw_steps: Duration) {
+        self.time_bw_steps = time_bw_steps.clamp(SPEED_FACTOR, MAX_SPEED);
+    }
+
+    /// Same as the commands of the window, except for the ones only it has
+    pub fn run(&mut self, command: Command) -> Option<String> {
+        match command {
+            Command::Load(name) => {
+                return match patterns::load(&name) {
+                    Ok(pattern) => self.load_centered(pattern, &name),
+                    Err(err) => Some(err),
+                };
+            }
+            #[cfg(feature = "net")]
+            Command::Fetch(url) => {
+                return match net::fetch(&url) {
+                    Ok(pattern) => self.load_centered(pattern, &url),
+                    Err(err) => Some(err),
+                };
+            }
+            Command::Rule(automaton) => {
+                self.set_automaton(automaton);
+                return Some(fill("Rule: {}", &[&automaton]));
+            }
+            Command::Speed(ms) => self.set_speed(Duration::from_millis(ms as u64)),
+            Command::Resize(..) if self.lenia.is_some() => {
+                return Some(text("Leave continuous mode first"));
+            }
+            Command::Resize(rows, cols) => self.resize(rows, cols),
+            Command::Seed { seed, density } => {
+                self.seed = seed;
+                self.rng = Rng::new(seed);
+                self.noise.seed = seed;
+                self.soup_density = density.unwrap_or(self.soup_density);
+                self.random_fill();
+            }
+            Command::Density(density) => self.soup_density = density,
+            Command::Step(count) => return self.step_forward(count, |_| {}),
+            #[cfg(feature = "serde")]
+            Command::Save(_) | Command::Restore(_) => {
+                return Some(text("Snapshots can only be taken in the window"))
+            }
+            Command::Name(name) => self.info.name = Some(name).filter(|name| !name.is_empty()),
+            Command::Author(author) => {
+                self.info.author = Some(author).filter(|author| !author.is_empty())
+            }
+            Command::Comment(comment) if comment.is_empty() => self.info.comments.clear(),
+            Command::Comment(comment) => self.info.comments.push(comment),
+            Command::Export(path) => {
+                return Some(match fs::write(&path, self.to_rle()) {
+                    Ok(()) => fill("Exported to {}", &[&path]),
+                    Err(err) => fill("Cannot export to {}: {}", &[&path, &err]),
+                });
+            }
+            Command::Topology(topology) => self.topology = topology,
+            Command::Noise(level) => self.noise.level = level,
+            Command::Palette(palette) => self.palette = palette,
+            Command::Contrast(_) | Command::FontScale(_) => {
+                return Some(text(
+                    "High contrast and the font size only apply to the window",
+                ))
+            }
+            Command::Predecessor if self.lenia.is_some() => {
+                return Some(text("Leave continuous mode first"));
+            }
+            Command::Predecessor => {
+                return Some(
+                    match predecessor::find(
+                        &self.grid,
+                        self.automaton,
+                        self.topology,
+                        predecessor::BUDGET,
+                    ) {
+                        Ok(result) => {
+                            let message = result.message();
+                            if let Predecessor::Found(grid) = result {
+                                self.grid = grid;
+                                self.edits += 1;
+                                self.epoch += 1;
+                            }
+                            text(message)
+                        }
+                        Err(err) => err,
+                    },
+                );
+            }
+            Command::Clear => self.reset(),
+            Command::Help => return Some(text(command::HELP)),
+        }
+
+        None
+    }
+
+    /// Parses and runs a line typed after `:`
+    #[cfg(any(feature = "tui", feature = "web"))]
+    pub fn run_line(&mut self, line: &str) -> Option<String> {
+        match command::parse(line) {
+            Ok(command) => self.run(command),
+            Err(err) => Some(err),
+        }
+    }
+
+    /// Replaces the grid with the pattern in its middle, growing the grid if
+    /// it doesn't fit
+    pub fn load_centered(&mut self, pattern: Pattern, name: &str) -> Option<String> {
+        let (rows, cols) = self.grid.size();
+        let (pattern_rows, pattern_cols) = pattern.cells.size();
+        if pattern_rows > rows || pattern_cols > cols {
+            if self.lenia.is_some() {
+                return Some(text("The pattern doesn't fit the grid"));
+            }
+            self.resize(rows.max(pattern_rows), cols.max(pattern_cols));
+        }
+
+        self.reset();
+        if let Some(automaton) = pattern.rule.as_deref().and_then(|rule| rule.parse().ok()) {
+            self.set_automaton(automaton);
+        }
+
+        let (rows, cols) = self.grid.size();
+        pattern.stamp(
+            &mut self.grid,
+            (rows - pattern_rows) as i64 / 2,
+            (cols - pattern_cols) as i64 / 2,
+            self.automaton.states(),
+        );
+
+        if let Some(lenia) = &mut self.lenia {
+            lenia.grid = Lenia::from_cells(&self.grid).grid;
+        }
+        self.info = pattern.info.clone();
+
+        let shelved = pattern
+            .info
+            .name
+            .clone()
+            .unwrap_or_else(|| name.to_string());
+        self.loaded = Some((shelved, pattern));
+        Some(fill("Loaded {}", &[&name]))
+    }
+
+    /// Replaces the grid with the pattern, switching to its size and rule
+    pub fn load(&mut self, pattern: Pattern) {
+        let (rows, cols) = pattern.cells.size();
+        if self.grid.size() != (rows, cols) {
+            self.resize(rows, cols);
+        }
+
+        self.reset();
+        if let Some(automaton) = pattern.rule.as_deref().and_then(|rule| rule.parse().ok()) {
+            self.set_automaton(automaton);
+        }
+
+        pattern.stamp(&mut self.grid, 0, 0, self.automaton.states());
+        self.info = pattern.info;
+    }
+
+    /// Replaces the board with one stepped elsewhere, keeping the current
+    /// one as the previous generation
+    pub fn adopt(&mut self, pattern: &Pattern, generation: u64) {
+        let (rows, cols) = pattern.cells.size();
+        if self.grid.size() != (rows, cols) {
+            self.resize(rows, cols);
+        }
+
+        if let Some(automaton) = pattern.rule.as_deref().and_then(|rule| rule.parse().ok()) {
+            if automaton != self.automaton {
+                self.set_automaton(automaton);
+            }
+        }
+
+        self.stepper.keep(&self.grid);
+        self.grid
+            .iter_mut()
+            .for_each(|cell| *cell = CellState::DEAD);
+        pattern.stamp(&mut self.grid, 0, 0, self.automaton.states());
+
+        // the recorded generations are someone else's
+        self.history.clear();
+        self.generation = generation;
+    }
+
+    pub fn steps_per_frame(&self) -> usize {
+        STEPS_PER_FRAME[self.steps_per_frame]
+    }
+
+    /// Number of cells which aren't dead
+    pub fn population(&self) -> usize {
+        match &self.lenia {
+            Some(lenia) => lenia.grid.iter().filter(|value| **value >= 0.5).count(),
+            None => self
+                .grid
+                .iter()
+                .filter(|cell| **cell != CellState::DEAD)
+                .count(),
+        }
+    }
+
+    /// Status line shown below the grid
+    #[cfg(any(feature = "tui", feature = "web"))]
+    pub fn status(&self) -> String {
+        let name = match &self.info.name {
+            Some(name) => format!("{name}  "),
+            None => String::new(),
+        };
+
+        let noise = if self.noise.is_enabled() {
+            format!("{}: {}  ", tr("noise"), self.noise.level)
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{name}{}: {}  {}: {}  {}: {}ms  {}: x{}  {}: {}x{} {}  {}: {}  {noise}{}: {}  {}",
+            tr("gen"),
+            self.generation,
+            tr("pop"),
+            self.population(),
+            tr("speed"),
+            self.time_bw_steps.as_millis(),
+            tr("steps"),
+            self.steps_per_frame(),
+            tr("grid"),
+            self.grid.rows(),
+            self.grid.cols(),
+            self.topology,
+            tr("rule"),
+            self.rule_description(),
+            tr("seed"),
+            self.seed,
+            tr(if self.playing { "playing" } else { "paused" }),
+        )
+    }
+
+    /// Description of whatever is currently computing the next generation
+    pub fn rule_description(&self) -> String {
+        match &self.lenia {
+            Some(lenia) => lenia.to_string(),
+            None => self.automaton.to_string(),
+        }
+    }
+
+    /// The whole grid as RLE
+    pub fn to_rle(&self) -> String {
+        rle::encode(&self.grid, &self.automaton.to_string(), &self.info)
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            grid: self.grid.clone(),
+            ants: self.ants.clone(),
+            generation: self.generation,
+            automaton: self.automaton,
+            automaton_enabled: self.automaton_enabled,
+            topology: self.topology,
+            noise: self.noise,
+        }
+    }
+
+    /// Identifies the current generation, including the ants and the rule
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new(
+            &self.grid,
+            &self.ants,
+            self.generation,
+            self.automaton,
+            self.automaton_enabled,
+            self.topology,
+            self.noise,
+        )
+    }
+
+    pub fn step(&mut self) {
+        if let Some(lenia) = &mut self.lenia {
+            self.generation += 1;
+            lenia.step();
+            return;
+        }
+
+        let current = self.fingerprint();
+        let edited = !self.history.continues(&current);
+        if self.history.wants(&current) {
+            self.history.record(self.snapshot());
+        }
+
+        // only the cells the stepper evaluated and the ones under the ants
+        // can change
+        let ant_cells = self
+            .ants
+            .iter()
+            .map(|ant| Bounds {
+                top: ant.row,
+                left: ant.col,
+                bottom: ant.row,
+                right: ant.col,
+            })
+            .collect::<Vec<_>>();
+
+        history::advance(
+            &mut self.grid,
+            &mut self.ants,
+            &self.automaton,
+            self.automaton_enabled,
+            self.topology,
+            self.noise,
+            &mut self.stepper,
+        );
+        self.generation += 1;
+        let changed = self
+            .stepper
+            .active()
+            .into_iter()
+            .chain(ant_cells)
+            .collect::<Vec<_>>();
+        self.observers.stepped(
+            self.generation,
+            self.stepper.previous(),
+            &self.grid,
+            &changed,
+            edited,
+        );
+
+        for (idx, layer) in self.layers.iter_mut().enumerate() {
+            if idx != self.active_layer {
+                self.layer_stepper
+                    .step(&self.automaton, self.topology, layer);
+            }
+        }
+
+        self.history.stepped(self.fingerprint());
+    }
+
+    /// Moves `count` generations forward, through the recorded generations
+    /// first and by stepping once they run out, calling `stepped` after
+    /// every step
+    pub fn step_forward(&mut self, count: u64, mut stepped: impl FnMut(&Self)) -> Option<String> {
+        let target = self.generation.saturating_add(count);
+        let end = self.history.range().map_or(0, |(_, end)| end);
+
+        if self.generation < end {
+            let msg = self.seek(target.min(end));
+            if msg.is_some() {
+                return msg;
+            }
+        }
+
+        while self.generation < target {
+            self.step();
+            stepped(self);
+        }
+
+        None
+    }
+
+    /// Restores the previous generation
+    pub fn step_back(&mut self) -> Option<String> {
+        match self.history.range() {
+            Some((start, _)) if self.generation > start => self.seek(self.generation - 1),
+            _ if self.lenia.is_some() => Some(text("Cannot step back in continuous mode")),
+            _ => Some(text("No earlier generation")),
+        }
+    }
+
+    /// Jumps to any recorded generation
+    pub fn seek(&mut self, generation: u64) -> Option<String> {
+        if self.lenia.is_some() {
+            return Some(text("Cannot rewind in continuous mode"));
+        }
+
+        let Some(snapshot) = self.history.seek(self.snapshot(), generation) else {
+            return Some(fill("Generation {} isn't recorded", &[&generation]));
+        };
+
+        self.grid = snapshot.grid;
+        self.ants = snapshot.ants;
+        self.generation = snapshot.generation;
+        self.automaton = snapshot.automaton;
+        self.automaton_enabled = snapshot.automaton_enabled;
+        self.topology = snapshot.topology;
+        self.noise = snapshot.noise;
+        self.epoch += 1;
+        None
+    }
+
+    /// Earliest and latest generation which can be jumped to
+    pub fn timeline(&self) -> Option<(u64, u64)> {
+        self.history.range()
+    }
+
+    /// The grid before the last step
+    pub fn previous(&self) -> &Grid<CellState> {
+        self.stepper.previous()
+    }
+
+    /// Rows and columns around the live cells which the last step evaluated,
+    /// `None` if nothing was stepped
+    pub fn active_area(&self) -> Option<(usize, usize)> {
+        if self.lenia.is_some() {
+            return None;
+        }
+
+        self.stepper
+            .active()
+            .map(|active| (active.rows(), active.cols()))
+    }
+
+    pub fn reset(&mut self) {
+        if let Some(lenia) = &mut self.lenia {
+            lenia.grid.iter_mut().for_each(|value| *value = 0.0);
+        }
+
+        self.ants.clear();
+        self.history.clear();
+        self.generation = 0;
+        self.epoch += 1;
+        self.info = Info::default();
+        self.grid
+            .iter_mut()
+            .for_each(|cell| *cell = CellState::DEAD);
+    }
+
+    /// Replaces the grid with a random soup drawn from the seeded RNG
+    pub fn random_fill(&mut self) {
+        self.reset();
+
+        if let Some(lenia) = &mut self.lenia {
+            for value in lenia.grid.iter_mut() {
+                if self.rng.chance(self.soup_density) {
+                    *value = self.rng.next_f64() as f32;
+                }
+            }
+            return;
+        }
+
+        let (rows, cols) = self.grid.size();
+        self.grid = engine::soup(rows, cols, self.soup_density, &mut self.rng);
+    }
+
+    /// Resizes the grid, keeping the existing cells centered in the new grid.
+    /// Cells which fall outside the new bounds are clipped.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        let row_offset = rows as i32 / 2 - self.grid.rows() as i32 / 2;
+        let col_offset = cols as i32 / 2 - self.grid.cols() as i32 / 2;
+
+        self.grid = recentered(&self.grid, rows, cols);
+        for (idx, layer) in self.layers.iter_mut().enumerate() {
+            if idx != self.active_layer {
+                *layer = recentered(layer, rows, cols);
+            }
+        }
+
+        self.ants.retain_mut(|ant| {
+            let row = ant.row as i32 + row_offset;
+            let col = ant.col as i32 + col_offset;
+            (ant.row, ant.col) = (row as _, col as _);
+
+            (0..rows as i32).contains(&row) && (0..cols as i32).contains(&col)
+        });
+    }
+
+    pub fn is_clear(&self) -> bool {
+        self.grid.iter().all(|cell| *cell == CellState::DEAD)
+    }
+
+    /// Switches to another automaton, killing any cell whose state the new one doesn't have
+    pub fn set_automaton(&mut self, automaton: Automaton) {
+        let states = automaton.states();
+
+        self.automaton = automaton;
+        self.grid
+            .iter_mut()
+            .chain(self.layers.iter_mut().flat_map(|layer| layer.iter_mut()))
+            .filter(|cell| cell.0 >= states)
+            .for_each(|cell| *cell = CellState::DEAD);
+
+        // seed elementary automata with a single cell in the middle of the bottom row
+        if matches!(automaton, Automaton::Elementary(_)) && self.is_clear() {
+            let (rows, cols) = self.grid.size();
+            self.grid[rows - 1][cols / 2] = CellState::ALIVE;
+        }
+    }
+
+    #[cfg(any(feature = "tui", feature = "web"))]
+    pub fn toggle(&mut self, row: usize, col: usize) {
+        if let Some(cell) = self.grid.get_mut(row, col) {
+            cell.toggle(CellState::ALIVE);
+            self.edits += 1;
+        }
+    }
+
+    /// Adds a copy of the active layer and switches to it, so a variant of
+    /// the pattern can be compared with the original
+    pub fn add_layer(&mut self) -> Option<String> {
+        if self.layers.len() == MAX_LAYERS {
+            return Some(fill("At most {} layers", &[&MAX_LAYERS]));
+        }
+
+        self.layers.push(self.grid.clone());
+        self.switch_layer(self.layers.len() - 1);
+        None
+    }
+
+    pub fn remove_layer(&mut self) -> Option<String> {
+        if self.layers.len() == 1 {
+            return Some(text("Cannot remove the only layer"));
+        }
+
+        self.layers.remove(self.active_layer);
+        self.active_layer = self.active_layer.min(self.layers.len() - 1);
+        self.grid = std::mem::replace(&mut self.layers[self.active_layer], Grid::new(0, 0));
+        self.history.clear();
+        self.epoch += 1;
+        None
+    }
+
+    /// Makes another layer the one which is stepped with the history and
+    /// edited
+    pub fn switch_layer(&mut self, idx: usize) {
+        if idx == self.active_layer {
+            return;
+        }
+
+        std::mem::swap(&mut self.grid, &mut self.layers[self.active_layer]);
+        std::mem::swap(&mut self.grid, &mut self.layers[idx]);
+        self.active_layer = idx;
+
+        // the recorded generations belong to the previous layer
+        self.history.clear();
+        self.epoch += 1;
+    }
+
+    /// Runs a script against the active layer, the generations it went
+    /// through aren't recorded so the history starts over
+    #[cfg(feature = "scripting")]
+    pub fn run_script(&mut self, source: script::Source) -> Result<String, String> {
+        if self.lenia.is_some() {
+            return Err(text("Scripts can't run in the continuous mode"));
+        }
+
+        let mut world = script::World {
+            grid: self.grid.clone(),
+            ants: self.ants.clone(),
+            automaton: self.automaton,
+            automaton_enabled: self.automaton_enabled,
+            topology: self.topology,
+            noise: self.noise,
+            generation: self.generation,
+            rng: self.rng.clone(),
+            stepper: std::mem::take(&mut self.stepper),
+        };
+        let output = script::run(&mut world, source);
+        self.stepper = std::mem::take(&mut world.stepper);
+        let output = output?;
+
+        if world.automaton != self.automaton {
+            self.set_automaton(world.automaton);
+        }
+
+        self.topology = world.topology;
+        self.grid = world.grid;
+        self.ants = world.ants;
+        self.generation = world.generation;
+        self.rng = world.rng;
+        self.history.clear();
+        self.epoch += 1;
+
+        Ok(output)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn save_session(&self, camera: Camera) -> snapshot::Session {
+        snapshot::Session {
+            board: self.snapshot(),
+            layers: self.layers.clone(),
+            active_layer: self.active_layer,
+            speed: i32::try_from(self.time_bw_steps.as_millis()).unwrap_or(i32::MAX),
+            steps_per_frame: self.steps_per_frame,
+            camera,
+            seed: self.seed,
+            rng: self.rng.clone(),
+            soup_density: self.soup_density,
+        }
+    }
+
+    /// Replaces the whole game with a snapshot, which has to be consistent,
+    /// the camera is left to the window
+    #[cfg(feature = "serde")]
+    pub fn restore_session(&mut self, session: snapshot::Session) {
+        let board = session.board;
+
+        self.lenia = None;
+        self.reset();
+        self.grid = board.grid;
+        self.layers = session.layers;
+        self.active_layer = session.active_layer;
+        self.ants = board.ants;
+        self.generation = board.generation;
+        self.automaton_enabled = board.automaton_enabled;
+        self.topology = board.topology;
+        self.noise = board.noise;
+        self.set_automaton(board.automaton);
+
+        let (rows, cols) = self.grid.size();
+        self.ants.retain(|ant| ant.row < rows && ant.col < cols);
+
+        self.set_speed(Duration::from_millis(session.speed.max(0) as u64));
+        self.steps_per_frame = session.steps_per_frame.min(STEPS_PER_FRAME.len() - 1);
+        self.seed = session.seed;
+        self.rng = session.rng;
+        self.soup_density = session.soup_density;
+    }
+}
@@ -0,0 +1,2565 @@
+//! The window, drawing the grid with SFML and the status bar below it
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use grid::*;
+use sfml::graphics::*;
+use sfml::system::*;
+use sfml::window::*;
+
+use crate::ant::*;
+use crate::audio::{Audio, Sounds};
+use crate::automaton::*;
+use crate::camera::{self, Camera};
+use crate::census::*;
+use crate::cli::{GuiOptions, SessionOption};
+use crate::command::{self, Command};
+use crate::game::{self, Game};
+use crate::history::{self, Fingerprint};
+use crate::hud::*;
+use crate::lenia::*;
+use crate::locale::{fill, text, tr};
+use crate::minimap::Minimap;
+use crate::mipmap::Mipmap;
+use crate::net::{self, Session};
+use crate::observer::Observer;
+use crate::palette::Palette;
+use crate::render::{self, BoardLayout, Renderer, SfmlRenderer};
+use crate::replay::{Recorder, Replay};
+use crate::rule::*;
+#[cfg(feature = "scripting")]
+use crate::script;
+use crate::shelf::{Shelf, ShelfResponse};
+#[cfg(feature = "serde")]
+use crate::snapshot;
+use crate::spaceship::*;
+use crate::topology::Topology;
+use crate::tutorial::{self, Advance, Progress, Setup, Tutorial};
+use crate::{autosave, engine, hex, patterns, period, rle, CellState};
+use crate::{HEIGHT, STATUS_BAR_HEIGHT, WIDTH};
+
+const MULTI_STEP_COUNT: usize = 10;
+const MAX_MULTI_STEP_COUNT: usize = 10_000;
+const RULE_EDITOR_HELP: &str = "0-8: birth, Shift + 0-8: survival, E: done";
+const MESSAGE_DURATION: Time = Time::milliseconds(3000);
+const RECOVERY_MESSAGE_DURATION: Time = Time::milliseconds(15_000);
+
+/// How long the changes of an explained step are shown before they happen
+const EXPLAIN_DURATION: Time = Time::milliseconds(700);
+
+/// Cells smaller than this many pixels on the screen are drawn as blocks of
+/// them shaded by how many are alive
+const MIN_CELL_SIZE: f32 = 2.0;
+
+/// High contrast makes the lines between cells this much thicker and the
+/// status text this much larger
+const HIGH_CONTRAST_BORDERS: f32 = 3.0;
+const HIGH_CONTRAST_FONT_SCALE: f32 = 1.25;
+
+/// Neighbour counts are only written into cells at least this many pixels
+/// wide, smaller ones would be covered by the text
+const MIN_COUNT_CELL_SIZE: f32 = 16.0;
+const COUNT_FONT_SIZE: u32 = 12;
+
+/// Text of the cell inspection tooltip
+const TOOLTIP_FONT_SIZE: u32 = 14;
+const TOOLTIP_LINE_HEIGHT: f32 = 18.0;
+const TOOLTIP_PADDING: f32 = 6.0;
+
+/// Advance of every character of the monospaced font at the tooltip size
+const TOOLTIP_CHAR_WIDTH: f32 = 8.5;
+
+/// The FPS readout follows the frame times with this much lag, so it doesn't
+/// jitter with every frame
+const FPS_SMOOTHING: Time = Time::milliseconds(500);
+
+/// Overlay colors of the layers, also limiting how many there can be
+const LAYER_COLORS: [Color; game::MAX_LAYERS] = [
+    Color::rgba(0, 200, 255, 110),
+    Color::rgba(255, 0, 200, 110),
+    Color::rgba(0, 255, 100, 110),
+    Color::rgba(255, 140, 0, 110),
+];
+
+pub fn run(options: GuiOptions, seed: u64) -> Result<(), String> {
+    let mut replay = options.replay.as_deref().map(Replay::load).transpose()?;
+    let (seed, commands, command_lines) = match &replay {
+        Some(replay) => (
+            replay.seed,
+            replay
+                .commands
+                .iter()
+                .map(|line| command::parse(line))
+                .collect::<Result<_, _>>()?,
+            replay.commands.clone(),
+        ),
+        None => (seed, options.commands, options.command_lines),
+    };
+
+    let mut recorder = options
+        .record
+        .as_deref()
+        .map(|path| {
+            Recorder::create(path, seed, &command_lines)
+                .map_err(|err| fill("Couldn't record to {}: {}", &[&path.display(), &err]))
+        })
+        .transpose()?;
+
+    let mut window = RenderWindow::new(
+        (WIDTH, HEIGHT + STATUS_BAR_HEIGHT),
+        tr("Conway's game of life"),
+        Style::CLOSE,
+        &Default::default(),
+    );
+
+    match options.fps_cap {
+        Some(cap) => {
+            window.set_vertical_sync_enabled(false);
+            window.set_framerate_limit(cap);
+        }
+        None => window.set_vertical_sync_enabled(true),
+    }
+
+    let font_bytes = include_bytes!("../Hack NF.ttf");
+    let mut font = unsafe { Font::from_memory(font_bytes).unwrap() };
+    font.set_smooth(true);
+
+    let mut renderer = SfmlRenderer::new(window, &font);
+
+    let sounds = Sounds::new();
+    let mut audio = Audio::new(&sounds);
+
+    let mut hud = Hud::new(&font);
+    let mut state = GameState::new(seed);
+    let report = Rc::new(RefCell::new(StepReport::default()));
+    state.game.observers.add(Rc::clone(&report));
+    let mut clock = Clock::start();
+    let mut fps = None;
+
+    let (mut autosave, recovered) = autosave::Autosave::start();
+    if let Some(option) = &options.session {
+        hud.set_message(state.start_session(option), MESSAGE_DURATION);
+    }
+
+    for command in commands {
+        if let Some(msg) = state.run(command) {
+            hud.set_message(msg, MESSAGE_DURATION);
+        }
+    }
+
+    #[cfg(feature = "scripting")]
+    {
+        state.script = options.script;
+        for line in state.rerun_script().iter().flat_map(|msg| msg.lines()) {
+            hud.set_message(line, MESSAGE_DURATION);
+        }
+    }
+
+    // a recovered session would play out differently every time
+    if let Some(pattern) = recovered.filter(|_| recorder.is_none() && replay.is_none()) {
+        state.recovery = Some(pattern);
+        hud.set_message(
+            tr("The last session didn't exit cleanly, press Enter to restore it"),
+            RECOVERY_MESSAGE_DURATION,
+        );
+    }
+
+    while renderer.window.is_open() {
+        let mut dt = clock.restart();
+        fps = smoothed_fps(fps, dt);
+        let (generation, edits) = (state.game.generation, state.game.edits);
+
+        let mut events = Vec::new();
+        while let Some(event) = renderer.window.poll_event() {
+            events.push(event);
+        }
+
+        if let Some(frame) = replay.as_mut().map(Replay::next_frame) {
+            match frame {
+                Some((recorded_dt, recorded)) => {
+                    // the only input while replaying is closing the window
+                    events.retain(|event| matches!(event, Event::Closed));
+                    events.extend(recorded);
+                    dt = recorded_dt;
+                }
+                None => {
+                    replay = None;
+                    hud.set_message(tr("Replay finished"), MESSAGE_DURATION);
+                }
+            }
+        }
+
+        if let Some(Err(err)) = recorder
+            .as_mut()
+            .map(|recorder| recorder.frame(dt, &events))
+        {
+            recorder = None;
+            hud.set_message(fill("Stopped recording: {}", &[&err]), MESSAGE_DURATION);
+        }
+
+        for event in events {
+            let msg = match hud.handle_event(&event) {
+                HudResponse::Handled(Some(action)) => state.apply(action),
+                HudResponse::Handled(None) => None,
+                HudResponse::Ignored => state.handle_event(&event),
+            };
+
+            for line in msg.iter().flat_map(|msg| msg.lines()) {
+                hud.set_message(line, MESSAGE_DURATION);
+            }
+
+            if let Event::Closed = event {
+                if state.confirm(Confirm::Quit) {
+                    renderer.window.close();
+                } else {
+                    hud.set_message(
+                        tr("The grid isn't saved, close again to quit anyway"),
+                        MESSAGE_DURATION,
+                    );
+                }
+            }
+        }
+
+        let (rows, cols) = state.game.grid.size();
+
+        hud.update(dt);
+        hud.set_playing(state.game.playing);
+        hud.set_speed(to_time(state.game.time_bw_steps));
+        hud.set_font_scale(state.font_scale());
+        hud.set_timeline(state.game.timeline(), state.game.generation);
+        hud.set_field("FPS", format!("{:.0}", fps.unwrap_or_default()));
+        hud.set_field("gen", state.game.generation);
+        hud.set_field("pop", state.game.population());
+        hud.set_field(
+            "speed",
+            format!("{}ms", state.game.time_bw_steps.as_millis()),
+        );
+        hud.set_field("grid", format!("{rows}x{cols}"));
+
+        match state.game.topology {
+            Topology::Plane => hud.remove_field("edges"),
+            topology => hud.set_field("edges", topology.name()),
+        }
+
+        if state.game.noise.is_enabled() {
+            hud.set_field("noise", state.game.noise.level);
+        } else {
+            hud.remove_field("noise");
+        }
+
+        match state.game.palette {
+            Palette::Classic => hud.remove_field("palette"),
+            palette => hud.set_field("palette", tr(palette.name())),
+        }
+
+        match state.game.active_area() {
+            Some((rows, cols)) => hud.set_field("active", format!("{rows}x{cols}")),
+            None => hud.remove_field("active"),
+        }
+        hud.set_field("jump", state.multi_step_count);
+        hud.set_field("steps", format!("x{}", state.game.steps_per_frame()));
+        hud.set_field("mode", state.mode());
+        hud.set_field("rule", state.game.rule_description());
+        hud.set_field("seed", state.game.seed);
+
+        match state.super_cell_size() {
+            Some(size) => hud.set_field("blocks", format!("{size}x{size}")),
+            None => hud.remove_field("blocks"),
+        }
+
+        if state.camera.is_zoomed() {
+            hud.set_field("zoom", format!("x{:.1}", state.camera.zoom));
+        } else {
+            hud.remove_field("zoom");
+        }
+
+        match state.session_description() {
+            Some(session) => hud.set_field("net", session),
+            None => hud.remove_field("net"),
+        }
+
+        match state.player_counts() {
+            Some((first, second)) => {
+                hud.set_field("p1", first);
+                hud.set_field("p2", second);
+            }
+            None => {
+                hud.remove_field("p1");
+                hud.remove_field("p2");
+            }
+        }
+
+        match state.layer_description() {
+            Some(layer) => hud.set_field("layer", layer),
+            None => hud.remove_field("layer"),
+        }
+
+        match state.census() {
+            Some(census) => hud.set_field("census", census),
+            None => hud.remove_field("census"),
+        }
+
+        match state.spaceship_summary() {
+            Some(summary) => hud.set_field("ships", summary),
+            None => hud.remove_field("ships"),
+        }
+
+        autosave.update(dt, || state.game.to_rle());
+
+        if let Some(msg) = state.update_session() {
+            hud.set_message(msg, MESSAGE_DURATION);
+        }
+        state.update(dt);
+
+        if let Some(msg) = state.update_tutorial() {
+            hud.set_message(msg, MESSAGE_DURATION);
+        }
+        hud.set_prompt(state.tutorial_prompt());
+
+        let mut step_report = report.borrow_mut();
+        if let Some(settled) = step_report.settled.take() {
+            hud.set_message(settled, MESSAGE_DURATION);
+        }
+        match step_report.changed {
+            Some(changed) => hud.set_field("changed", changed),
+            None => hud.remove_field("changed"),
+        }
+        drop(step_report);
+
+        if state.sound {
+            if state.game.edits != edits {
+                audio.click();
+            }
+
+            if state.game.generation != generation {
+                if state.sonify {
+                    audio.sonify(state.game.population() as f32 / (rows * cols) as f32);
+                } else {
+                    audio.tick();
+                }
+            }
+        }
+
+        state.draw(&mut renderer);
+        hud.draw(&mut renderer);
+        renderer.present();
+    }
+
+    autosave.finish();
+    Ok(())
+}
+
+/// A destructive action which has to be repeated to go through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirm {
+    Reset,
+    Quit,
+}
+
+/// What the status bar shows about the steps, told by the observers of the
+/// game
+#[derive(Default)]
+struct StepReport {
+    /// Cells the step being reported changed so far
+    changing: usize,
+
+    /// Cells the last step changed
+    changed: Option<usize>,
+
+    /// Message about the grid having settled, taken once it is shown
+    settled: Option<String>,
+}
+
+impl Observer for StepReport {
+    fn on_generation(&mut self, _generation: u64, _grid: &Grid<CellState>) {
+        self.changed = Some(std::mem::take(&mut self.changing));
+    }
+
+    fn on_cell_changed(&mut self, _row: usize, _col: usize, _before: CellState, _after: CellState) {
+        self.changing += 1;
+    }
+
+    fn on_stabilized(&mut self, generation: u64, period: u64) {
+        self.settled = Some(match period {
+            1 => fill("Settled into a still life at generation {}", &[&generation]),
+            period => fill(
+                "Settled into a period {} oscillator at generation {}",
+                &[&period, &generation],
+            ),
+        });
+    }
+
+    fn wants_stabilized(&self) -> bool {
+        true
+    }
+}
+
+/// What clicking on the grid does while autoplaying
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditPolicy {
+    /// Autoplay is paused until the mouse button is released
+    Pause,
+
+    /// The toggled cells are collected and applied all at once between two
+    /// steps when the mouse button is released
+    Queue,
+}
+
+/// Blocks of cells as they were last handed to the renderer, drawn instead of
+/// the cells while those are too small
+struct SuperCells {
+    /// Cells on each side of a block
+    size: usize,
+    layout: BoardLayout,
+    color: Color,
+
+    /// Cells counted into the mipmap and the automaton deciding which of
+    /// them are alive
+    grid: Grid<CellState>,
+    automaton: Automaton,
+    mipmap: Mipmap,
+}
+
+/// What the window derives from the steps of the game, started over whenever
+/// the game replaces its grid
+struct Tracking {
+    /// Epoch of the game the grid was last seen in
+    epoch: u64,
+
+    /// Generations every cell has stayed in its state, shown when it is
+    /// inspected and rebuilt on the next step if it doesn't match the grid
+    ages: Grid<u32>,
+
+    /// Set while spaceships are being tracked
+    spaceship_tracker: Option<SpaceshipTracker>,
+
+    /// Spaceships found in the current generation
+    spaceships: Vec<Spaceship>,
+}
+
+impl Tracking {
+    fn new() -> Self {
+        Self {
+            epoch: 0,
+            ages: Grid::new(0, 0),
+            spaceship_tracker: None,
+            spaceships: Vec::new(),
+        }
+    }
+
+    /// Forgets the ages and the spaceships if the grid was replaced since
+    /// it was last seen
+    fn catch_up(&mut self, game: &Game) {
+        if self.epoch == game.epoch {
+            return;
+        }
+
+        self.epoch = game.epoch;
+        self.ages = Grid::new(0, 0);
+        self.spaceships.clear();
+
+        if let Some(tracker) = &mut self.spaceship_tracker {
+            tracker.clear();
+        }
+    }
+
+    fn stepped(&mut self, game: &Game) {
+        self.catch_up(game);
+        if game.lenia.is_some() {
+            return;
+        }
+
+        self.update_ages(&game.grid, game.previous());
+
+        if let Some(tracker) = &mut self.spaceship_tracker {
+            self.spaceships = tracker.observe(&game.grid);
+        }
+    }
+
+    /// Ages the cells which stayed the same since the previous grid the
+    /// stepper kept, the ages are rebuilt if the size changed in between
+    fn update_ages(&mut self, grid: &Grid<CellState>, previous: &Grid<CellState>) {
+        if self.ages.size() != grid.size() || previous.size() != grid.size() {
+            self.ages = Grid::new(grid.rows(), grid.cols());
+            return;
+        }
+
+        for ((age, cell), before) in self.ages.iter_mut().zip(grid.iter()).zip(previous.iter()) {
+            *age = if cell == before {
+                age.saturating_add(1)
+            } else {
+                0
+            };
+        }
+    }
+}
+
+struct GameState {
+    /// Everything the terminal and the web page share with the window
+    game: Game,
+
+    /// Epoch of the game the edits and the explained step belong to
+    epoch: u64,
+
+    /// Automaton the brush and the census were chosen for
+    automaton: Automaton,
+
+    tracking: Tracking,
+
+    button_pressed: bool,
+
+    elapsed_time: Time,
+
+    /// Number of generations advanced by a single `Shift + Space`
+    pub multi_step_count: usize,
+
+    // used for handling the cell toggle
+    toggled_cell: (i32, i32),
+
+    edit_policy: EditPolicy,
+
+    /// Set if autoplay was paused by clicking and resumes after the click
+    resume_after_edit: bool,
+
+    /// Cells toggled while autoplaying with [`EditPolicy::Queue`]
+    pending_edits: Vec<(i32, i32)>,
+
+    /// Whether steps and toggled cells make a sound, and whether steps play a
+    /// note following the population instead of ticking
+    pub sound: bool,
+    pub sonify: bool,
+
+    /// Whether the lines between cells are drawn
+    grid_lines: bool,
+
+    /// Draws thick, bright lines between the cells whether `grid_lines` is
+    /// on or not and enlarges the status text
+    high_contrast: bool,
+
+    /// Size of the status text relative to the default one
+    font_scale: f32,
+
+    /// Cell under the mouse cursor, highlighted to preview what a click affects
+    hovered_cell: Option<(i32, i32)>,
+
+    /// Window coordinates of the mouse cursor while it is over the window
+    mouse_position: Option<(i32, i32)>,
+
+    /// Whether manual steps show which cells are born and which die before
+    /// they happen
+    explaining: bool,
+
+    /// Next generation of an explained step, the generation it follows and
+    /// how much longer it is shown before it is applied. Edits and the
+    /// steps of autoplay make it outdated, it is dropped then.
+    explained_step: Option<(Grid<CellState>, Fingerprint, Time)>,
+
+    /// Whether every cell shows the number of its alive neighbours
+    neighbour_counts: bool,
+
+    /// The grid as it was last handed to the renderer with the layout, the
+    /// automaton and the palette it was colored by, only the cells which
+    /// changed since are sent again
+    drawn: Option<(BoardLayout, Automaton, Palette, Grid<CellState>)>,
+
+    super_cells: Option<SuperCells>,
+
+    /// Part of the grid shown in the window
+    pub camera: Camera,
+
+    /// Last mouse position while the view is dragged with the middle button
+    panning: Option<(i32, i32)>,
+
+    /// Whether the camera keeps the live cells in view, until it is moved by
+    /// hand
+    following: bool,
+
+    /// Whether Ctrl was held at the last key event, kept track of instead of
+    /// asking the keyboard so recorded sessions replay the same
+    ctrl_held: bool,
+
+    /// State painted by the mouse
+    brush: CellState,
+
+    /// While set the number keys flip the birth/survival counts of the rule
+    editing_rule: bool,
+
+    /// Set while the objects on the grid are being counted
+    census: Option<Census>,
+
+    /// Whether the inactive layers are overlaid on the active one
+    show_layers: bool,
+
+    /// Two opposite corners of the selected cells
+    selection: Option<((i32, i32), (i32, i32))>,
+
+    /// Set while the selection is being dragged out
+    selecting: bool,
+
+    /// Autosave of a session which didn't exit cleanly, restored with Enter
+    pub recovery: Option<rle::Pattern>,
+
+    /// Started and stopped with Ctrl + T
+    tutorial: Option<Tutorial>,
+
+    /// Whether the info is shown over the grid
+    show_info: bool,
+
+    /// Patterns of the session to stamp onto the grid
+    shelf: Shelf,
+
+    /// Action waiting to be repeated to confirm it
+    confirming: Option<Confirm>,
+
+    /// Hash of the grid when it was last copied or loaded as a whole
+    saved: Option<u64>,
+
+    /// Board shared with other players, only the host steps it and the
+    /// clients show what it sends them
+    session: Option<Session>,
+
+    /// Script given with `--script`, run again with F5
+    #[cfg(feature = "scripting")]
+    pub script: Option<std::path::PathBuf>,
+}
+
+impl GameState {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            game: Game::new(seed),
+            epoch: 0,
+            automaton: Automaton::default(),
+            tracking: Tracking::new(),
+            census: None,
+            show_layers: true,
+            selection: None,
+            selecting: false,
+            recovery: None,
+            tutorial: None,
+            show_info: true,
+            shelf: Shelf::new(),
+            confirming: None,
+            saved: None,
+            session: None,
+            #[cfg(feature = "scripting")]
+            script: None,
+            multi_step_count: MULTI_STEP_COUNT,
+            elapsed_time: Time::ZERO,
+            toggled_cell: (-1, -1),
+            edit_policy: EditPolicy::Pause,
+            resume_after_edit: false,
+            pending_edits: Vec::new(),
+            hovered_cell: None,
+            mouse_position: None,
+            explaining: false,
+            explained_step: None,
+            neighbour_counts: false,
+            drawn: None,
+            super_cells: None,
+            camera: Camera::default(),
+            panning: None,
+            following: false,
+            sound: false,
+            sonify: false,
+            grid_lines: true,
+            high_contrast: false,
+            font_scale: 1.0,
+            button_pressed: false,
+            ctrl_held: false,
+            brush: CellState::ALIVE,
+            editing_rule: false,
+        }
+    }
+
+    /// Catches up with what the game did, forgetting what belonged to a grid
+    /// it replaced or to the automaton before
+    fn sync(&mut self) {
+        self.tracking.catch_up(&self.game);
+
+        if self.epoch != self.game.epoch {
+            self.epoch = self.game.epoch;
+            self.pending_edits.clear();
+            self.explained_step = None;
+        }
+
+        if self.automaton != self.game.automaton {
+            self.automaton = self.game.automaton;
+            self.brush = CellState::ALIVE;
+            self.editing_rule = false;
+
+            // the objects are only recognised if they behave the same in the new automaton
+            if self.census.is_some() {
+                self.census = Some(Census::new(&self.automaton));
+            }
+        }
+
+        if let Some((name, pattern)) = self.game.loaded.take() {
+            self.shelf.add(&name, &pattern);
+        }
+    }
+
+    /// Optionally a message that has to be displayed in the status bar
+    pub fn handle_event(&mut self, event: &Event) -> Option<String> {
+        let msg = self.handle_input(event);
+        self.sync();
+        msg
+    }
+
+    fn handle_input(&mut self, event: &Event) -> Option<String> {
+        // anything else cancels a pending confirmation
+        if matches!(
+            event,
+            Event::KeyPressed { .. } | Event::MouseButtonPressed { .. }
+        ) && !matches!(event, Event::KeyPressed { code: Key::R, .. })
+        {
+            self.confirming = None;
+        }
+
+        // on X11 the modifiers of the Ctrl key's own events are the ones from
+        // before it was pressed or released
+        match *event {
+            Event::KeyPressed {
+                code: Key::LControl | Key::RControl,
+                ..
+            } => self.ctrl_held = true,
+            Event::KeyReleased {
+                code: Key::LControl | Key::RControl,
+                ..
+            } => self.ctrl_held = false,
+            Event::KeyPressed { ctrl, .. } | Event::KeyReleased { ctrl, .. } => {
+                self.ctrl_held = ctrl
+            }
+            _ => {}
+        }
+
+        match self.shelf.handle_event(event) {
+            ShelfResponse::Ignored => {}
+            ShelfResponse::Handled => return None,
+            ShelfResponse::Dropped { item, x, y } => return self.place(item, x, y),
+        }
+
+        match event {
+            &Event::KeyPressed {
+                code, shift, ctrl, ..
+            } => match code {
+                Key::Space if shift => (0..self.multi_step_count).for_each(|_| self.step()),
+                Key::Space => self.manual_step(),
+                Key::R if !self.confirm(Confirm::Reset) => {
+                    return Some(text("Press R again to clear the grid"));
+                }
+                Key::R => self.game.reset(),
+                Key::F => return self.game.key('f'),
+                Key::P if shift => self.shelf.toggle(),
+                Key::P => self.toggle_play(),
+                Key::Backspace => return self.game.step_back(),
+                Key::Left => {
+                    let count = if shift {
+                        self.multi_step_count as u64
+                    } else {
+                        1
+                    };
+                    let start = self.game.timeline().map_or(0, |(start, _)| start);
+
+                    return match self.game.generation.saturating_sub(count).max(start) {
+                        _ if self.game.generation <= start => self.game.step_back(),
+                        generation => self.game.seek(generation),
+                    };
+                }
+                Key::Right => {
+                    let count = if shift { self.multi_step_count } else { 1 };
+                    return self.step_forward(count as u64);
+                }
+
+                Key::M => return self.game.key('m'),
+                Key::K => return self.game.key('k'),
+
+                Key::L => {
+                    self.game.automaton_enabled = !self.game.automaton_enabled;
+                    return Some(text(if self.game.automaton_enabled {
+                        "Rule enabled"
+                    } else {
+                        "Rule disabled, only ants move"
+                    }));
+                }
+
+                Key::N => {
+                    let Automaton::Life(rule) = &mut self.game.automaton else {
+                        return Some(text(
+                            "Only life-like rules can change their neighbourhood",
+                        ));
+                    };
+
+                    let idx = Neighbourhood::ALL
+                        .iter()
+                        .position(|neighbourhood| *neighbourhood == rule.neighbourhood)
+                        .map_or(0, |idx| (idx + 1) % Neighbourhood::ALL.len());
+
+                    rule.neighbourhood = Neighbourhood::ALL[idx];
+                    return Some(fill("Neighbourhood: {}", &[&rule.neighbourhood]));
+                }
+
+                Key::E => {
+                    if !matches!(self.game.automaton, Automaton::Life(_)) {
+                        return Some(text("Only life-like rules can be edited"));
+                    }
+
+                    self.editing_rule = !self.editing_rule;
+                    if !self.editing_rule {
+                        return Some(fill("Rule: {}", &[&self.game.automaton]));
+                    }
+
+                    return Some(text(RULE_EDITOR_HELP));
+                }
+
+                Key::Escape if self.editing_rule => {
+                    self.editing_rule = false;
+                    return Some(fill("Rule: {}", &[&self.game.automaton]));
+                }
+                Key::Enter if self.recovery.is_some() => {
+                    let pattern = self.recovery.take()?;
+                    self.game.load(pattern);
+                    return Some(text("Restored the last session"));
+                }
+                Key::Enter => {
+                    if let Some(tutorial) = &mut self.tutorial {
+                        tutorial.confirm();
+                    }
+                }
+                Key::Escape => {
+                    self.selection = None;
+                    self.shelf.deselect();
+                }
+                Key::Home => {
+                    self.following = false;
+                    self.camera = Camera::default();
+                }
+                Key::X if shift => {
+                    self.neighbour_counts = !self.neighbour_counts;
+                    return Some(text(if self.neighbour_counts {
+                        "Showing the neighbour counts"
+                    } else {
+                        "Hiding the neighbour counts"
+                    }));
+                }
+                Key::X => {
+                    self.explaining = !self.explaining;
+                    return Some(text(if self.explaining {
+                        "Explaining steps: cells about to be born turn green, dying ones red"
+                    } else {
+                        "Stopped explaining steps"
+                    }));
+                }
+                Key::W => {
+                    self.following = !self.following;
+                    return Some(text(if self.following {
+                        "Following the live cells"
+                    } else {
+                        "Stopped following"
+                    }));
+                }
+                #[cfg(feature = "scripting")]
+                Key::F5 => return self.rerun_script(),
+                Key::C if ctrl => return self.copy(),
+                Key::V if ctrl => return self.paste(),
+
+                Key::Num0
+                | Key::Num1
+                | Key::Num2
+                | Key::Num3
+                | Key::Num4
+                | Key::Num5
+                | Key::Num6
+                | Key::Num7
+                | Key::Num8
+                    if self.editing_rule =>
+                {
+                    let count = code as i32 - Key::Num0 as i32;
+                    if let Automaton::Life(rule) = &mut self.game.automaton {
+                        if shift {
+                            rule.survival ^= 1 << count;
+                        } else {
+                            rule.birth ^= 1 << count;
+                        }
+                    }
+
+                    // the edited rule keeps the brush and the editor open
+                    self.automaton = self.game.automaton;
+
+                    return Some(text(RULE_EDITOR_HELP));
+                }
+
+                Key::Num1
+                | Key::Num2
+                | Key::Num3
+                | Key::Num4
+                | Key::Num5
+                | Key::Num6
+                | Key::Num7
+                | Key::Num8
+                | Key::Num9 => {
+                    let state = (code as i32 - Key::Num0 as i32) as u8;
+                    if state >= self.game.automaton.states() {
+                        return Some(text("No such state"));
+                    }
+
+                    self.brush = CellState(state);
+                    return Some(fill(
+                        "Brush: {}",
+                        &[&tr(self.game.automaton.state_name(self.brush))],
+                    ));
+                }
+
+                Key::RBracket => {
+                    if self.multi_step_count >= MAX_MULTI_STEP_COUNT {
+                        return Some(text("Cannot increase further"));
+                    }
+
+                    self.multi_step_count *= 10;
+                }
+
+                Key::LBracket => {
+                    if self.multi_step_count == 1 {
+                        return Some(text("Cannot decrease further"));
+                    }
+
+                    self.multi_step_count /= 10;
+                }
+
+                Key::Equal => return self.game.key('='),
+                Key::Hyphen => return self.game.key('-'),
+                Key::Add if shift => return self.game.key('='),
+                Key::Subtract if shift => return self.game.key('-'),
+                Key::Add => return self.game.key('+'),
+                Key::Subtract => return self.game.key('_'),
+
+                Key::Period => return self.game.key('.'),
+                Key::Comma => return self.game.key(','),
+                Key::A => {
+                    self.edit_policy = match self.edit_policy {
+                        EditPolicy::Pause => EditPolicy::Queue,
+                        EditPolicy::Queue => EditPolicy::Pause,
+                    };
+
+                    return Some(text(match self.edit_policy {
+                        EditPolicy::Pause => "Clicking while playing pauses until the click ends",
+                        EditPolicy::Queue => {
+                            "Cells clicked while playing change when the click ends"
+                        }
+                    }));
+                }
+                Key::T if ctrl => return self.toggle_tutorial(),
+                Key::T if shift => return self.game.remove_layer(),
+                Key::T => return self.game.add_layer(),
+                Key::Tab => {
+                    let next = (self.game.active_layer + 1) % self.game.layers.len();
+                    self.game.switch_layer(next);
+                }
+                Key::V => self.show_layers = !self.show_layers,
+                Key::G => self.grid_lines = !self.grid_lines,
+                Key::H if shift => {
+                    self.game.palette = self.game.palette.next();
+                    return Some(fill("Palette: {}", &[&tr(self.game.palette.name())]));
+                }
+                Key::H => {
+                    self.high_contrast = !self.high_contrast;
+                    return Some(text(if self.high_contrast {
+                        "High contrast on"
+                    } else {
+                        "High contrast off"
+                    }));
+                }
+                Key::I if self.game.info.is_empty() => {
+                    return Some(text(
+                        "The pattern has no name or comments, add them with :name, :author and :comment",
+                    ))
+                }
+                Key::I => self.show_info = !self.show_info,
+                Key::B if shift => {
+                    self.sonify = !self.sonify;
+                    self.sound |= self.sonify;
+                    return Some(text(if self.sonify {
+                        "Steps play the population"
+                    } else {
+                        "Steps tick"
+                    }));
+                }
+                Key::B => {
+                    self.sound = !self.sound;
+                    return Some(text(if self.sound {
+                        "Sound on"
+                    } else {
+                        "Sound off"
+                    }));
+                }
+                Key::O => {
+                    self.census = match self.census {
+                        Some(_) => None,
+                        None => Some(Census::new(&self.game.automaton)),
+                    };
+                }
+                Key::S => {
+                    self.tracking.spaceships.clear();
+                    self.tracking.spaceship_tracker = match self.tracking.spaceship_tracker {
+                        Some(_) => None,
+                        None => Some(SpaceshipTracker::new()),
+                    };
+                }
+
+                Key::C => {
+                    return Some(text(match self.game.lenia.take() {
+                        Some(lenia) => {
+                            self.game.grid = lenia.to_cells();
+                            "Left continuous mode"
+                        }
+                        None => {
+                            self.game.lenia = Some(Lenia::from_cells(&self.game.grid));
+                            "Entered continuous mode"
+                        }
+                    }));
+                }
+
+                _ => {}
+            },
+
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } if self.camera.is_zoomed() && Minimap::contains(x as f32, y as f32) => {
+                let minimap = Minimap::new(&self.live_positions(), self.camera.viewport());
+                self.following = false;
+                self.camera.look_at(minimap.to_world(x as f32, y as f32));
+            }
+
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } if self.ctrl_held => {
+                self.toggle_ant(x, y);
+            }
+
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } if self.shelf.selected().is_some() => {
+                return self.shelf.selected().and_then(|item| self.place(item, x, y));
+            }
+
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } => {
+                if self.game.automaton == Automaton::Immigration {
+                    self.brush = CellState(1);
+                }
+                self.start_stroke(x, y);
+            }
+
+            Event::MouseButtonReleased {
+                button: mouse::Button::Left,
+                ..
+            } => self.end_stroke(),
+
+            // the second player paints with the right button instead of
+            // selecting
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Right,
+                x,
+                y,
+            } if self.game.automaton == Automaton::Immigration && self.game.lenia.is_none() => {
+                self.brush = CellState(2);
+                self.start_stroke(x, y);
+            }
+
+            Event::MouseButtonReleased {
+                button: mouse::Button::Right,
+                ..
+            } if self.button_pressed => self.end_stroke(),
+
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Right,
+                x,
+                y,
+            } => {
+                let cell = self.cell_at(x, y);
+                self.selection = Some((cell, cell));
+                self.selecting = true;
+            }
+
+            Event::MouseButtonReleased {
+                button: mouse::Button::Right,
+                ..
+            } => self.selecting = false,
+
+            &Event::MouseButtonPressed {
+                button: mouse::Button::Middle,
+                x,
+                y,
+            } => self.panning = Some((x, y)),
+
+            Event::MouseButtonReleased {
+                button: mouse::Button::Middle,
+                ..
+            } => self.panning = None,
+
+            &Event::MouseWheelScrolled {
+                wheel: mouse::Wheel::VerticalWheel,
+                delta,
+                x,
+                y,
+            } => {
+                if self.ctrl_held {
+                    // scrolling up makes it faster
+                    return self.game.change_speed(delta < 0.0);
+                }
+
+                self.following = false;
+                self.camera.zoom_at((x as f32, y as f32), delta);
+            }
+
+            &Event::MouseMoved { x, y } => {
+                self.mouse_position = Some((x, y));
+
+                if let Some((last_x, last_y)) = self.panning.replace((x, y)) {
+                    self.following = false;
+                    self.camera.pan((x - last_x) as f32, (y - last_y) as f32);
+                }
+
+                let cell = self.cell_at(x, y);
+                self.hovered_cell = Some(cell);
+
+                if let Some((_, corner)) = self.selection.as_mut().filter(|_| self.selecting) {
+                    *corner = cell;
+                }
+
+                if self.button_pressed {
+                    self.toggle_cell(x, y);
+                }
+            }
+
+            Event::MouseLeft => {
+                self.hovered_cell = None;
+                self.mouse_position = None;
+            }
+
+            _ => {}
+        }
+
+        None
+    }
+
+    fn is_hexagonal(&self) -> bool {
+        self.game.lenia.is_none() && self.game.automaton.neighbourhood() == Neighbourhood::Hexagonal
+    }
+
+    /// Counts of the known objects on the grid, `None` while they aren't
+    /// being counted
+    pub fn census(&self) -> Option<String> {
+        Some(self.census.as_ref()?.count(&self.game.grid))
+    }
+
+    /// Number and speeds of the spaceships in the current generation, `None`
+    /// while they aren't being tracked
+    pub fn spaceship_summary(&self) -> Option<String> {
+        self.tracking.spaceship_tracker.as_ref()?;
+
+        let mut speeds = self
+            .tracking
+            .spaceships
+            .iter()
+            .map(Spaceship::speed)
+            .collect::<Vec<_>>();
+        speeds.sort();
+        speeds.dedup();
+
+        Some(match self.tracking.spaceships.len() {
+            0 => text("none"),
+            count => format!("{count} ({})", speeds.join(", ")),
+        })
+    }
+
+    /// Top, left, bottom and right row/column of the selection, clipped to
+    /// the grid
+    fn selection_bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        let ((row_a, col_a), (row_b, col_b)) = self.selection?;
+        let (rows, cols) = self.game.grid.size();
+        let clamp = |value: i32, len: usize| value.clamp(0, len as i32 - 1) as usize;
+
+        Some((
+            clamp(row_a.min(row_b), rows),
+            clamp(col_a.min(col_b), cols),
+            clamp(row_a.max(row_b), rows),
+            clamp(col_a.max(col_b), cols),
+        ))
+    }
+
+    /// Copies the selection, or the whole grid without one, to the clipboard as RLE
+    pub fn copy(&mut self) -> Option<String> {
+        let (rows, cols) = self.game.grid.size();
+        let (top, left, bottom, right) =
+            self.selection_bounds()
+                .unwrap_or((0, 0, rows - 1, cols - 1));
+
+        let mut cells = Grid::new(bottom - top + 1, right - left + 1);
+        for row in top..=bottom {
+            for col in left..=right {
+                cells[row - top][col - left] = self.game.grid[row][col];
+            }
+        }
+
+        clipboard::set_string(&rle::encode(
+            &cells,
+            &self.game.automaton.to_string(),
+            &self.game.info,
+        ));
+        if cells.size() == (rows, cols) {
+            self.saved = Some(period::hash(&self.game.grid));
+        }
+
+        Some(fill("Copied {}x{} cells", &[&cells.cols(), &cells.rows()]))
+    }
+
+    /// Whether the action can go ahead, it has to be requested twice in a
+    /// row if it would throw away a grid which wasn't saved
+    pub fn confirm(&mut self, action: Confirm) -> bool {
+        let unsaved = !self.game.is_clear() && self.saved != Some(period::hash(&self.game.grid));
+
+        if !unsaved || self.confirming == Some(action) {
+            self.confirming = None;
+            true
+        } else {
+            self.confirming = Some(action);
+            false
+        }
+    }
+
+    /// Pastes an RLE pattern from the clipboard at the top left corner of the
+    /// selection, or at the hovered cell without one, switching to its rule
+    pub fn paste(&mut self) -> Option<String> {
+        let pattern = match rle::parse(&clipboard::get_string()) {
+            Ok(pattern) => pattern,
+            Err(err) => return Some(fill("Cannot paste: {}", &[&err])),
+        };
+
+        if let Some(automaton) = pattern.rule.as_deref().and_then(|rule| rule.parse().ok()) {
+            if automaton != self.game.automaton {
+                self.game.set_automaton(automaton);
+            }
+        }
+
+        let (top, left) = match self.selection_bounds() {
+            Some((top, left, ..)) => (top, left),
+            None => self.hovered_cell.map_or((0, 0), |(row, col)| {
+                (row.max(0) as usize, col.max(0) as usize)
+            }),
+        };
+
+        pattern.stamp(
+            &mut self.game.grid,
+            top as i64,
+            left as i64,
+            self.game.automaton.states(),
+        );
+
+        self.shelf.add(
+            pattern.info.name.as_deref().unwrap_or(tr("Pasted")),
+            &pattern,
+        );
+
+        // a pattern pasted onto one nobody named yet gives it its name
+        if self.game.info.is_empty() {
+            self.game.info = pattern.info.clone();
+        }
+
+        Some(fill(
+            "Pasted {}x{} cells",
+            &[&pattern.cells.cols(), &pattern.cells.rows()],
+        ))
+    }
+
+    /// Cells of the first and the second player while playing Immigration
+    pub fn player_counts(&self) -> Option<(usize, usize)> {
+        if self.game.automaton != Automaton::Immigration || self.game.lenia.is_some() {
+            return None;
+        }
+
+        Some(
+            self.game
+                .grid
+                .iter()
+                .fold((0, 0), |(first, second), cell| match cell.0 {
+                    1 => (first + 1, second),
+                    2 => (first, second + 1),
+                    _ => (first, second),
+                }),
+        )
+    }
+
+    /// Which layer is active, `None` with just a single layer
+    pub fn layer_description(&self) -> Option<String> {
+        (self.game.layers.len() > 1)
+            .then(|| format!("{}/{}", self.game.active_layer + 1, self.game.layers.len()))
+    }
+
+    pub fn mode(&self) -> &'static str {
+        match (self.game.playing, self.editing_rule) {
+            (_, true) => tr("editing rule"),
+            (true, _) => tr("playing"),
+            (false, _) => tr("paused"),
+        }
+    }
+
+    /// Size of the status text relative to the default one
+    pub fn font_scale(&self) -> f32 {
+        if self.high_contrast {
+            self.font_scale * HIGH_CONTRAST_FONT_SCALE
+        } else {
+            self.font_scale
+        }
+    }
+
+    fn hex_radius(&self) -> f32 {
+        hex::radius(
+            self.game.grid.rows(),
+            self.game.grid.cols(),
+            WIDTH as f32,
+            HEIGHT as f32,
+        )
+    }
+
+    /// Row and column of the cell under the given window coordinates
+    pub fn cell_at(&self, x: i32, y: i32) -> (i32, i32) {
+        let (x, y) = self.camera.to_world((x as f32, y as f32));
+
+        if self.is_hexagonal() {
+            return hex::cell_at(x, y, self.hex_radius());
+        }
+
+        let (width, height) = self.cell_size();
+
+        ((y / height).floor() as i32, (x / width).floor() as i32)
+    }
+
+    /// World coordinates of the centers of the live cells
+    fn live_positions(&self) -> Vec<(f32, f32)> {
+        match &self.game.lenia {
+            // continuous cells count as alive once they are more than half
+            // way there
+            Some(lenia) => lenia
+                .grid
+                .indexed_iter()
+                .filter(|(_, value)| **value >= 0.5)
+                .map(|((row, col), _)| self.cell_center(row, col))
+                .collect(),
+            None => self
+                .game
+                .grid
+                .indexed_iter()
+                .filter(|(_, cell)| **cell != CellState::DEAD)
+                .map(|((row, col), _)| self.cell_center(row, col))
+                .collect(),
+        }
+    }
+
+    /// World rectangle around all of the live cells, `None` if there are none
+    fn live_bounds(&self) -> Option<camera::Rect> {
+        let live = self.live_positions();
+        let &(x, y) = live.first()?;
+        let (mut left, mut top, mut right, mut bottom) = (x, y, x, y);
+
+        for &(x, y) in &live {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+
+        // the positions are the centers of the cells
+        let (width, height) = self.cell_size();
+        Some((
+            left - width / 2.0,
+            top - height / 2.0,
+            right - left + width,
+            bottom - top + height,
+        ))
+    }
+
+    /// World coordinates of the center of a cell
+    pub fn cell_center(&self, row: usize, col: usize) -> (f32, f32) {
+        if self.is_hexagonal() {
+            return hex::cell_center(row, col, self.hex_radius());
+        }
+
+        let (width, height) = self.cell_size();
+
+        ((col as f32 + 0.5) * width, (row as f32 + 0.5) * height)
+    }
+
+    /// Places an ant on the cell under the cursor, or removes the ant already there
+    pub fn toggle_ant(&mut self, x: i32, y: i32) {
+        let (row_idx, col_idx) = self.cell_at(x, y);
+        if self.game.grid.get(row_idx as _, col_idx as _).is_none() {
+            return;
+        }
+
+        let (row_idx, col_idx) = (row_idx as usize, col_idx as usize);
+        match self
+            .game
+            .ants
+            .iter()
+            .position(|ant| ant.row == row_idx && ant.col == col_idx)
+        {
+            Some(idx) => {
+                self.game.ants.remove(idx);
+            }
+
+            None => self.game.ants.push(Ant::new(row_idx, col_idx)),
+        }
+    }
+
+    /// Starts painting cells with the brush while a button is held
+    fn start_stroke(&mut self, x: i32, y: i32) {
+        self.button_pressed = true;
+
+        if self.game.playing && self.edit_policy == EditPolicy::Pause {
+            self.game.playing = false;
+            self.resume_after_edit = true;
+        }
+
+        self.toggle_cell(x, y);
+    }
+
+    fn end_stroke(&mut self) {
+        self.button_pressed = false;
+        self.toggled_cell = (-1, -1);
+
+        // the whole stroke lands between two steps
+        for (row, col) in std::mem::take(&mut self.pending_edits) {
+            self.toggle_cell_at(row, col);
+        }
+
+        if std::mem::take(&mut self.resume_after_edit) {
+            self.game.playing = true;
+        }
+    }
+
+    pub fn toggle_cell(&mut self, x: i32, y: i32) {
+        let (row_idx, col_idx) = self.cell_at(x, y);
+
+        if self.toggled_cell == (row_idx, col_idx) {
+            return;
+        }
+        self.toggled_cell = (row_idx, col_idx);
+
+        // only reachable while autoplaying with the queue policy
+        if self.game.playing {
+            match self
+                .pending_edits
+                .iter()
+                .position(|cell| *cell == (row_idx, col_idx))
+            {
+                Some(idx) => {
+                    self.pending_edits.remove(idx);
+                }
+                None => self.pending_edits.push((row_idx, col_idx)),
+            }
+        } else {
+            self.toggle_cell_at(row_idx, col_idx);
+        }
+    }
+
+    fn toggle_cell_at(&mut self, row: i32, col: i32) {
+        if let Some(lenia) = &mut self.game.lenia {
+            if let Some(value) = lenia.grid.get_mut(row as _, col as _) {
+                *value = if *value >= 0.5 { 0.0 } else { 1.0 };
+                self.game.edits += 1;
+            }
+        } else if let Some(cell) = self.game.grid.get_mut(row as _, col as _) {
+            cell.toggle(self.brush);
+            self.game.edits += 1;
+            let state = cell.0;
+
+            if let Some(age) = self.tracking.ages.get_mut(row as _, col as _) {
+                *age = 0;
+            }
+
+            if let Some(session) = self.session.as_mut().filter(|session| !session.is_host()) {
+                session.send(&net::Message::Set {
+                    row: row as _,
+                    col: col as _,
+                    state,
+                });
+
+                // the host makes the same change, no need to send the board
+                session.mark_shared(
+                    &self.game.grid,
+                    &self.game.automaton.to_string(),
+                    self.game.generation,
+                );
+            }
+        }
+    }
+
+    pub fn toggle_play(&mut self) {
+        // the host decides and sends the new state back
+        if let Some(session) = self.session.as_ref().filter(|_| self.is_client()) {
+            session.send(if self.game.playing {
+                &net::Message::Pause
+            } else {
+                &net::Message::Play
+            });
+            return;
+        }
+
+        self.game.playing = !self.game.playing;
+        self.elapsed_time = Time::ZERO;
+    }
+
+    /// Hosts or joins a shared board, returns what happened
+    pub fn start_session(&mut self, option: &SessionOption) -> String {
+        let result = match option {
+            SessionOption::Host(addr) => Session::host(addr.as_str())
+                .map(|session| (session, fill("Hosting on {}", &[addr])))
+                .map_err(|err| fill("Couldn't host on {}: {}", &[addr, &err])),
+            SessionOption::Join(addr) => Session::join(addr.as_str())
+                .map(|session| (session, fill("Joined {}", &[addr])))
+                .map_err(|err| fill("Couldn't join {}: {}", &[addr, &err])),
+        };
+
+        match result {
+            Ok((session, msg)) => {
+                self.session = Some(session);
+                msg
+            }
+            Err(err) => err,
+        }
+    }
+
+    fn is_client(&self) -> bool {
+        self.session
+            .as_ref()
+            .is_some_and(|session| !session.is_host())
+    }
+
+    pub fn session_description(&self) -> Option<String> {
+        let session = self.session.as_ref()?;
+
+        Some(if session.is_host() {
+            fill("hosting, {} joined", &[&session.peers()])
+        } else {
+            text("joined")
+        })
+    }
+
+    fn toggle_tutorial(&mut self) -> Option<String> {
+        if self.tutorial.take().is_some() {
+            return Some(text("Left the tutorial"));
+        }
+
+        let mut tutorial = Tutorial::new(tutorial::INTRODUCTION);
+        self.set_up(tutorial.setup());
+        tutorial.begin(self.progress());
+        self.tutorial = Some(tutorial);
+        None
+    }
+
+    fn progress(&self) -> Progress {
+        Progress {
+            generation: self.game.generation,
+            playing: self.game.playing,
+            edits: self.game.edits,
+        }
+    }
+
+    /// Prepares the grid for a lesson of the tutorial
+    fn set_up(&mut self, setup: Setup) {
+        match setup {
+            Setup::Keep => {}
+            Setup::Clear => self.game.reset(),
+            Setup::Place(name) => {
+                self.game.playing = false;
+                if let Ok(pattern) = patterns::load(name) {
+                    self.game.load_centered(pattern, name);
+                }
+            }
+        }
+
+        self.sync();
+    }
+
+    /// Current prompt of the tutorial, `None` outside of it
+    pub fn tutorial_prompt(&self) -> Option<String> {
+        self.tutorial.as_ref().map(Tutorial::prompt)
+    }
+
+    /// Starts the next lesson of the tutorial once the current one is done,
+    /// returns a message once the last one is
+    pub fn update_tutorial(&mut self) -> Option<String> {
+        let progress = self.progress();
+
+        match self.tutorial.as_mut()?.update(progress) {
+            Advance::Stay => None,
+            Advance::Start(setup) => {
+                self.set_up(setup);
+                let progress = self.progress();
+                self.tutorial.as_mut()?.begin(progress);
+                None
+            }
+            Advance::Finish => {
+                self.tutorial = None;
+                Some(text("Tutorial finished, have fun!"))
+            }
+        }
+    }
+
+    /// Applies what the other players sent and, on the host, sends them the
+    /// board if it changed. Optionally returns a message to show.
+    pub fn update_session(&mut self) -> Option<String> {
+        let session = self.session.as_mut()?;
+        let is_host = session.is_host();
+        let mut msg = None;
+
+        // every other edit of a client goes to the host as the whole board,
+        // before a board from the host replaces it
+        if !is_host {
+            session.share_board(
+                &self.game.grid,
+                &self.game.automaton.to_string(),
+                self.game.generation,
+            );
+        }
+
+        for event in session.receive() {
+            match event {
+                net::Event::Message(message) => {
+                    msg = self.receive(message, is_host).or(msg);
+                }
+                net::Event::Disconnected if is_host => msg = Some(text("A player left")),
+                net::Event::Disconnected => {
+                    self.session = None;
+                    return Some(text("Lost the connection to the host"));
+                }
+            }
+        }
+
+        if let Some(session) = self.session.as_mut().filter(|_| is_host) {
+            session.share(
+                &self.game.grid,
+                &self.game.automaton.to_string(),
+                self.game.generation,
+                self.game.playing,
+            );
+        }
+
+        msg
+    }
+
+    /// Messages a host doesn't expect from a client, or the other way
+    /// around, are ignored
+    fn receive(&mut self, message: net::Message, is_host: bool) -> Option<String> {
+        match message {
+            net::Message::Hello if is_host => {
+                if let Some(session) = &mut self.session {
+                    session.reshare();
+                }
+                return Some(text("A player joined"));
+            }
+            net::Message::Set { row, col, state }
+                if is_host && state < self.game.automaton.states() =>
+            {
+                if let Some(cell) = self.game.grid.get_mut(row, col) {
+                    *cell = CellState(state);
+                }
+                if let Some(age) = self.tracking.ages.get_mut(row, col) {
+                    *age = 0;
+                }
+            }
+            net::Message::Play | net::Message::Pause if is_host => {
+                self.game.playing = message == net::Message::Play;
+                self.elapsed_time = Time::ZERO;
+            }
+            net::Message::Step if is_host => self.step(),
+            net::Message::Play | net::Message::Pause => {
+                self.game.playing = message == net::Message::Play;
+            }
+            net::Message::Sync {
+                generation,
+                pattern,
+            } => return self.apply_sync(generation, &pattern, is_host),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Replaces the board with the one the host sent, or on the host with
+    /// the one a client edited
+    fn apply_sync(&mut self, generation: u64, pattern: &str, is_host: bool) -> Option<String> {
+        let pattern = match rle::parse(pattern) {
+            Ok(pattern) => pattern,
+            Err(err) if is_host => return Some(fill("Invalid board from a player: {}", &[&err])),
+            Err(err) => return Some(fill("Invalid board from the host: {}", &[&err])),
+        };
+
+        let (rows, cols) = pattern.cells.size();
+        let sizes = 1..=command::MAX_GRID_SIZE;
+        if !sizes.contains(&rows) || !sizes.contains(&cols) {
+            return Some(if is_host {
+                fill(
+                    "Invalid board from a player: {} by {} cells",
+                    &[&rows, &cols],
+                )
+            } else {
+                fill(
+                    "Invalid board from the host: {} by {} cells",
+                    &[&rows, &cols],
+                )
+            });
+        }
+
+        self.game.adopt(&pattern, generation);
+        self.tracking.stepped(&self.game);
+        self.sync();
+
+        // the host sends the board on to every client with its next share
+        if let Some(session) = self.session.as_mut().filter(|_| !is_host) {
+            session.mark_shared(
+                &self.game.grid,
+                &self.game.automaton.to_string(),
+                self.game.generation,
+            );
+        }
+        None
+    }
+
+    /// Applies an action triggered through the playback controls
+    pub fn apply(&mut self, action: HudAction) -> Option<String> {
+        let msg = self.apply_action(action);
+        self.sync();
+        msg
+    }
+
+    fn apply_action(&mut self, action: HudAction) -> Option<String> {
+        match action {
+            HudAction::TogglePlay => self.toggle_play(),
+            HudAction::Step => self.manual_step(),
+            HudAction::StepBack => return self.game.step_back(),
+            HudAction::Seek(generation) => {
+                self.game.playing = false;
+
+                if generation != self.game.generation {
+                    return self.game.seek(generation);
+                }
+            }
+            HudAction::SetSpeed(time) => self.game.set_speed(to_duration(time)),
+            HudAction::Eval(code) => return self.eval(&code),
+            HudAction::Command(line) => {
+                return match command::parse(&line) {
+                    Ok(command) => self.run(command),
+                    Err(err) => Some(err),
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Stamps a pattern of the shelf centered on the cell under the window
+    /// coordinates
+    fn place(&mut self, item: usize, x: i32, y: i32) -> Option<String> {
+        if self.game.lenia.is_some() {
+            return Some(text("Patterns can't be placed in lenia mode"));
+        }
+
+        let (row, col) = self.cell_at(x, y);
+        let pattern = self.shelf.pattern(item)?;
+        let (rows, cols) = pattern.cells.size();
+        pattern.stamp(
+            &mut self.game.grid,
+            row as i64 - rows as i64 / 2,
+            col as i64 - cols as i64 / 2,
+            self.game.automaton.states(),
+        );
+        self.game.edits += 1;
+        None
+    }
+
+    /// Runs the commands only the window has and hands the others to the
+    /// game
+    pub fn run(&mut self, command: Command) -> Option<String> {
+        let msg = match command {
+            Command::Step(count) => self.step_forward(count),
+            #[cfg(feature = "serde")]
+            Command::Save(_) if self.game.lenia.is_some() => {
+                Some(text("Leave continuous mode first"))
+            }
+            #[cfg(feature = "serde")]
+            Command::Save(path) => Some(
+                match self.game.save_session(self.camera).save(path.as_ref()) {
+                    Ok(()) => fill("Saved {}", &[&path]),
+                    Err(err) => err,
+                },
+            ),
+            #[cfg(feature = "serde")]
+            Command::Restore(path) => Some(match snapshot::Session::load(path.as_ref()) {
+                Ok(session) => {
+                    self.restore_session(session);
+                    fill("Restored {}", &[&path])
+                }
+                Err(err) => err,
+            }),
+            Command::Contrast(high_contrast) => {
+                self.high_contrast = high_contrast;
+                None
+            }
+            Command::FontScale(scale) => {
+                self.font_scale = scale;
+                None
+            }
+            // only the window has the tutorial
+            Command::Help => Some(fill(
+                "{}. Ctrl + T starts the tutorial",
+                &[&text(command::HELP)],
+            )),
+            command => self.game.run(command),
+        };
+
+        self.sync();
+        msg
+    }
+
+    /// Runs a line typed into the console as a script
+    #[cfg(feature = "scripting")]
+    pub fn eval(&mut self, code: &str) -> Option<String> {
+        match self.run_script(script::Source::Code(code)) {
+            Ok(output) => Some(output).filter(|output| !output.is_empty()),
+            Err(err) => Some(err),
+        }
+    }
+
+    #[cfg(not(feature = "scripting"))]
+    pub fn eval(&mut self, _code: &str) -> Option<String> {
+        Some(text(
+            "Built without scripting, rebuild with `--features scripting`",
+        ))
+    }
+
+    /// Runs the script given with `--script` again
+    #[cfg(feature = "scripting")]
+    pub fn rerun_script(&mut self) -> Option<String> {
+        let path = self.script.clone()?;
+
+        match self.run_script(script::Source::File(&path)) {
+            Ok(output) => Some(output).filter(|output| !output.is_empty()),
+            Err(err) => Some(format!("{}: {err}", path.display())),
+        }
+    }
+
+    #[cfg(feature = "scripting")]
+    fn run_script(&mut self, source: script::Source) -> Result<String, String> {
+        let output = self.game.run_script(source);
+        self.sync();
+        output
+    }
+
+    /// Moves `count` generations forward, through the recorded generations
+    /// first and by stepping once they run out
+    pub fn step_forward(&mut self, count: u64) -> Option<String> {
+        // clients have no history and only get to the next generation once
+        // the host sends it back
+        if let Some(session) = self.session.as_ref().filter(|_| self.is_client()) {
+            for _ in 0..count {
+                session.send(&net::Message::Step);
+            }
+            return None;
+        }
+
+        let tracking = &mut self.tracking;
+        let msg = self.game.step_forward(count, |game| tracking.stepped(game));
+        self.sync();
+        msg
+    }
+
+    /// Replaces the whole session with a snapshot, which has to be
+    /// consistent
+    #[cfg(feature = "serde")]
+    fn restore_session(&mut self, session: snapshot::Session) {
+        self.camera = session.camera;
+        self.following = false;
+        self.game.restore_session(session);
+    }
+
+    /// Steps once, first showing what is about to change while explaining
+    /// steps. Stepping again during that applies the step right away.
+    pub fn manual_step(&mut self) {
+        if !self.explaining || self.game.lenia.is_some() || self.explained_step.take().is_some() {
+            self.step();
+            return;
+        }
+
+        // stepped the way the game steps, so the ants are part of it
+        let (mut next, mut ants) = (self.game.grid.clone(), self.game.ants.clone());
+        history::advance(
+            &mut next,
+            &mut ants,
+            &self.game.automaton,
+            self.game.automaton_enabled,
+            self.game.topology,
+            self.game.noise,
+            &mut engine::naive::Stepper::new(),
+        );
+        self.explained_step = Some((next, self.game.fingerprint(), EXPLAIN_DURATION));
+    }
+
+    pub fn step(&mut self) {
+        if let Some(session) = self.session.as_ref().filter(|_| self.is_client()) {
+            session.send(&net::Message::Step);
+            return;
+        }
+
+        self.game.step();
+        self.tracking.stepped(&self.game);
+    }
+
+    /// Steps autoplay once enough time has passed since its last step and
+    /// moves the camera along with the live cells while following them
+    pub fn update(&mut self, dt: Time) {
+        // clients show the steps of the host instead
+        if self.game.playing && !self.is_client() {
+            self.elapsed_time += dt;
+
+            let time_bw_steps = to_time(self.game.time_bw_steps);
+            if self.elapsed_time > time_bw_steps {
+                self.elapsed_time %= time_bw_steps;
+                (0..self.game.steps_per_frame()).for_each(|_| self.step());
+            }
+        }
+
+        if let Some((_, from, remaining)) = &mut self.explained_step {
+            *remaining -= dt;
+
+            if *from != self.game.fingerprint() {
+                self.explained_step = None;
+            } else if *remaining <= Time::ZERO {
+                self.explained_step = None;
+                self.step();
+            }
+        }
+
+        if self.following {
+            if let Some(bounds) = self.live_bounds() {
+                self.camera.follow(bounds, dt.as_seconds());
+            }
+        }
+    }
+
+    pub fn draw(&mut self, renderer: &mut impl Renderer) {
+        let (width, height) = self.cell_size();
+        let square = |row: usize, col: usize, color: Color| render::Cell {
+            shape: render::Shape::Rect { width, height },
+            position: (col as f32 * width, row as f32 * height),
+            color,
+        };
+
+        let mut cells = Vec::with_capacity(self.game.grid.rows() * self.game.grid.cols());
+
+        if let Some(lenia) = &self.game.lenia {
+            cells.extend(
+                lenia
+                    .grid
+                    .indexed_iter()
+                    .map(|((row, col), value)| square(row, col, Lenia::color(*value))),
+            );
+        } else if self.is_hexagonal() {
+            let radius = self.hex_radius();
+
+            cells.extend(
+                self.game
+                    .grid
+                    .indexed_iter()
+                    .map(|((row, col), cell)| render::Cell {
+                        shape: render::Shape::Polygon {
+                            radius,
+                            sides: 6,
+                            rotation: 0.0,
+                        },
+                        position: hex::cell_center(row, col, radius),
+                        color: self.game.palette.color(&self.game.automaton, *cell),
+                    }),
+            );
+        } else if let Some(size) = self.super_cell_size() {
+            // the board of the cells is rebuilt once they are large enough
+            self.drawn = None;
+
+            let (rows, cols) = self.game.grid.size();
+            let layout = BoardLayout {
+                rows: rows.div_ceil(size),
+                cols: cols.div_ceil(size),
+                cell_size: (size as f32 * width, size as f32 * height),
+                spacing: (size as f32 * width, size as f32 * height),
+            };
+            renderer.update_board(layout, &self.super_cell_changes(size, layout));
+            renderer.draw_board(&self.camera);
+        } else {
+            self.super_cells = None;
+
+            let layout = BoardLayout {
+                rows: self.game.grid.rows(),
+                cols: self.game.grid.cols(),
+                cell_size: (width, height),
+                spacing: (width, height),
+            };
+            renderer.update_board(layout, &self.board_changes(layout));
+            renderer.draw_board(&self.camera);
+
+            if self.grid_lines || self.high_contrast {
+                self.grid_lines(&mut cells);
+            }
+        }
+
+        let radius = if self.is_hexagonal() {
+            self.hex_radius() * 0.8
+        } else {
+            width.min(height) / 2.0
+        };
+
+        cells.extend(self.game.ants.iter().map(|ant| render::Cell {
+            shape: render::Shape::Polygon {
+                radius,
+                sides: 3,
+                rotation: ant.direction.angle(),
+            },
+            position: self.cell_center(ant.row, ant.col),
+            color: Color::RED,
+        }));
+
+        if self.show_layers && self.game.lenia.is_none() {
+            for (idx, color) in LAYER_COLORS
+                .into_iter()
+                .enumerate()
+                .take(self.game.layers.len())
+            {
+                if idx == self.game.active_layer {
+                    continue;
+                }
+
+                cells.extend(
+                    self.game.layers[idx]
+                        .indexed_iter()
+                        .filter(|(_, cell)| **cell != CellState::DEAD)
+                        .filter_map(|((row, col), _)| self.cell_overlay(row as _, col as _, color)),
+                );
+            }
+        }
+
+        if let Some((top, left, bottom, right)) = self.selection_bounds() {
+            for row in top..=bottom {
+                for col in left..=right {
+                    cells.extend(self.cell_overlay(
+                        row as _,
+                        col as _,
+                        Color::rgba(80, 120, 255, 70),
+                    ));
+                }
+            }
+        }
+
+        for (row, col, _) in self
+            .tracking
+            .spaceships
+            .iter()
+            .flat_map(|ship| &ship.cluster.cells)
+        {
+            cells.extend(self.cell_overlay(*row as _, *col as _, Color::rgba(0, 255, 255, 90)));
+        }
+
+        for &(row, col) in &self.pending_edits {
+            cells.extend(self.cell_overlay(row, col, Color::rgba(255, 255, 255, 150)));
+        }
+
+        if let Some((next, _, _)) = &self.explained_step {
+            let automaton = self.game.automaton;
+
+            for (((row, col), cell), next) in self.game.grid.indexed_iter().zip(next.iter()) {
+                let color = match (automaton.is_alive(*cell), automaton.is_alive(*next)) {
+                    (false, true) => self.game.palette.birth(),
+                    (true, false) => self.game.palette.death(),
+                    _ => continue,
+                };
+
+                cells.extend(self.cell_overlay(row as _, col as _, color));
+            }
+        }
+
+        if let Some((row, col)) = self.hovered_cell {
+            match self.shelf.picked() {
+                // where the pattern would be stamped
+                Some(pattern) => {
+                    let (rows, cols) = pattern.cells.size();
+                    let (top, left) = (row - rows as i32 / 2, col - cols as i32 / 2);
+
+                    cells.extend(
+                        pattern
+                            .cells
+                            .indexed_iter()
+                            .filter(|(_, cell)| **cell != CellState::DEAD)
+                            .filter_map(|((row, col), _)| {
+                                self.cell_overlay(
+                                    top + row as i32,
+                                    left + col as i32,
+                                    Color::rgba(255, 255, 255, 110),
+                                )
+                            }),
+                    );
+                }
+                None => cells.extend(self.cell_overlay(row, col, Color::rgba(255, 255, 255, 70))),
+            }
+        }
+
+        let mut cells = cells
+            .into_iter()
+            .filter_map(|cell| self.camera.project(cell))
+            .collect::<Vec<_>>();
+
+        if self.camera.is_zoomed() {
+            let live = self.live_positions();
+            let viewport = self.camera.viewport();
+            Minimap::new(&live, viewport).draw(&live, viewport, &mut cells);
+        }
+
+        renderer.draw_cells(&cells);
+
+        if self.neighbour_counts && self.game.lenia.is_none() {
+            self.draw_neighbour_counts(renderer);
+        }
+
+        self.shelf.draw(renderer);
+
+        if self.show_info && !self.game.info.is_empty() {
+            draw_tooltip(renderer, (0, 0), &self.info_lines());
+        }
+
+        if Key::LAlt.is_pressed() || Key::RAlt.is_pressed() {
+            let lines = self
+                .hovered_cell
+                .and_then(|(row, col)| self.inspect(row, col));
+
+            if let Some((position, lines)) = self.mouse_position.zip(lines) {
+                draw_tooltip(renderer, position, &lines);
+            }
+        }
+    }
+
+    /// Cells which changed since the grid was last handed to the renderer,
+    /// or all of them if the layout or the colors changed
+    fn board_changes(&mut self, layout: BoardLayout) -> Vec<(usize, Color)> {
+        let color = |(idx, cell): (usize, &CellState)| {
+            (idx, self.game.palette.color(&self.game.automaton, *cell))
+        };
+
+        match &mut self.drawn {
+            Some((drawn_layout, automaton, palette, drawn))
+                if *drawn_layout == layout
+                    && *automaton == self.game.automaton
+                    && *palette == self.game.palette =>
+            {
+                let mut changes = Vec::new();
+                for (idx, (cell, before)) in self.game.grid.iter().zip(drawn.iter_mut()).enumerate()
+                {
+                    if cell != before {
+                        *before = *cell;
+                        changes.push(color((idx, cell)));
+                    }
+                }
+                changes
+            }
+            _ => {
+                self.drawn = Some((
+                    layout,
+                    self.game.automaton,
+                    self.game.palette,
+                    self.game.grid.clone(),
+                ));
+                self.game.grid.iter().enumerate().map(color).collect()
+            }
+        }
+    }
+
+    /// Cells on each side of the blocks drawn instead of the cells of the
+    /// square tiling, `None` while the cells are large enough to be drawn
+    pub fn super_cell_size(&self) -> Option<usize> {
+        if self.game.lenia.is_some() || self.is_hexagonal() {
+            return None;
+        }
+
+        let (width, height) = self.cell_size();
+        let size = width.min(height) * self.camera.zoom;
+        (size < MIN_CELL_SIZE).then(|| ((MIN_CELL_SIZE / size).ceil() as usize).next_power_of_two())
+    }
+
+    /// Blocks whose share of live cells changed since they were last handed
+    /// to the renderer, or all of them if the layout or the colors changed
+    fn super_cell_changes(&mut self, size: usize, layout: BoardLayout) -> Vec<(usize, Color)> {
+        let color = self
+            .game
+            .palette
+            .color(&self.game.automaton, CellState::ALIVE);
+
+        let automaton = self.game.automaton;
+        let super_cells = match &mut self.super_cells {
+            Some(super_cells)
+                if super_cells.grid.size() == self.game.grid.size()
+                    && super_cells.automaton == automaton =>
+            {
+                super_cells
+            }
+            _ => self.super_cells.insert(SuperCells {
+                size,
+                layout,
+                color,
+                grid: self.game.grid.clone(),
+                automaton,
+                mipmap: Mipmap::new(&self.game.grid, &automaton),
+            }),
+        };
+
+        let mut changed = Vec::new();
+        let cols = self.game.grid.cols();
+        for (idx, (cell, before)) in self
+            .game
+            .grid
+            .iter()
+            .zip(super_cells.grid.iter_mut())
+            .enumerate()
+        {
+            let alive = automaton.is_alive(*cell);
+            if alive != automaton.is_alive(*before) {
+                let (row, col) = (idx / cols, idx % cols);
+                super_cells.mipmap.update(row, col, alive);
+                changed.push((row / size) * layout.cols + col / size);
+            }
+            *before = *cell;
+        }
+
+        if super_cells.size != size || super_cells.layout != layout || super_cells.color != color {
+            super_cells.size = size;
+            super_cells.layout = layout;
+            super_cells.color = color;
+            changed = (0..layout.rows * layout.cols).collect();
+        } else {
+            changed.sort_unstable();
+            changed.dedup();
+        }
+
+        // the square root keeps a few live cells in a block visible
+        let shade = |density: f32| {
+            let scale = |channel: u8| (channel as f32 * density.sqrt()) as u8;
+            Color::rgb(scale(color.r), scale(color.g), scale(color.b))
+        };
+
+        changed
+            .into_iter()
+            .map(|idx| {
+                let (row, col) = (idx / layout.cols, idx % layout.cols);
+                (idx, shade(super_cells.mipmap.density(size, row, col)))
+            })
+            .collect()
+    }
+
+    /// Writes the number of alive neighbours into every cell in view which
+    /// has any, once the cells are large enough for it
+    fn draw_neighbour_counts(&self, renderer: &mut impl Renderer) {
+        let (width, height) = self.cell_size();
+        if width.min(height) * self.camera.zoom < MIN_COUNT_CELL_SIZE {
+            return;
+        }
+
+        let neighbourhood = self.game.automaton.neighbourhood();
+        let offsets = [neighbourhood.offsets(0), neighbourhood.offsets(1)];
+        let (left, top, view_width, view_height) = self.camera.viewport();
+
+        for (row, col) in self.game.grid.indexed_iter().map(|(cell, _)| cell) {
+            let (x, y) = self.cell_center(row, col);
+            if !(left..left + view_width).contains(&x) || !(top..top + view_height).contains(&y) {
+                continue;
+            }
+
+            let count = engine::naive::live_neighbours(
+                &self.game.automaton,
+                &self.game.grid,
+                self.game.topology,
+                row,
+                col,
+                &offsets[row & 1],
+            );
+            if count == 0 {
+                continue;
+            }
+
+            let (x, y) = self.camera.to_screen((x, y));
+            let text = count.to_string();
+            renderer.draw_text(
+                &text,
+                (
+                    x - text.len() as f32 * COUNT_FONT_SIZE as f32 * 0.3,
+                    y - COUNT_FONT_SIZE as f32 * 0.7,
+                ),
+                COUNT_FONT_SIZE,
+                Color::rgb(255, 140, 0),
+            );
+        }
+    }
+
+    /// Lines of the overlay showing the name and comments of the pattern
+    fn info_lines(&self) -> Vec<String> {
+        let name = self.game.info.name.as_deref().unwrap_or(tr("Untitled"));
+        let title = match &self.game.info.author {
+            Some(author) => fill("{} by {}", &[&name, author]),
+            None => name.to_string(),
+        };
+
+        [title]
+            .into_iter()
+            .chain(self.game.info.comments.iter().cloned())
+            .collect()
+    }
+
+    /// Lines of the tooltip describing a cell, `None` outside the grid
+    fn inspect(&self, row: i32, col: i32) -> Option<Vec<String>> {
+        if row < 0 || col < 0 {
+            return None;
+        }
+        let (row, col) = (row as usize, col as usize);
+        let position = fill("row {}, col {}", &[&row, &col]);
+
+        if let Some(lenia) = &self.game.lenia {
+            let value = lenia.grid.get(row, col)?;
+            return Some(vec![position, fill("value: {}", &[&format!("{value:.2}")])]);
+        }
+
+        let cell = *self.game.grid.get(row, col)?;
+        let age = self.tracking.ages.get(row, col).copied().unwrap_or(0);
+        let neighbours = engine::naive::live_neighbours(
+            &self.game.automaton,
+            &self.game.grid,
+            self.game.topology,
+            row,
+            col,
+            &self.game.automaton.neighbourhood().offsets(row),
+        );
+
+        Some(vec![
+            position,
+            fill("state: {}", &[&tr(self.game.automaton.state_name(cell))]),
+            if age == 1 {
+                fill("age: {} generation", &[&age])
+            } else {
+                fill("age: {} generations", &[&age])
+            },
+            fill("neighbours: {}", &[&neighbours]),
+        ])
+    }
+
+    /// Width and height of a cell of the square tiling
+    fn cell_size(&self) -> (f32, f32) {
+        (
+            WIDTH as f32 / self.game.grid.cols() as f32,
+            HEIGHT as f32 / self.game.grid.rows() as f32,
+        )
+    }
+
+    /// Adds the lines between the cells of the square tiling
+    fn grid_lines(&self, cells: &mut Vec<render::Cell>) {
+        static GREY: Color = Color::rgb(50, 50, 50);
+        static LIGHT_GREY: Color = Color::rgb(170, 170, 170);
+
+        let (rows, cols) = self.game.grid.size();
+        let (cell_width, cell_height) = self.cell_size();
+
+        let thickness = line_thickness(cell_width.min(cell_height));
+        if thickness == 0.0 {
+            return;
+        }
+
+        let (thickness, color) = if self.high_contrast {
+            (thickness * HIGH_CONTRAST_BORDERS, LIGHT_GREY)
+        } else {
+            (thickness, GREY)
+        };
+
+        let mut line = |x: f32, y: f32, width: f32, height: f32| {
+            cells.push(render::Cell {
+                shape: render::Shape::Rect { width, height },
+                position: (x, y),
+                color,
+            });
+        };
+
+        let (width, height) = (cols as f32 * cell_width, rows as f32 * cell_height);
+
+        for col in 0..=cols {
+            line(
+                col as f32 * cell_width - thickness / 2.0,
+                0.0,
+                thickness,
+                height,
+            );
+        }
+
+        for row in 0..=rows {
+            line(
+                0.0,
+                row as f32 * cell_height - thickness / 2.0,
+                width,
+                thickness,
+            );
+        }
+    }
+
+    /// Translucent highlight over a cell, none for cells outside the grid
+    fn cell_overlay(&self, row: i32, col: i32, color: Color) -> Option<render::Cell> {
+        if row < 0 || col < 0 || self.game.grid.get(row as usize, col as usize).is_none() {
+            return None;
+        }
+
+        let (x, y) = self.cell_center(row as _, col as _);
+
+        Some(if self.is_hexagonal() {
+            render::Cell {
+                shape: render::Shape::Polygon {
+                    radius: self.hex_radius(),
+                    sides: 6,
+                    rotation: 0.0,
+                },
+                position: (x, y),
+                color,
+            }
+        } else {
+            let (width, height) = self.cell_size();
+
+            render::Cell {
+                shape: render::Shape::Rect { width, height },
+                position: (x - width / 2.0, y - height / 2.0),
+                color,
+            }
+        })
+    }
+}
+
+/// The time between steps of the game in the time of SFML
+fn to_time(duration: Duration) -> Time {
+    Time::microseconds(duration.as_micros().try_into().unwrap_or(i64::MAX))
+}
+
+fn to_duration(time: Time) -> Duration {
+    Duration::from_micros(time.as_microseconds().max(0) as u64)
+}
+
+/// Draws the lines in a box next to the mouse cursor, kept inside the grid
+fn draw_tooltip(renderer: &mut impl Renderer, (x, y): (i32, i32), lines: &[String]) {
+    let longest = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = longest as f32 * TOOLTIP_CHAR_WIDTH + 2.0 * TOOLTIP_PADDING;
+    let height = lines.len() as f32 * TOOLTIP_LINE_HEIGHT + 2.0 * TOOLTIP_PADDING;
+
+    let left = (x as f32 + 16.0).min(WIDTH as f32 - width).max(0.0);
+    let top = (y as f32 + 16.0).min(HEIGHT as f32 - height).max(0.0);
+
+    renderer.draw_cells(&[render::Cell {
+        shape: render::Shape::Rect { width, height },
+        position: (left, top),
+        color: Color::rgba(20, 20, 20, 230),
+    }]);
+
+    for (idx, line) in lines.iter().enumerate() {
+        renderer.draw_text(
+            line,
+            (
+                left + TOOLTIP_PADDING,
+                top + TOOLTIP_PADDING + idx as f32 * TOOLTIP_LINE_HEIGHT,
+            ),
+            TOOLTIP_FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}
+
+/// Moves the FPS towards the rate of the last frame, weighted by how long the
+/// frame took so the smoothing doesn't depend on the frame rate
+fn smoothed_fps(fps: Option<f32>, dt: Time) -> Option<f32> {
+    let seconds = dt.as_seconds();
+    if seconds <= 0.0 {
+        return fps;
+    }
+
+    let current = 1.0 / seconds;
+    let weight = 1.0 - (-seconds / FPS_SMOOTHING.as_seconds()).exp();
+
+    Some(fps.map_or(current, |fps| fps + (current - fps) * weight))
+}
+
+/// Thickness of the lines between cells, thinner for smaller cells and
+/// hidden once the cells get too small for the lines not to swallow them
+fn line_thickness(cell_size: f32) -> f32 {
+    if cell_size < 6.0 {
+        0.0
+    } else {
+        (cell_size / 40.0).clamp(0.5, 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_square_grids_fill_the_window() {
+        let mut state = GameState::new(0);
+        state.game.resize(16, 64);
+
+        let (width, height) = state.cell_size();
+        assert_eq!((width, height), (WIDTH as f32 / 64.0, HEIGHT as f32 / 16.0));
+
+        assert_eq!(state.cell_at(0, 0), (0, 0));
+        assert_eq!(state.cell_at(WIDTH as i32 - 1, HEIGHT as i32 - 1), (15, 63));
+        assert_eq!(
+            state.cell_center(15, 63),
+            (WIDTH as f32 - width / 2.0, HEIGHT as f32 - height / 2.0)
+        );
+    }
+
+    #[test]
+    fn explained_steps_apply_what_they_showed() {
+        let mut state = GameState::new(0);
+        state.game.playing = false;
+        state.game.grid = Grid::new(16, 16);
+        (7..=9).for_each(|col| state.game.grid[8][col] = CellState::ALIVE);
+        state.game.ants = vec![Ant::new(2, 2)];
+        state.explaining = true;
+
+        state.manual_step();
+        let (shown, _, _) = state.explained_step.clone().unwrap();
+        assert!(shown[2][2].is_alive());
+        assert_eq!(state.game.generation, 0);
+
+        state.update(EXPLAIN_DURATION + Time::milliseconds(1));
+        assert!(state.explained_step.is_none());
+        assert!(state.game.grid == shown);
+        assert_eq!(state.game.generation, 1);
+
+        // edits before the step is applied make it outdated
+        state.manual_step();
+        state.toggle_cell_at(12, 12);
+        state.update(Time::milliseconds(1));
+        assert!(state.explained_step.is_none());
+        assert_eq!(state.game.generation, 1);
+    }
+}
@@ -0,0 +1,46 @@
+//! Geometry of the hexagonal tiling
+//!
+//! Cells are pointy-top hexagons laid out in "odd-r" offset coordinates,
+//! every odd row is shifted half a cell to the right.
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Circumradius of a cell so that a `rows` x `cols` grid fits in `width` x `height`
+pub fn radius(rows: usize, cols: usize, width: f32, height: f32) -> f32 {
+    let by_width = width / (SQRT_3 * (cols as f32 + 0.5));
+    let by_height = height / (1.5 * rows as f32 + 0.5);
+
+    by_width.min(by_height)
+}
+
+/// Center of the cell at the given row and column
+pub fn cell_center(row: usize, col: usize, radius: f32) -> (f32, f32) {
+    let x = SQRT_3 * radius * (col as f32 + 0.5 * (row % 2) as f32 + 0.5);
+    let y = 1.5 * radius * row as f32 + radius;
+
+    (x, y)
+}
+
+/// Row and column of the cell containing the point `(x, y)`
+pub fn cell_at(x: f32, y: f32, radius: f32) -> (i32, i32) {
+    let x = x - SQRT_3 * radius / 2.0;
+    let y = y - radius;
+
+    // fractional axial coordinates
+    let q = (SQRT_3 / 3.0 * x - y / 3.0) / radius;
+    let r = (2.0 / 3.0 * y) / radius;
+    let s = -q - r;
+
+    // round to the nearest hexagon, fixing up the component with the largest error
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+
+    let (q, r) = (rq as i32, rr as i32);
+    (r, q + (r - (r & 1)) / 2)
+}
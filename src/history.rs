@@ -0,0 +1,256 @@
+//! Recorded generations which can be jumped back and forth between
+//!
+//! Only every [`KEYFRAME_INTERVAL`]th generation is stored, the ones in
+//! between are re-simulated from the closest earlier keyframe. Any edit made
+//! between two steps starts a new keyframe so re-simulating never loses it.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+use grid::Grid;
+
+use crate::ant::Ant;
+use crate::automaton::Automaton;
+use crate::engine::naive::Stepper;
+use crate::noise::Noise;
+use crate::topology::Topology;
+use crate::CellState;
+
+pub const KEYFRAME_INTERVAL: u64 = 16;
+
+/// Oldest keyframes are dropped past this, bounding the history to roughly
+/// `KEYFRAME_INTERVAL * MAX_KEYFRAMES` generations
+pub const MAX_KEYFRAMES: usize = 64;
+
+/// Everything needed to recreate a generation
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    pub grid: Grid<CellState>,
+    pub ants: Vec<Ant>,
+    pub generation: u64,
+    pub automaton: Automaton,
+    pub automaton_enabled: bool,
+
+    /// Missing from snapshots saved before there were topologies
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub topology: Topology,
+
+    /// Missing from snapshots saved before there was noise
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub noise: Noise,
+}
+
+impl Snapshot {
+    pub fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new(
+            &self.grid,
+            &self.ants,
+            self.generation,
+            self.automaton,
+            self.automaton_enabled,
+            self.topology,
+            self.noise,
+        )
+    }
+}
+
+/// Identifies a generation without keeping a copy of it around
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Fingerprint {
+    hash: u64,
+    generation: u64,
+    automaton: Automaton,
+    automaton_enabled: bool,
+    topology: Topology,
+    noise: Noise,
+}
+
+impl Fingerprint {
+    pub fn new(
+        grid: &Grid<CellState>,
+        ants: &[Ant],
+        generation: u64,
+        automaton: Automaton,
+        automaton_enabled: bool,
+        topology: Topology,
+        noise: Noise,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        grid.cols().hash(&mut hasher);
+        grid.flatten().hash(&mut hasher);
+        ants.hash(&mut hasher);
+
+        Self {
+            hash: hasher.finish(),
+            generation,
+            automaton,
+            automaton_enabled,
+            topology,
+            noise,
+        }
+    }
+}
+
+/// Advances the grid and the ants by one generation, shared by the game and
+/// the re-simulation so both always agree. The stepper keeps the generation
+/// before.
+pub fn advance(
+    grid: &mut Grid<CellState>,
+    ants: &mut [Ant],
+    automaton: &Automaton,
+    automaton_enabled: bool,
+    topology: Topology,
+    noise: Noise,
+    stepper: &mut Stepper,
+) {
+    if automaton_enabled {
+        stepper.step(automaton, topology, grid);
+        noise.apply(automaton, stepper.previous(), grid);
+    } else {
+        stepper.keep(grid);
+    }
+
+    for ant in ants {
+        ant.step(grid);
+    }
+}
+
+#[derive(Default)]
+pub struct History {
+    /// Oldest keyframe at the front
+    keyframes: VecDeque<Snapshot>,
+
+    /// The generation after the last recorded step, anything else at that
+    /// generation was edited
+    tip: Option<Fingerprint>,
+
+    /// Latest generation which can be jumped to
+    end: u64,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+        self.tip = None;
+        self.end = 0;
+    }
+
+    /// Earliest and latest generation which can be jumped to, `None` if
+    /// nothing was recorded yet
+    pub fn range(&self) -> Option<(u64, u64)> {
+        Some((self.keyframes.front()?.generation, self.end))
+    }
+
+    /// Has to be called with the current generation right before it is
+    /// stepped
+    pub fn record(&mut self, current: Snapshot) {
+        self.checkpoint(current);
+    }
+
+    /// Whether [`History::record`] would do anything with the generation,
+    /// which saves copying it for every step
+    pub fn wants(&self, current: &Fingerprint) -> bool {
+        self.tip != Some(*current)
+            || self
+                .keyframes
+                .iter()
+                .rev()
+                .find(|keyframe| keyframe.generation <= current.generation)
+                .is_none_or(|keyframe| {
+                    current.generation - keyframe.generation >= KEYFRAME_INTERVAL
+                })
+    }
+
+    /// Whether the generation is the one the last step produced, which
+    /// means it wasn't edited since
+    pub fn continues(&self, current: &Fingerprint) -> bool {
+        self.tip == Some(*current)
+    }
+
+    /// Has to be called after every step with the generation it produced
+    pub fn stepped(&mut self, fingerprint: Fingerprint) {
+        self.end = self.end.max(fingerprint.generation);
+        self.tip = Some(fingerprint);
+    }
+
+    /// Stores the current generation as a keyframe if it can't be
+    /// re-simulated or the previous keyframe is too far back. Edits make the
+    /// recorded later generations unreachable so they are forgotten.
+    fn checkpoint(&mut self, current: Snapshot) {
+        let generation = current.generation;
+        let fingerprint = current.fingerprint();
+
+        if !self.wants(&fingerprint) {
+            return;
+        }
+
+        if self.tip != Some(fingerprint) {
+            self.keyframes
+                .retain(|keyframe| keyframe.generation < generation);
+            self.end = generation;
+        }
+
+        // later keyframes only remain if they were recorded after this one
+        let idx = self
+            .keyframes
+            .iter()
+            .position(|keyframe| keyframe.generation >= generation)
+            .unwrap_or(self.keyframes.len());
+
+        if self
+            .keyframes
+            .get(idx)
+            .is_some_and(|k| k.generation == generation)
+        {
+            return;
+        }
+
+        self.tip = Some(fingerprint);
+        self.keyframes.insert(idx, current);
+
+        if self.keyframes.len() > MAX_KEYFRAMES {
+            self.keyframes.pop_front();
+        }
+    }
+
+    /// Recreates the given generation, `current` is kept first so jumping
+    /// back to it later doesn't lose any edits to it
+    pub fn seek(&mut self, current: Snapshot, generation: u64) -> Option<Snapshot> {
+        self.checkpoint(current);
+
+        let (start, end) = self.range()?;
+        if !(start..=end).contains(&generation) {
+            return None;
+        }
+
+        let mut snapshot = self
+            .keyframes
+            .iter()
+            .rev()
+            .find(|keyframe| keyframe.generation <= generation)?
+            .clone();
+
+        let mut stepper = Stepper::new();
+        while snapshot.generation < generation {
+            advance(
+                &mut snapshot.grid,
+                &mut snapshot.ants,
+                &snapshot.automaton,
+                snapshot.automaton_enabled,
+                snapshot.topology,
+                snapshot.noise,
+                &mut stepper,
+            );
+            snapshot.generation += 1;
+        }
+
+        self.tip = Some(snapshot.fingerprint());
+        Some(snapshot)
+    }
+}
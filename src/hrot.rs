@@ -0,0 +1,346 @@
+//! The HROT notation of rules with larger neighbourhoods
+//!
+//! Both [`Rule`] and [`LtlRule`] are written as `R2,C0,S6-9,B7-8,NM`, a
+//! radius, the number of states, whether a cell counts itself (`M1`), the
+//! survival and birth counts and the neighbourhood. Counts are single numbers
+//! or ranges, several of them separated by commas. Ranges written the way
+//! Larger than Life rules usually are, `34..58`, are read as well.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::automaton::Automaton;
+use crate::ltl::{self, LtlRule};
+use crate::rule::{self, Neighbourhood, Rule};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hrot {
+    pub radius: u8,
+
+    /// Total number of states, `2` unless cells take a few generations to die
+    pub states: u8,
+
+    /// Whether a cell counts towards its own neighbour count
+    pub middle: bool,
+
+    pub von_neumann: bool,
+
+    /// Inclusive ranges of neighbour counts an alive cell survives with
+    pub survival: Vec<(u32, u32)>,
+
+    /// Inclusive ranges of neighbour counts a dead cell is born with
+    pub birth: Vec<(u32, u32)>,
+}
+
+impl Hrot {
+    /// The rule this describes, a [`Rule`] where the counts fit into its
+    /// masks and a [`LtlRule`] otherwise
+    pub fn automaton(&self) -> Result<Automaton, String> {
+        if self.radius <= rule::MAX_RADIUS && !self.middle {
+            self.rule().map(Automaton::Life)
+        } else {
+            self.ltl().map(Automaton::LargerThanLife)
+        }
+    }
+
+    fn rule(&self) -> Result<Rule, String> {
+        let mask = |ranges: &[(u32, u32)]| {
+            ranges.iter().try_fold(0u32, |mask, &(start, end)| {
+                if end < u32::BITS {
+                    Ok((start..=end).fold(mask, |mask, n| mask | 1 << n))
+                } else {
+                    Err(format!("Invalid neighbour count '{end}'"))
+                }
+            })
+        };
+
+        Ok(Rule {
+            birth: mask(&self.birth)?,
+            survival: mask(&self.survival)?,
+            states: self.states,
+            neighbourhood: if self.von_neumann {
+                Neighbourhood::VonNeumann(self.radius)
+            } else {
+                Neighbourhood::Moore(self.radius)
+            },
+        })
+    }
+
+    fn ltl(&self) -> Result<LtlRule, String> {
+        if self.von_neumann {
+            return Err(format!(
+                "The von Neumann neighbourhood only goes up to radius {}",
+                rule::MAX_RADIUS
+            ));
+        }
+
+        let range = |ranges: &[(u32, u32)]| {
+            match *ranges {
+            [range] => Ok(range),
+            _ => Err(format!(
+                "Rules with a radius above {} or M1 need exactly one range of survival and one of birth counts",
+                rule::MAX_RADIUS
+            )),
+        }
+        };
+
+        Ok(LtlRule {
+            radius: self.radius,
+            states: self.states,
+            middle: self.middle,
+            survival: range(&self.survival)?,
+            birth: range(&self.birth)?,
+        })
+    }
+}
+
+impl From<&Rule> for Hrot {
+    fn from(rule: &Rule) -> Self {
+        // runs of consecutive counts become ranges
+        let ranges = |mask: u32| {
+            let mut ranges = Vec::new();
+            let mut n = 0;
+
+            while n < u32::BITS {
+                if mask & 1 << n == 0 {
+                    n += 1;
+                    continue;
+                }
+
+                let start = n;
+                while n + 1 < u32::BITS && mask & 1 << (n + 1) != 0 {
+                    n += 1;
+                }
+
+                ranges.push((start, n));
+                n += 1;
+            }
+
+            ranges
+        };
+
+        Self {
+            radius: rule.neighbourhood.radius(),
+            states: rule.states,
+            middle: false,
+            von_neumann: matches!(rule.neighbourhood, Neighbourhood::VonNeumann(_)),
+            survival: ranges(rule.survival),
+            birth: ranges(rule.birth),
+        }
+    }
+}
+
+impl From<&LtlRule> for Hrot {
+    fn from(rule: &LtlRule) -> Self {
+        Self {
+            radius: rule.radius,
+            states: rule.states,
+            middle: rule.middle,
+            von_neumann: false,
+            survival: vec![rule.survival],
+            birth: vec![rule.birth],
+        }
+    }
+}
+
+impl fmt::Display for Hrot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts = |ranges: &[(u32, u32)]| {
+            ranges
+                .iter()
+                .map(|&(start, end)| {
+                    if start == end {
+                        start.to_string()
+                    } else {
+                        format!("{start}-{end}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        write!(
+            f,
+            "R{},C{},",
+            self.radius,
+            if self.states > 2 { self.states } else { 0 }
+        )?;
+
+        if self.middle {
+            f.write_str("M1,")?;
+        }
+
+        write!(
+            f,
+            "S{},B{},N{}",
+            counts(&self.survival),
+            counts(&self.birth),
+            if self.von_neumann { 'N' } else { 'M' }
+        )
+    }
+}
+
+impl FromStr for Hrot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let mut rule = Self {
+            radius: 1,
+            states: 2,
+            middle: false,
+            von_neumann: false,
+            survival: Vec::new(),
+            birth: Vec::new(),
+        };
+
+        // birth/survival counts may continue over several comma separated parts
+        let mut last_was_birth = None;
+
+        let add_counts = |ranges: &mut Vec<(u32, u32)>, part: &str| -> Result<(), String> {
+            if part.is_empty() {
+                return Ok(());
+            }
+
+            let (start, end) = part
+                .split_once("..")
+                .or_else(|| part.split_once('-'))
+                .unwrap_or((part, part));
+
+            match (start.parse::<u32>(), end.parse::<u32>()) {
+                (Ok(start), Ok(end)) if start <= end => {
+                    ranges.push((start, end));
+                    Ok(())
+                }
+                _ => Err(format!("Invalid neighbour count '{part}'")),
+            }
+        };
+
+        for part in s.split(',') {
+            let value = part.get(1..).unwrap_or_default();
+
+            match part.chars().next().map(|c| c.to_ascii_uppercase()) {
+                Some('R') => {
+                    rule.radius = value
+                        .parse()
+                        .ok()
+                        .filter(|radius| (1..=ltl::MAX_RADIUS).contains(radius))
+                        .ok_or_else(|| {
+                            format!("Radius must be between 1 and {}", ltl::MAX_RADIUS)
+                        })?;
+                    last_was_birth = None;
+                }
+                Some('C') => {
+                    rule.states = match value.parse() {
+                        Ok(0) => 2,
+                        Ok(states) if states >= 2 => states,
+                        _ => return Err(format!("Invalid number of states '{part}'")),
+                    };
+                    last_was_birth = None;
+                }
+                Some('M') => {
+                    rule.middle = match value {
+                        "0" => false,
+                        "1" => true,
+                        _ => return Err(format!("Invalid middle cell '{part}'")),
+                    };
+                    last_was_birth = None;
+                }
+                Some('N') => {
+                    rule.von_neumann = match value.to_ascii_uppercase().as_str() {
+                        "M" => false,
+                        "N" => true,
+                        _ => return Err(format!("Unknown neighbourhood '{part}'")),
+                    };
+                    last_was_birth = None;
+                }
+                Some('S') => {
+                    add_counts(&mut rule.survival, value)?;
+                    last_was_birth = Some(false);
+                }
+                Some('B') => {
+                    add_counts(&mut rule.birth, value)?;
+                    last_was_birth = Some(true);
+                }
+                Some(c) if c.is_ascii_digit() => match last_was_birth {
+                    Some(true) => add_counts(&mut rule.birth, part)?,
+                    Some(false) => add_counts(&mut rule.survival, part)?,
+                    None => return Err(format!("Unexpected '{part}'")),
+                },
+                _ => return Err(format!("Invalid rule '{s}'")),
+            }
+        }
+
+        Ok(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn automaton(s: &str) -> Result<Automaton, String> {
+        s.parse::<Hrot>()?.automaton()
+    }
+
+    #[test]
+    fn picks_the_rule_the_counts_fit_into() {
+        assert!(matches!(
+            automaton("R2,C0,S6-9,B7-8,NM"),
+            Ok(Automaton::Life(Rule {
+                neighbourhood: Neighbourhood::Moore(2),
+                ..
+            }))
+        ));
+        assert!(matches!(
+            automaton("R2,C0,S2,4-5,B3,NN"),
+            Ok(Automaton::Life(Rule {
+                neighbourhood: Neighbourhood::VonNeumann(2),
+                ..
+            }))
+        ));
+        assert_eq!(
+            automaton("R5,C0,M1,S34..58,B34..45,NM"),
+            Ok(Automaton::LargerThanLife(LtlRule {
+                radius: 5,
+                states: 2,
+                middle: true,
+                survival: (34, 58),
+                birth: (34, 45),
+            }))
+        );
+    }
+
+    #[test]
+    fn writes_rules_back_the_way_it_reads_them() {
+        for s in [
+            "R2,C0,S6-9,B7-8,NM",
+            "R2,C3,S2,4-5,B3,NN",
+            "R5,C0,M1,S34-58,B34-45,NM",
+            "R7,C4,S10-20,B15-17,NM",
+        ] {
+            let hrot = s.parse::<Hrot>().unwrap();
+            assert_eq!(hrot.to_string(), s);
+            assert_eq!(automaton(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn refuses_what_no_rule_can_run() {
+        for s in [
+            "R0,C0,S2,B3,NM",
+            "R11,C0,S2,B3,NM",
+            "R2,C1,S2,B3,NM",
+            "R2,C0,S5-3,B3,NM",
+            "R2,C0,S2,B3,NX",
+            "R2,C0,M2,S2,B3,NM",
+            "R2,C0,S2,B40,NM",
+            "R5,C0,S2,B3,NN",
+            "R5,C0,S2,4,B3,NM",
+            "R2,3,S2,B3",
+        ] {
+            assert!(automaton(s).is_err(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! The status bar below the grid
+
+use std::collections::VecDeque;
+
+use sfml::graphics::*;
+use sfml::system::*;
+use sfml::window::*;
+
+use crate::locale::tr;
+use crate::render::{Renderer, SfmlRenderer};
+use crate::widget::{Button, Slider, TextInput};
+use crate::{HEIGHT, STATUS_BAR_HEIGHT, WIDTH};
+
+const FONT_SIZE: u32 = 14;
+const LINE_HEIGHT: f32 = 18.0;
+const PADDING: f32 = 10.0;
+const FIELD_SPACING: f32 = 20.0;
+
+/// Height of the row of playback controls at the top of the bar
+const CONTROLS_HEIGHT: f32 = 24.0;
+const BUTTON_WIDTH: f32 = 60.0;
+
+/// Range of the speed slider in milliseconds between steps
+const MIN_SPEED: f32 = 10.0;
+const MAX_SPEED: f32 = 1000.0;
+
+/// Height of the timeline along the bottom of the bar
+const TIMELINE_HEIGHT: f32 = 14.0;
+
+/// Height of the console over the bottom of the grid
+const CONSOLE_HEIGHT: f32 = 24.0;
+
+/// Messages fade out over this much of the end of their duration
+const FADE_TIME: Time = Time::milliseconds(500);
+
+/// Oldest messages are dropped once more than this many are waiting
+const MAX_QUEUED_MESSAGES: usize = 5;
+
+/// Something the user asked for through the playback controls
+pub enum HudAction {
+    TogglePlay,
+    Step,
+    StepBack,
+    SetSpeed(Time),
+
+    /// Jump to a recorded generation
+    Seek(u64),
+
+    /// Run a line typed into the console
+    Eval(String),
+
+    /// Run a line typed into the `:` command line
+    Command(String),
+}
+
+/// Result of passing an event to the hud
+pub enum HudResponse {
+    /// The event wasn't meant for the hud
+    Ignored,
+
+    /// The event was used up by the hud, maybe triggering an action
+    Handled(Option<HudAction>),
+}
+
+struct Message {
+    text: String,
+    remaining: Time,
+}
+
+pub struct Hud<'a> {
+    font: &'a Font,
+    background: RectangleShape<'a>,
+
+    /// Labels and values, drawn in the order they were first set
+    fields: Vec<(&'static str, String)>,
+
+    /// Size of the fields and messages relative to [`FONT_SIZE`]
+    font_scale: f32,
+
+    /// Messages waiting to be shown, the front one is currently displayed
+    messages: VecDeque<Message>,
+
+    /// Shown over the top of the grid until it is replaced, for the tutorial
+    prompt: Option<String>,
+
+    back_button: Button,
+    play_button: Button,
+    step_button: Button,
+    speed_slider: Slider,
+
+    /// Only shown once there is more than one recorded generation
+    timeline: Slider,
+    timeline_visible: bool,
+
+    /// Opened with the backtick key
+    console: TextInput,
+
+    /// Opened by typing `:`, shown in the same place as the console
+    command_line: TextInput,
+}
+
+impl<'a> Hud<'a> {
+    pub fn new(font: &'a Font) -> Self {
+        let mut background = RectangleShape::new();
+        background.set_outline_thickness(2.0);
+        background.set_outline_color(Color::WHITE);
+        background.set_fill_color(Color::BLACK);
+        background.set_position((0.0, HEIGHT as f32));
+        background.set_size((WIDTH as f32, STATUS_BAR_HEIGHT as f32));
+
+        let top = HEIGHT as f32 + PADDING / 2.0;
+        let button = |idx: usize, label: &'static str| {
+            let left = PADDING + idx as f32 * (BUTTON_WIDTH + PADDING / 2.0);
+            Button::new(
+                FloatRect::new(left, top, BUTTON_WIDTH, CONTROLS_HEIGHT),
+                tr(label),
+            )
+        };
+
+        let console_bounds = FloatRect::new(
+            0.0,
+            HEIGHT as f32 - CONSOLE_HEIGHT,
+            WIDTH as f32,
+            CONSOLE_HEIGHT,
+        );
+        let slider_left = PADDING + 3.0 * (BUTTON_WIDTH + PADDING / 2.0) + 70.0;
+
+        Self {
+            font,
+            background,
+            fields: Vec::new(),
+            font_scale: 1.0,
+            messages: VecDeque::new(),
+            prompt: None,
+            back_button: button(0, "Back"),
+            play_button: button(1, "Play"),
+            step_button: button(2, "Step"),
+            speed_slider: Slider::new(
+                FloatRect::new(slider_left, top, 200.0, CONTROLS_HEIGHT),
+                MIN_SPEED,
+                MAX_SPEED,
+                MIN_SPEED,
+            ),
+            timeline: Slider::new(
+                FloatRect::new(
+                    PADDING,
+                    (HEIGHT + STATUS_BAR_HEIGHT) as f32 - PADDING / 2.0 - TIMELINE_HEIGHT,
+                    WIDTH as f32 - 2.0 * PADDING,
+                    TIMELINE_HEIGHT,
+                ),
+                0.0,
+                1.0,
+                0.0,
+            ),
+            timeline_visible: false,
+            console: TextInput::new(console_bounds, "> "),
+            command_line: TextInput::new(console_bounds, ":"),
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> HudResponse {
+        // an open console takes all typing
+        if self.console.is_focused() || self.command_line.is_focused() {
+            if let Some(line) = self.console.handle_event(event) {
+                return HudResponse::Handled(Some(HudAction::Eval(line)));
+            }
+
+            if let Some(line) = self.command_line.handle_event(event) {
+                return HudResponse::Handled(Some(HudAction::Command(line)));
+            }
+
+            if matches!(
+                event,
+                Event::KeyPressed { .. } | Event::KeyReleased { .. } | Event::TextEntered { .. }
+            ) {
+                return HudResponse::Handled(None);
+            }
+        }
+
+        match *event {
+            Event::KeyPressed {
+                code: Key::Tilde, ..
+            } => {
+                self.console.focus(Some('`'));
+                return HudResponse::Handled(None);
+            }
+            Event::TextEntered { unicode: ':' } => {
+                self.command_line.focus(None);
+                return HudResponse::Handled(None);
+            }
+            _ => {}
+        }
+
+        let was_dragging = self.speed_slider.is_dragging() || self.timeline.is_dragging();
+
+        if self.timeline_visible {
+            if let Some(generation) = self.timeline.handle_event(event) {
+                return HudResponse::Handled(Some(HudAction::Seek(generation.round() as u64)));
+            }
+        }
+
+        if let Some(speed) = self.speed_slider.handle_event(event) {
+            // snap to whole steps of 10ms
+            let ms = (speed / 10.0).round() as i32 * 10;
+            return HudResponse::Handled(Some(HudAction::SetSpeed(Time::milliseconds(ms))));
+        }
+
+        let action = if self.back_button.handle_event(event) {
+            Some(HudAction::StepBack)
+        } else if self.play_button.handle_event(event) {
+            Some(HudAction::TogglePlay)
+        } else if self.step_button.handle_event(event) {
+            Some(HudAction::Step)
+        } else {
+            None
+        };
+
+        match *event {
+            Event::MouseButtonPressed { y, .. } if y >= HEIGHT as i32 => {
+                HudResponse::Handled(action)
+            }
+            Event::MouseButtonReleased { .. } if was_dragging => HudResponse::Handled(None),
+            _ => HudResponse::Ignored,
+        }
+    }
+
+    pub fn set_playing(&mut self, playing: bool) {
+        self.play_button.label = tr(if playing { "Pause" } else { "Play" }).to_string();
+    }
+
+    pub fn set_font_scale(&mut self, scale: f32) {
+        self.font_scale = scale;
+    }
+
+    pub fn set_speed(&mut self, time_bw_steps: Time) {
+        if !self.speed_slider.is_dragging() {
+            self.speed_slider.value = time_bw_steps.as_milliseconds() as f32;
+        }
+    }
+
+    /// Shows the range of recorded generations and the current one on the
+    /// timeline
+    pub fn set_timeline(&mut self, range: Option<(u64, u64)>, generation: u64) {
+        self.timeline_visible = matches!(range, Some((start, end)) if end > start);
+
+        if let Some((start, end)) = range.filter(|_| !self.timeline.is_dragging()) {
+            self.timeline.min = start as f32;
+            self.timeline.max = end as f32;
+            self.timeline.value = generation as f32;
+        }
+    }
+
+    pub fn set_field(&mut self, label: &'static str, value: impl ToString) {
+        let value = value.to_string();
+
+        match self.fields.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, v)) => *v = value,
+            None => self.fields.push((label, value)),
+        }
+    }
+
+    pub fn remove_field(&mut self, label: &'static str) {
+        self.fields.retain(|(l, _)| *l != label);
+    }
+
+    /// Queues a message to be shown below the fields for the given duration,
+    /// repeating the last queued message only restarts its timer
+    pub fn set_message(&mut self, msg: impl Into<String>, duration: Time) {
+        let text = msg.into();
+
+        if let Some(last) = self.messages.back_mut().filter(|last| last.text == text) {
+            last.remaining = duration;
+            return;
+        }
+
+        if self.messages.len() == MAX_QUEUED_MESSAGES {
+            self.messages.pop_front();
+        }
+
+        self.messages.push_back(Message {
+            text,
+            remaining: duration,
+        });
+    }
+
+    pub fn set_prompt(&mut self, prompt: Option<String>) {
+        self.prompt = prompt;
+    }
+
+    pub fn update(&mut self, dt: Time) {
+        if let Some(message) = self.messages.front_mut() {
+            message.remaining -= dt;
+
+            if message.remaining <= Time::ZERO {
+                self.messages.pop_front();
+            }
+        }
+    }
+
+    pub fn draw(&self, renderer: &mut SfmlRenderer) {
+        let window = &mut renderer.window;
+        window.draw(&self.background);
+
+        self.back_button.draw(window, self.font);
+        self.play_button.draw(window, self.font);
+        self.step_button.draw(window, self.font);
+        self.speed_slider.draw(window);
+
+        if self.timeline_visible {
+            self.timeline.draw(window);
+        }
+
+        if self.console.is_focused() {
+            self.console.draw(window, self.font);
+        } else if self.command_line.is_focused() {
+            self.command_line.draw(window, self.font);
+        }
+
+        renderer.draw_text(
+            tr("speed"),
+            (
+                self.speed_slider.bounds.left - 60.0,
+                self.speed_slider.bounds.top + 3.0,
+            ),
+            FONT_SIZE,
+            Color::WHITE,
+        );
+
+        let mut position = Vector2f::new(
+            PADDING,
+            HEIGHT as f32 + PADDING + CONTROLS_HEIGHT + PADDING / 2.0,
+        );
+
+        let font_size = (FONT_SIZE as f32 * self.font_scale).round() as u32;
+        let line_height = LINE_HEIGHT * self.font_scale;
+
+        for (label, value) in &self.fields {
+            let text = format!("{}: {value}", tr(label));
+            let width = Text::new(&text, self.font, font_size).local_bounds().width;
+
+            // wrap onto the next line once the bar is full
+            if position.x > PADDING && position.x + width > WIDTH as f32 - PADDING {
+                position = Vector2f::new(PADDING, position.y + line_height);
+            }
+
+            renderer.draw_text(&text, (position.x, position.y), font_size, Color::WHITE);
+
+            position.x += width + FIELD_SPACING;
+        }
+
+        if let Some(message) = self.messages.front() {
+            let queued = match self.messages.len() {
+                1 => String::new(),
+                n => format!(" (+{})", n - 1),
+            };
+
+            let alpha = (message.remaining.as_seconds() / FADE_TIME.as_seconds()).min(1.0);
+
+            renderer.draw_text(
+                &format!("{}{queued}", message.text),
+                (PADDING, position.y + line_height),
+                font_size,
+                Color::rgba(200, 200, 0, (alpha * 255.0) as u8),
+            );
+        }
+
+        if let Some(prompt) = &self.prompt {
+            self.draw_prompt(renderer, prompt, font_size, line_height);
+        }
+    }
+
+    /// Draws the prompt wrapped into a box along the top of the grid
+    fn draw_prompt(
+        &self,
+        renderer: &mut SfmlRenderer,
+        prompt: &str,
+        font_size: u32,
+        line_height: f32,
+    ) {
+        let width = |text: &str| Text::new(text, self.font, font_size).local_bounds().width;
+
+        let mut lines = vec![String::new()];
+        for word in prompt.split_whitespace() {
+            let line = lines.last_mut().unwrap();
+            let extended = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+
+            if !line.is_empty() && width(&extended) > WIDTH as f32 - 2.0 * PADDING {
+                lines.push(word.to_string());
+            } else {
+                *line = extended;
+            }
+        }
+
+        let mut background = RectangleShape::new();
+        background.set_position((0.0, 0.0));
+        background.set_size((WIDTH as f32, lines.len() as f32 * line_height + PADDING));
+        background.set_fill_color(Color::rgba(0, 0, 0, 210));
+        background.set_outline_thickness(2.0);
+        background.set_outline_color(Color::rgb(200, 200, 0));
+        renderer.window.draw(&background);
+
+        for (idx, line) in lines.iter().enumerate() {
+            renderer.draw_text(
+                line,
+                (PADDING, PADDING / 2.0 + idx as f32 * line_height),
+                font_size,
+                Color::WHITE,
+            );
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Lenia, a continuous cellular automaton
+//!
+//! Every cell holds a value between `0` and `1`. Each step the values around
+//! a cell are weighted by a ring shaped kernel, the resulting potential is
+//! mapped through the growth function and added back to the cell.
+
+use std::fmt;
+
+use grid::Grid;
+
+use crate::color::Color;
+use crate::CellState;
+
+pub struct Lenia {
+    pub grid: Grid<f32>,
+
+    /// Radius of the kernel in cells
+    pub radius: u8,
+
+    /// Center of the growth function
+    pub mu: f32,
+
+    /// Width of the growth function
+    pub sigma: f32,
+
+    /// Fraction of the growth applied each step
+    pub dt: f32,
+
+    /// Offsets and normalized weights of the kernel
+    kernel: Vec<(i32, i32, f32)>,
+}
+
+impl Lenia {
+    /// Creates a universe from a grid of discrete cells, alive cells start at `1`
+    pub fn from_cells(cells: &Grid<CellState>) -> Self {
+        let mut grid = Grid::new(cells.rows(), cells.cols());
+        grid.iter_mut()
+            .zip(cells.iter())
+            .for_each(|(value, cell)| *value = cell.is_alive() as u8 as f32);
+
+        let mut lenia = Self {
+            grid,
+            radius: 0,
+            mu: 0.15,
+            sigma: 0.015,
+            dt: 0.1,
+            kernel: Vec::new(),
+        };
+
+        lenia.set_radius(8);
+        lenia
+    }
+
+    /// Thresholds the values back into discrete cells
+    pub fn to_cells(&self) -> Grid<CellState> {
+        let mut cells = Grid::new(self.grid.rows(), self.grid.cols());
+        cells
+            .iter_mut()
+            .zip(self.grid.iter())
+            .for_each(|(cell, value)| {
+                if *value >= 0.5 {
+                    *cell = CellState::ALIVE;
+                }
+            });
+
+        cells
+    }
+
+    pub fn set_radius(&mut self, radius: u8) {
+        self.radius = radius;
+
+        let r = radius as i32;
+        let mut kernel = Vec::new();
+
+        for dr in -r..=r {
+            for dc in -r..=r {
+                let distance = ((dr * dr + dc * dc) as f32).sqrt() / r as f32;
+
+                // smooth bump peaking halfway between the center and the edge
+                if distance > 0.0 && distance < 1.0 {
+                    let weight = (4.0 - 1.0 / (distance * (1.0 - distance))).exp();
+                    kernel.push((dr, dc, weight));
+                }
+            }
+        }
+
+        let total = kernel.iter().map(|(_, _, weight)| weight).sum::<f32>();
+        kernel
+            .iter_mut()
+            .for_each(|(_, _, weight)| *weight /= total);
+
+        self.kernel = kernel;
+    }
+
+    fn growth(&self, potential: f32) -> f32 {
+        let d = potential - self.mu;
+        2.0 * (-d * d / (2.0 * self.sigma * self.sigma)).exp() - 1.0
+    }
+
+    /// Advances the universe by one step, the edges wrap around
+    pub fn step(&mut self) {
+        let (rows, cols) = (self.grid.rows() as i32, self.grid.cols() as i32);
+        let mut new_grid = self.grid.clone();
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let potential = self
+                    .kernel
+                    .iter()
+                    .map(|(dr, dc, weight)| {
+                        let r = (row + dr).rem_euclid(rows) as usize;
+                        let c = (col + dc).rem_euclid(cols) as usize;
+                        self.grid[r][c] * weight
+                    })
+                    .sum::<f32>();
+
+                let value = &mut new_grid[row as usize][col as usize];
+                *value = (*value + self.dt * self.growth(potential)).clamp(0.0, 1.0);
+            }
+        }
+
+        self.grid = new_grid;
+    }
+
+    /// Maps a value to a color ramping from black over purple to yellow
+    pub fn color(value: f32) -> Color {
+        static FADED: Color = Color::rgb(60, 20, 80);
+        static YELLOW: Color = Color::rgb(200, 200, 0);
+
+        let (from, to, t) = if value < 0.5 {
+            (Color::BLACK, FADED, value * 2.0)
+        } else {
+            (FADED, YELLOW, value * 2.0 - 1.0)
+        };
+
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+        Color::rgb(lerp(from.r, to.r), lerp(from.g, to.g), lerp(from.b, to.b))
+    }
+}
+
+impl fmt::Display for Lenia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Lenia R{} mu={} sigma={}",
+            self.radius, self.mu, self.sigma
+        )
+    }
+}
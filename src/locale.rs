@@ -0,0 +1,505 @@
+//! Translations of the text the game shows
+//!
+//! Every message is looked up by its English text, so the English text in
+//! the code is the key and the fallback. A language only lists the messages
+//! it translates, anything missing from its catalog is shown in English.
+//! Messages with values in them mark each value with `{}`, or with `{0}`,
+//! `{1}`, ... where a translation puts them in another order.
+//!
+//! The language is picked once at startup, by `--lang` or else the
+//! `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables.
+
+use std::fmt::{self, Display, Write as _};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub const LOCALES: [Locale; 2] = [Locale::English, Locale::German];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+/// Index into [`LOCALES`] of the language in use
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+impl Locale {
+    /// The language the environment asks for, English if it is none of the
+    /// translated ones
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+        }
+    }
+
+    /// Translations from English
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::English => &[],
+            Self::German => GERMAN,
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts the language code alone or as the start of a locale like
+    /// `de_DE.UTF-8`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().split(['_', '-', '.']).next().unwrap_or_default();
+
+        LOCALES
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(code))
+            .ok_or_else(|| fill("Unknown language '{}', expected en or de", &[&s]))
+    }
+}
+
+/// Switches every message shown from now on to the language
+pub fn set(locale: Locale) {
+    let idx = LOCALES.iter().position(|l| *l == locale).unwrap_or(0);
+    CURRENT.store(idx as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Locale {
+    LOCALES[CURRENT.load(Ordering::Relaxed) as usize]
+}
+
+/// The message in the current language
+pub fn tr(english: &'static str) -> &'static str {
+    current()
+        .catalog()
+        .iter()
+        .find(|(key, _)| *key == english)
+        .map_or(english, |(_, translation)| translation)
+}
+
+/// Owned [`tr`], for the functions returning messages
+pub fn text(english: &'static str) -> String {
+    tr(english).to_string()
+}
+
+/// The message in the current language with its `{}` filled in by the
+/// values in order, or `{N}` by the value at that index
+pub fn fill(english: &'static str, values: &[&dyn Display]) -> String {
+    let mut rest = tr(english);
+    let mut filled = String::with_capacity(rest.len());
+    let mut next = 0;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        filled.push_str(&rest[..start]);
+
+        let marker = &rest[start + 1..start + len];
+        let idx = if marker.is_empty() {
+            next += 1;
+            next - 1
+        } else {
+            marker.parse().unwrap_or(usize::MAX)
+        };
+
+        match values.get(idx) {
+            Some(value) => {
+                let _ = write!(filled, "{value}");
+            }
+            None => filled.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+
+    filled.push_str(rest);
+    filled
+}
+
+static GERMAN: &[(&str, &str)] = &[
+    // window and status bar
+    ("Conway's game of life", "Conways Spiel des Lebens"),
+    ("Back", "Zurück"),
+    ("Play", "Start"),
+    ("Pause", "Pause"),
+    ("Step", "Schritt"),
+    ("editing rule", "Regel bearbeiten"),
+    ("playing", "läuft"),
+    ("paused", "pausiert"),
+    ("gen", "Gen"),
+    ("pop", "Pop"),
+    ("speed", "Tempo"),
+    ("steps", "Schritte"),
+    ("grid", "Gitter"),
+    ("edges", "Ränder"),
+    ("noise", "Rauschen"),
+    ("active", "aktiv"),
+    ("jump", "Sprung"),
+    ("mode", "Modus"),
+    ("rule", "Regel"),
+    ("seed", "Seed"),
+    ("blocks", "Blöcke"),
+    ("zoom", "Zoom"),
+    ("net", "Netz"),
+    ("p1", "S1"),
+    ("p2", "S2"),
+    ("layer", "Ebene"),
+    ("census", "Zählung"),
+    ("ships", "Schiffe"),
+    ("changed", "geändert"),
+    ("palette", "Palette"),
+    ("none", "keine"),
+    ("joined", "beigetreten"),
+    ("hosting, {} joined", "Gastgeber, {} beigetreten"),
+    // rules, topologies and states
+    ("Conway's life", "Conways Leben"),
+    ("Brian's brain", "Brians Gehirn"),
+    ("Hex life", "Hex-Leben"),
+    ("Bugs", "Käfer"),
+    ("Wireworld", "Wireworld"),
+    ("Immigration", "Immigration"),
+    ("Rule 30", "Regel 30"),
+    ("Rule 90", "Regel 90"),
+    ("Rule 110", "Regel 110"),
+    ("Plane", "Ebene"),
+    ("Torus", "Torus"),
+    ("Klein bottle", "Kleinsche Flasche"),
+    ("Cross-surface", "Kreuzhaube"),
+    ("Mirrored edges", "Gespiegelte Ränder"),
+    ("classic", "klassisch"),
+    ("deuteranopia", "Deuteranopie"),
+    ("protanopia", "Protanopie"),
+    ("empty", "leer"),
+    ("electron head", "Elektronenkopf"),
+    ("electron tail", "Elektronenschwanz"),
+    ("conductor", "Leiter"),
+    ("player 1", "Spieler 1"),
+    ("player 2", "Spieler 2"),
+    ("dead", "tot"),
+    ("alive", "lebendig"),
+    ("dying", "sterbend"),
+    // messages
+    ("Rule: {}", "Regel: {}"),
+    ("Topology: {}", "Topologie: {}"),
+    ("Brush: {}", "Pinsel: {}"),
+    ("Neighbourhood: {}", "Nachbarschaft: {}"),
+    ("Palette: {}", "Palette: {}"),
+    ("High contrast on", "Hoher Kontrast an"),
+    ("High contrast off", "Hoher Kontrast aus"),
+    (
+        "High contrast and the font size only apply to the window",
+        "Hoher Kontrast und die Schriftgröße gelten nur im Fenster",
+    ),
+    ("Rule enabled", "Regel aktiviert"),
+    (
+        "Rule disabled, only ants move",
+        "Regel deaktiviert, nur Ameisen bewegen sich",
+    ),
+    (
+        "Only life-like rules can change their neighbourhood",
+        "Nur Life-artige Regeln können ihre Nachbarschaft ändern",
+    ),
+    (
+        "Only life-like rules can be edited",
+        "Nur Life-artige Regeln können bearbeitet werden",
+    ),
+    (
+        "0-8: birth, Shift + 0-8: survival, E: done",
+        "0-8: Geburt, Umschalt + 0-8: Überleben, E: fertig",
+    ),
+    ("Showing the neighbour counts", "Zeige die Anzahl der Nachbarn"),
+    ("Hiding the neighbour counts", "Verberge die Anzahl der Nachbarn"),
+    (
+        "Explaining steps: cells about to be born turn green, dying ones red",
+        "Erkläre Schritte: Zellen kurz vor der Geburt werden grün, sterbende rot",
+    ),
+    ("Stopped explaining steps", "Erkläre keine Schritte mehr"),
+    ("Following the live cells", "Folge den lebenden Zellen"),
+    ("Stopped following", "Folge nicht mehr"),
+    ("No such state", "Diesen Zustand gibt es nicht"),
+    ("Cannot increase further", "Kann nicht weiter erhöht werden"),
+    ("Cannot decrease further", "Kann nicht weiter verringert werden"),
+    (
+        "Clicking while playing pauses until the click ends",
+        "Klicken während des Spiels pausiert bis zum Ende des Klicks",
+    ),
+    (
+        "Cells clicked while playing change when the click ends",
+        "Während des Spiels geklickte Zellen ändern sich am Ende des Klicks",
+    ),
+    (
+        "The pattern has no name or comments, add them with :name, :author and :comment",
+        "Das Muster hat keinen Namen oder Kommentare, füge sie mit :name, :author und :comment hinzu",
+    ),
+    ("Steps play the population", "Schritte spielen die Population"),
+    ("Steps tick", "Schritte ticken"),
+    ("Sound on", "Ton an"),
+    ("Sound off", "Ton aus"),
+    ("Left continuous mode", "Kontinuierlichen Modus verlassen"),
+    ("Entered continuous mode", "Kontinuierlichen Modus betreten"),
+    (
+        "Leave continuous mode first",
+        "Verlasse zuerst den kontinuierlichen Modus",
+    ),
+    ("Max grid size reached", "Maximale Gittergröße erreicht"),
+    ("Min grid size reached", "Minimale Gittergröße erreicht"),
+    ("Press R again to clear the grid", "Drücke erneut R, um das Gitter zu leeren"),
+    ("Copied {}x{} cells", "{}x{} Zellen kopiert"),
+    ("Cannot paste: {}", "Einfügen nicht möglich: {}"),
+    ("Pasted", "Eingefügt"),
+    ("Pasted {}x{} cells", "{}x{} Zellen eingefügt"),
+    ("At most {} layers", "Höchstens {} Ebenen"),
+    ("Cannot remove the only layer", "Die einzige Ebene kann nicht entfernt werden"),
+    ("Hosting on {}", "Gastgeber auf {}"),
+    ("Couldn't host on {}: {}", "Konnte nicht Gastgeber auf {} sein: {}"),
+    ("Joined {}", "{} beigetreten"),
+    ("Couldn't join {}: {}", "Konnte {} nicht beitreten: {}"),
+    ("A player joined", "Ein Spieler ist beigetreten"),
+    ("A player left", "Ein Spieler ist gegangen"),
+    (
+        "Lost the connection to the host",
+        "Die Verbindung zum Gastgeber ist verloren",
+    ),
+    ("Invalid board from the host: {}", "Ungültiges Brett vom Gastgeber: {}"),
+    ("Invalid board from a player: {}", "Ungültiges Brett von einem Spieler: {}"),
+    (
+        "Invalid board from a player: {} by {} cells",
+        "Ungültiges Brett von einem Spieler: {} mal {} Zellen",
+    ),
+    (
+        "Invalid board from the host: {} by {} cells",
+        "Ungültiges Brett vom Gastgeber: {} mal {} Zellen",
+    ),
+    ("The pattern doesn't fit the grid", "Das Muster passt nicht ins Gitter"),
+    (
+        "Patterns can't be placed in lenia mode",
+        "Im Lenia-Modus können keine Muster platziert werden",
+    ),
+    ("Loaded {}", "{} geladen"),
+    ("Saved {}", "{} gespeichert"),
+    ("Restored {}", "{} wiederhergestellt"),
+    ("Restored the last session", "Letzte Sitzung wiederhergestellt"),
+    (
+        "The last session didn't exit cleanly, press Enter to restore it",
+        "Die letzte Sitzung wurde nicht sauber beendet, drücke Enter, um sie wiederherzustellen",
+    ),
+    ("Exported to {}", "Nach {} exportiert"),
+    ("Cannot export to {}: {}", "Export nach {} nicht möglich: {}"),
+    (
+        "Snapshots can only be taken in the window",
+        "Schnappschüsse gibt es nur im Fenster",
+    ),
+    (
+        "The grid isn't saved, close again to quit anyway",
+        "Das Gitter ist nicht gespeichert, schließe erneut, um trotzdem zu beenden",
+    ),
+    ("Couldn't record to {}: {}", "Konnte nicht nach {} aufnehmen: {}"),
+    ("Stopped recording: {}", "Aufnahme beendet: {}"),
+    ("Replay finished", "Wiedergabe beendet"),
+    (
+        "Built without scripting, rebuild with `--features scripting`",
+        "Ohne Skripte gebaut, baue mit `--features scripting` neu",
+    ),
+    (
+        "Scripts can't run in the continuous mode",
+        "Skripte laufen nicht im kontinuierlichen Modus",
+    ),
+    (
+        "Cannot step back in continuous mode",
+        "Im kontinuierlichen Modus geht es nicht zurück",
+    ),
+    (
+        "Cannot rewind in continuous mode",
+        "Im kontinuierlichen Modus kann nicht zurückgespult werden",
+    ),
+    ("No earlier generation", "Keine frühere Generation"),
+    ("Generation {} isn't recorded", "Generation {} ist nicht aufgezeichnet"),
+    (
+        "Settled into a still life at generation {}",
+        "In Generation {} zu einem Stillleben geworden",
+    ),
+    (
+        "Settled into a period {} oscillator at generation {}",
+        "In Generation {1} zu einem Oszillator der Periode {0} geworden",
+    ),
+    (
+        "Found a predecessor and put it onto the grid, one step gives back the grid",
+        "Einen Vorgänger gefunden und ins Gitter gesetzt, ein Schritt ergibt wieder das Gitter",
+    ),
+    (
+        "The grid is a Garden of Eden, no grid of its size steps into it",
+        "Das Gitter ist ein Garten Eden, kein Gitter seiner Größe führt zu ihm",
+    ),
+    (
+        "Gave up searching for a predecessor, the grid is too large",
+        "Suche nach einem Vorgänger aufgegeben, das Gitter ist zu groß",
+    ),
+    (
+        "Predecessors can only be searched for two state rules, not {}",
+        "Vorgänger können nur für Regeln mit zwei Zuständen gesucht werden, nicht für {}",
+    ),
+    (
+        "The grid is too large to search for a predecessor, at most {} cells",
+        "Das Gitter ist zu groß für die Suche nach einem Vorgänger, höchstens {} Zellen",
+    ),
+    (
+        "Unknown topology '{}', expected plane, torus, klein, cross or mirror",
+        "Unbekannte Topologie '{}', erwartet plane, torus, klein, cross oder mirror",
+    ),
+    (
+        "Unknown palette '{}', expected classic, deuteranopia or protanopia",
+        "Unbekannte Palette '{}', erwartet classic, deuteranopia oder protanopia",
+    ),
+    (
+        "Unknown language '{}', expected en or de",
+        "Unbekannte Sprache '{}', erwartet en oder de",
+    ),
+    // command line
+    ("No command", "Kein Befehl"),
+    (
+        "'{}' needs {}, try 'help'",
+        "'{}' braucht {}, versuche 'help'",
+    ),
+    ("a pattern", "ein Muster"),
+    ("a URL", "eine URL"),
+    ("a rule", "eine Regel"),
+    (
+        "the milliseconds between steps",
+        "die Millisekunden zwischen den Schritten",
+    ),
+    ("the rows", "die Zeilen"),
+    ("the columns", "die Spalten"),
+    ("a seed", "einen Seed"),
+    ("a file", "eine Datei"),
+    ("a topology", "eine Topologie"),
+    ("a probability", "eine Wahrscheinlichkeit"),
+    ("a palette", "eine Palette"),
+    ("on or off", "on oder off"),
+    ("a scale", "einen Maßstab"),
+    (
+        "Built without networking, rebuild with `--features net`",
+        "Ohne Netzwerk gebaut, baue mit `--features net` neu",
+    ),
+    (
+        "Built without snapshots, rebuild with `--features serde`",
+        "Ohne Schnappschüsse gebaut, baue mit `--features serde` neu",
+    ),
+    (
+        "The time between steps must be at most {} milliseconds",
+        "Die Zeit zwischen den Schritten darf höchstens {} Millisekunden sein",
+    ),
+    (
+        "The grid must be between 1 and {} cells on each side",
+        "Das Gitter muss auf jeder Seite zwischen 1 und {} Zellen haben",
+    ),
+    (
+        "At most {} generations can be stepped at once",
+        "Höchstens {} Generationen können auf einmal weitergeschaltet werden",
+    ),
+    ("Unexpected '{}' after the seed", "Unerwartetes '{}' nach dem Seed"),
+    ("Unexpected '{}' after '{}'", "Unerwartetes '{}' nach '{}'"),
+    (
+        "The noise must be between 0 and 1",
+        "Das Rauschen muss zwischen 0 und 1 liegen",
+    ),
+    (
+        "The density must be between 0 and 1",
+        "Die Dichte muss zwischen 0 und 1 liegen",
+    ),
+    (
+        "'density' needs a probability",
+        "'density' braucht eine Wahrscheinlichkeit",
+    ),
+    ("Expected on or off, not '{}'", "Erwartet on oder off, nicht '{}'"),
+    (
+        "The font scale must be between 0.5 and 2",
+        "Die Schriftgröße muss zwischen 0,5 und 2 liegen",
+    ),
+    (
+        "Unknown command '{}', try 'help'",
+        "Unbekannter Befehl '{}', versuche 'help'",
+    ),
+    ("Invalid value '{}' for '{}'", "Ungültiger Wert '{}' für '{}'"),
+    // tutorial
+    ("Tutorial {}/{}: {}", "Tutorial {}/{}: {}"),
+    ("Left the tutorial", "Tutorial verlassen"),
+    (
+        "{}. Ctrl + T starts the tutorial",
+        "{}. Strg + T startet das Tutorial",
+    ),
+    ("Tutorial finished, have fun!", "Tutorial beendet, viel Spaß!"),
+    (
+        "Welcome! Every cell is alive or dead and every step decides its fate by its 8 neighbours. Press Enter to continue, Ctrl + T leaves the tutorial",
+        "Willkommen! Jede Zelle ist lebendig oder tot und jeder Schritt entscheidet ihr Schicksal anhand ihrer 8 Nachbarn. Drücke Enter, um fortzufahren, Strg + T verlässt das Tutorial",
+    ),
+    (
+        "This is a blinker. Press Space to step it once",
+        "Das ist ein Blinker. Drücke die Leertaste für einen Schritt",
+    ),
+    (
+        "A live cell with 2 or 3 live neighbours survives and a dead cell with exactly 3 is born, every other cell dies. Step once more",
+        "Eine lebende Zelle mit 2 oder 3 lebenden Nachbarn überlebt und eine tote mit genau 3 wird geboren, jede andere Zelle stirbt. Mache noch einen Schritt",
+    ),
+    (
+        "Back where it started, the blinker oscillates with a period of 2. Press Enter to continue",
+        "Zurück am Anfang, der Blinker oszilliert mit einer Periode von 2. Drücke Enter, um fortzufahren",
+    ),
+    (
+        "This is a glider. Press P to play and watch it",
+        "Das ist ein Gleiter. Drücke P zum Abspielen und sieh ihm zu",
+    ),
+    (
+        "Every 4 generations the glider moves a cell diagonally. Press P again to pause",
+        "Alle 4 Generationen bewegt sich der Gleiter um eine Zelle diagonal. Drücke erneut P zum Pausieren",
+    ),
+    (
+        "Every generation is recorded, press Backspace to step back",
+        "Jede Generation wird aufgezeichnet, drücke die Rücktaste, um zurückzugehen",
+    ),
+    (
+        "Click cells of the empty grid to bring 3 of them to life",
+        "Klicke Zellen des leeren Gitters an, um 3 von ihnen zum Leben zu erwecken",
+    ),
+    (
+        "Press Space to see what your cells do",
+        "Drücke die Leertaste, um zu sehen, was deine Zellen tun",
+    ),
+    (
+        "F fills the grid with a random soup, M changes the rule, = and - change the speed, + and - on the numpad resize the grid and : opens the command line, where help lists the commands. Press Enter to finish",
+        "F füllt das Gitter mit einer zufälligen Suppe, M wechselt die Regel, = und - ändern die Geschwindigkeit, + und - auf dem Ziffernblock ändern die Gittergröße und : öffnet die Befehlszeile, wo help die Befehle auflistet. Drücke Enter zum Beenden",
+    ),
+    // info panel
+    ("Untitled", "Unbenannt"),
+    ("{} by {}", "{} von {}"),
+    ("row {}, col {}", "Zeile {}, Spalte {}"),
+    ("value: {}", "Wert: {}"),
+    ("state: {}", "Zustand: {}"),
+    ("age: {} generation", "Alter: {} Generation"),
+    ("age: {} generations", "Alter: {} Generationen"),
+    ("neighbours: {}", "Nachbarn: {}"),
+    (
+        crate::command::HELP,
+        "\
+load <MUSTER>, fetch <URL>, rule <REGEL>, speed <MS>, resize <ZEILEN> <SPALTEN>, \
+seed <N> [density <P>], density <P>, step <N>, save <DATEI>, restore <DATEI>, \
+name [TEXT], author [TEXT], comment [TEXT], export <DATEI>, predecessor, \
+topology <plane|torus|klein|cross|mirror>, noise <P>, \
+palette <classic|deuteranopia|protanopia>, contrast <on|off>, font <SKALIERUNG>, clear",
+    ),
+];
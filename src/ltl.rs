@@ -0,0 +1,171 @@
+//! Larger than Life rules
+//!
+//! Neighbours are counted in a large Moore neighbourhood, to keep stepping
+//! fast regardless of the radius the counts are read from a summed-area table
+//! instead of visiting every cell of the neighbourhood. The table covers the
+//! cells the topology glues beyond the edges as well.
+
+use std::fmt;
+
+use grid::Grid;
+
+use crate::hrot::Hrot;
+use crate::topology::Topology;
+use crate::CellState;
+
+pub const MAX_RADIUS: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LtlRule {
+    pub radius: u8,
+
+    /// Total number of states, `2` unless cells take a few generations to die
+    pub states: u8,
+
+    /// Whether a cell counts towards its own neighbour count
+    pub middle: bool,
+
+    /// Inclusive range of neighbour counts an alive cell survives with
+    pub survival: (u32, u32),
+
+    /// Inclusive range of neighbour counts a dead cell is born with
+    pub birth: (u32, u32),
+}
+
+impl LtlRule {
+    /// R5,C0,M1,S34-58,B34-45,NM
+    pub const BUGS: Self = Self {
+        radius: 5,
+        states: 2,
+        middle: true,
+        survival: (34, 58),
+        birth: (34, 45),
+    };
+
+    pub fn next_state(&self, cell: CellState, alive_neighbours: usize) -> CellState {
+        let n = alive_neighbours as u32;
+
+        match cell.0 {
+            0 if (self.birth.0..=self.birth.1).contains(&n) => CellState::ALIVE,
+            0 => CellState::DEAD,
+            1 if (self.survival.0..=self.survival.1).contains(&n) => CellState::ALIVE,
+            state if state + 1 < self.states => CellState(state + 1),
+            _ => CellState::DEAD,
+        }
+    }
+
+    /// Writes the next generation of the grid into `new_grid`, which has to
+    /// have the same size
+    pub fn step_into(
+        &self,
+        topology: Topology,
+        grid: &Grid<CellState>,
+        new_grid: &mut Grid<CellState>,
+    ) {
+        let (rows, cols) = (grid.rows(), grid.cols());
+        let radius = self.radius as usize;
+
+        // sums[r][c] is the number of alive cells above and to the left of
+        // (r, c), starting `radius` cells beyond the top left corner
+        let (padded_rows, padded_cols) = (rows + 2 * radius, cols + 2 * radius);
+        let mut sums = vec![vec![0u32; padded_cols + 1]; padded_rows + 1];
+        for row in 0..padded_rows {
+            for col in 0..padded_cols {
+                let alive = topology
+                    .cell(
+                        row as i32 - radius as i32,
+                        col as i32 - radius as i32,
+                        rows,
+                        cols,
+                    )
+                    .is_some_and(|(row, col)| grid[row][col].is_alive());
+
+                sums[row + 1][col + 1] =
+                    alive as u32 + sums[row][col + 1] + sums[row + 1][col] - sums[row][col];
+            }
+        }
+
+        for row in 0..rows {
+            let (top, bottom) = (row, row + 2 * radius + 1);
+
+            for col in 0..cols {
+                let (left, right) = (col, col + 2 * radius + 1);
+
+                let mut n =
+                    sums[bottom][right] + sums[top][left] - sums[top][right] - sums[bottom][left];
+
+                if !self.middle && grid[row][col].is_alive() {
+                    n -= 1;
+                }
+
+                new_grid[row][col] = self.next_state(grid[row][col], n as usize);
+            }
+        }
+    }
+}
+
+impl fmt::Display for LtlRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Hrot::from(self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use crate::topology::TOPOLOGIES;
+
+    /// The next generation by counting every neighbour on its own
+    fn counted(rule: &LtlRule, topology: Topology, grid: &Grid<CellState>) -> Grid<CellState> {
+        let (rows, cols) = grid.size();
+        let radius = rule.radius as i32;
+        let mut next = Grid::new(rows, cols);
+
+        for ((row, col), cell) in grid.indexed_iter() {
+            let mut n = 0;
+            for dr in -radius..=radius {
+                for dc in -radius..=radius {
+                    if (dr, dc) == (0, 0) && !rule.middle {
+                        continue;
+                    }
+
+                    n += topology
+                        .cell(row as i32 + dr, col as i32 + dc, rows, cols)
+                        .is_some_and(|(row, col)| grid[row][col].is_alive())
+                        as usize;
+                }
+            }
+
+            next[row][col] = rule.next_state(*cell, n);
+        }
+
+        next
+    }
+
+    #[test]
+    fn counts_the_neighbours_across_every_topology() {
+        let rule = LtlRule {
+            radius: 3,
+            states: 3,
+            middle: false,
+            survival: (8, 20),
+            birth: (10, 14),
+        };
+
+        let mut rng = Rng::new(7);
+        let mut grid = Grid::new(12, 17);
+        grid.iter_mut()
+            .filter(|_| rng.chance(0.4))
+            .for_each(|cell| *cell = CellState::ALIVE);
+
+        for rule in [rule, LtlRule::BUGS] {
+            for topology in TOPOLOGIES {
+                let mut next = Grid::new(12, 17);
+                rule.step_into(topology, &grid, &mut next);
+                assert!(next == counted(&rule, topology, &grid), "{topology}");
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Overview of the live cells in a corner of the window while zoomed in,
+//! with the part of the grid in view outlined
+
+use crate::camera::Rect;
+use crate::color::Color;
+use crate::render::{Cell, Shape};
+use crate::{HEIGHT, WIDTH};
+
+/// Side of the minimap in window pixels
+const SIZE: f32 = 160.0;
+const MARGIN: f32 = 10.0;
+
+/// Pixels along each side the live cells are rasterized into
+const RESOLUTION: usize = 64;
+
+/// Space around the live cells and the viewport, as a fraction of the region
+const PADDING: f32 = 0.05;
+
+const BACKGROUND: Color = Color::rgba(0, 0, 0, 200);
+const LIVE: Color = Color::rgb(200, 200, 200);
+const VIEWPORT: Color = Color::rgb(255, 200, 0);
+
+pub struct Minimap {
+    /// Square part of the world shown, covering the live cells and the
+    /// viewport
+    region: Rect,
+}
+
+impl Minimap {
+    /// `live` are the world coordinates of the live cells
+    pub fn new(live: &[(f32, f32)], viewport: Rect) -> Self {
+        let (mut left, mut top) = (viewport.0, viewport.1);
+        let (mut right, mut bottom) = (viewport.0 + viewport.2, viewport.1 + viewport.3);
+
+        for &(x, y) in live {
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x);
+            bottom = bottom.max(y);
+        }
+
+        let side = (right - left).max(bottom - top) * (1.0 + 2.0 * PADDING);
+        let center = ((left + right) / 2.0, (top + bottom) / 2.0);
+
+        Self {
+            region: (center.0 - side / 2.0, center.1 - side / 2.0, side, side),
+        }
+    }
+
+    /// Window rectangle the minimap is drawn into
+    fn bounds() -> Rect {
+        (
+            WIDTH as f32 - SIZE - MARGIN,
+            HEIGHT as f32 - SIZE - MARGIN,
+            SIZE,
+            SIZE,
+        )
+    }
+
+    /// Window row of the top edge, anything above it stays clear of the
+    /// minimap
+    pub fn top() -> f32 {
+        Self::bounds().1 - MARGIN
+    }
+
+    pub fn contains(x: f32, y: f32) -> bool {
+        let (left, top, width, height) = Self::bounds();
+        (left..left + width).contains(&x) && (top..top + height).contains(&y)
+    }
+
+    /// World coordinates under a window position on the minimap
+    pub fn to_world(&self, x: f32, y: f32) -> (f32, f32) {
+        let (left, top, size, _) = Self::bounds();
+        let scale = self.region.2 / size;
+
+        (
+            self.region.0 + (x - left) * scale,
+            self.region.1 + (y - top) * scale,
+        )
+    }
+
+    fn to_window(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let (left, top, size, _) = Self::bounds();
+        let scale = size / self.region.2;
+
+        (
+            left + (x - self.region.0) * scale,
+            top + (y - self.region.1) * scale,
+        )
+    }
+
+    /// Background, the live cells rasterized into [`RESOLUTION`] pixels
+    /// along each side and the outline of the viewport
+    pub fn draw(&self, live: &[(f32, f32)], viewport: Rect, cells: &mut Vec<Cell>) {
+        let (left, top, size, _) = Self::bounds();
+        let rect = |x: f32, y: f32, width: f32, height: f32, color: Color| Cell {
+            shape: Shape::Rect { width, height },
+            position: (x, y),
+            color,
+        };
+
+        cells.push(rect(left, top, size, size, BACKGROUND));
+
+        let pixel = size / RESOLUTION as f32;
+        let mut raster = vec![false; RESOLUTION * RESOLUTION];
+
+        for &position in live {
+            let (x, y) = self.to_window(position);
+            let col = ((x - left) / pixel) as usize;
+            let row = ((y - top) / pixel) as usize;
+
+            if row < RESOLUTION && col < RESOLUTION {
+                raster[row * RESOLUTION + col] = true;
+            }
+        }
+
+        for (idx, _) in raster.iter().enumerate().filter(|(_, live)| **live) {
+            let (row, col) = (idx / RESOLUTION, idx % RESOLUTION);
+            cells.push(rect(
+                left + col as f32 * pixel,
+                top + row as f32 * pixel,
+                pixel,
+                pixel,
+                LIVE,
+            ));
+        }
+
+        let (x, y) = self.to_window((viewport.0, viewport.1));
+        let (right, bottom) = self.to_window((viewport.0 + viewport.2, viewport.1 + viewport.3));
+        let (width, height) = (right - x, bottom - y);
+
+        cells.extend([
+            rect(x, y, width, 1.0, VIEWPORT),
+            rect(x, bottom - 1.0, width, 1.0, VIEWPORT),
+            rect(x, y, 1.0, height, VIEWPORT),
+            rect(right - 1.0, y, 1.0, height, VIEWPORT),
+        ]);
+    }
+}
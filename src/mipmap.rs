@@ -0,0 +1,78 @@
+//! Live cells counted in square blocks of the grid, to draw grids whose
+//! cells are smaller than a pixel
+//!
+//! Every level halves the resolution of the one before it: the first counts
+//! the live cells of the blocks of 2x2 cells, the next of 4x4 cells and so on
+//! until one block covers the whole grid. A cell changing only changes one
+//! count on every level.
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::CellState;
+
+pub struct Mipmap {
+    rows: usize,
+    cols: usize,
+
+    /// Live cells of the blocks of `2 << level` cells on each side, blocks at
+    /// the right and bottom edge may cover fewer cells
+    levels: Vec<Grid<u32>>,
+}
+
+impl Mipmap {
+    /// Counts the cells which are alive in the automaton
+    pub fn new(grid: &Grid<CellState>, automaton: &Automaton) -> Self {
+        let (rows, cols) = grid.size();
+        let mut levels: Vec<Grid<u32>> = Vec::new();
+
+        loop {
+            let (above_rows, above_cols) = levels.last().map_or((rows, cols), Grid::size);
+            if above_rows <= 1 && above_cols <= 1 && !levels.is_empty() {
+                break;
+            }
+
+            let mut level = Grid::new(above_rows.div_ceil(2), above_cols.div_ceil(2));
+            for row in 0..above_rows {
+                for col in 0..above_cols {
+                    level[row / 2][col / 2] += match levels.last() {
+                        Some(above) => above[row][col],
+                        None => automaton.is_alive(grid[row][col]) as u32,
+                    };
+                }
+            }
+            levels.push(level);
+        }
+
+        Self { rows, cols, levels }
+    }
+
+    /// Counts the cell as having come to life or died
+    pub fn update(&mut self, row: usize, col: usize, alive: bool) {
+        for (level, counts) in self.levels.iter_mut().enumerate() {
+            let count = &mut counts[row >> (level + 1)][col >> (level + 1)];
+            if alive {
+                *count += 1;
+            } else {
+                *count -= 1;
+            }
+        }
+    }
+
+    /// Share of the cells which are alive in the block of the size, a power
+    /// of two of at least 2, at the position counted in blocks
+    pub fn density(&self, size: usize, row: usize, col: usize) -> f32 {
+        let size = self.block_size(size);
+        let level = size.trailing_zeros() as usize - 1;
+
+        let covered_rows = size.min(self.rows - row * size);
+        let covered_cols = size.min(self.cols - col * size);
+        self.levels[level][row][col] as f32 / (covered_rows * covered_cols) as f32
+    }
+
+    /// The size of the nearest level, blocks larger than the grid are as large
+    /// as the last level's
+    fn block_size(&self, size: usize) -> usize {
+        2 << ((size.trailing_zeros() as usize).clamp(1, self.levels.len()) - 1)
+    }
+}
@@ -0,0 +1,22 @@
+//! Downloading patterns over HTTP, built with the `net` feature
+
+use std::time::Duration;
+
+use crate::patterns;
+use crate::rle::Pattern;
+
+/// Downloads taking longer than this are given up on
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Downloads a pattern file, e.g. from LifeWiki or Catagolue
+pub fn fetch(url: &str) -> Result<Pattern, String> {
+    let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+    let text = agent
+        .get(url)
+        .call()
+        .map_err(|err| format!("Couldn't fetch {url}: {err}"))?
+        .into_string()
+        .map_err(|err| format!("Couldn't read {url}: {err}"))?;
+
+    patterns::parse(url, &text)
+}
@@ -0,0 +1,188 @@
+//! Talking to other instances of the game: sharing one board between several
+//! windows over TCP and, with the `net` feature, downloading patterns
+//!
+//! A shared board is kept by the host, which alone steps it. Every message is
+//! one line of text, except for the pattern following a `sync` line:
+//!
+//! ```text
+//! hello                      client -> host, asks for the whole board
+//! set <row> <col> <state>    client -> host, sets one cell
+//! play / pause               both ways, the host sends the new state back
+//! step                       client -> host, steps once
+//! sync <gen> <len>           both ways, followed by len bytes of RLE
+//! ```
+//!
+//! A client sends a `sync` for every edit other than toggling a cell, which
+//! replaces the host's board and so reaches the other clients.
+
+#[cfg(feature = "net")]
+mod fetch;
+mod session;
+
+use std::io::{self, BufRead, Read};
+use std::str::FromStr;
+
+#[cfg(feature = "net")]
+pub use fetch::fetch;
+pub use session::{Event, Session};
+
+/// Larger patterns are refused instead of allocating whatever a peer asks for
+const MAX_PATTERN_LEN: usize = 64 << 20;
+
+/// Longest line of a message, not counting the pattern of a `sync`, longer
+/// ones are refused instead of buffered
+const MAX_LINE_LEN: u64 = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Hello,
+    Set {
+        row: usize,
+        col: usize,
+        state: u8,
+    },
+    Play,
+    Pause,
+    Step,
+
+    /// The whole board as RLE, including its rule
+    Sync {
+        generation: u64,
+        pattern: String,
+    },
+}
+
+impl Message {
+    /// The message as sent over the connection
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Hello => b"hello\n".to_vec(),
+            Self::Set { row, col, state } => format!("set {row} {col} {state}\n").into_bytes(),
+            Self::Play => b"play\n".to_vec(),
+            Self::Pause => b"pause\n".to_vec(),
+            Self::Step => b"step\n".to_vec(),
+            Self::Sync {
+                generation,
+                pattern,
+            } => format!("sync {generation} {}\n{pattern}", pattern.len()).into_bytes(),
+        }
+    }
+
+    /// Reads the next message, `None` once the connection was closed
+    pub fn read(input: &mut impl BufRead) -> io::Result<Option<Self>> {
+        let mut line = String::new();
+        if input.by_ref().take(MAX_LINE_LEN).read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        if !line.ends_with('\n') && line.len() as u64 == MAX_LINE_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The message is too long",
+            ));
+        }
+
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid message '{}'", line.trim()),
+            )
+        };
+        fn number<T: FromStr>(word: Option<&str>) -> Option<T> {
+            word?.parse().ok()
+        }
+
+        let mut words = line.split_whitespace();
+        let message = match words.next() {
+            Some("hello") => Self::Hello,
+            Some("play") => Self::Play,
+            Some("pause") => Self::Pause,
+            Some("step") => Self::Step,
+            Some("set") => {
+                let (Some(row), Some(col), Some(state)) = (
+                    number(words.next()),
+                    number(words.next()),
+                    number(words.next()),
+                ) else {
+                    return Err(invalid());
+                };
+
+                Self::Set { row, col, state }
+            }
+            Some("sync") => {
+                let (Some(generation), Some(len)) =
+                    (number(words.next()), number::<usize>(words.next()))
+                else {
+                    return Err(invalid());
+                };
+
+                if len > MAX_PATTERN_LEN {
+                    return Err(invalid());
+                }
+
+                let mut pattern = vec![0; len];
+                input.read_exact(&mut pattern)?;
+
+                Self::Sync {
+                    generation,
+                    pattern: String::from_utf8(pattern).map_err(|_| invalid())?,
+                }
+            }
+            _ => return Err(invalid()),
+        };
+
+        Ok(Some(message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(bytes: &[u8]) -> io::Result<Option<Message>> {
+        Message::read(&mut io::Cursor::new(bytes))
+    }
+
+    #[test]
+    fn reads_back_what_it_sends() {
+        let messages = [
+            Message::Hello,
+            Message::Set {
+                row: 3,
+                col: 1024,
+                state: 2,
+            },
+            Message::Play,
+            Message::Pause,
+            Message::Step,
+            Message::Sync {
+                generation: 42,
+                pattern: String::from("x = 3, y = 1, rule = B3/S23\n3o!\n"),
+            },
+            Message::Hello,
+        ];
+
+        let bytes = messages
+            .iter()
+            .flat_map(Message::encode)
+            .collect::<Vec<_>>();
+        let mut input = io::Cursor::new(bytes);
+
+        for message in &messages {
+            assert_eq!(Message::read(&mut input).unwrap().as_ref(), Some(message));
+        }
+        assert_eq!(Message::read(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn refuses_messages_it_would_have_to_buffer() {
+        let long = format!("set 1 1 1{}\n", " ".repeat(MAX_LINE_LEN as usize));
+        assert!(read(long.as_bytes()).is_err());
+        assert!(read(format!("sync 0 {}\n", MAX_PATTERN_LEN + 1).as_bytes()).is_err());
+        assert!(read(b"sync 0 10\nx = 1").is_err());
+        assert!(read(b"sync 0\n").is_err());
+        assert!(read(b"set 1 1 256\n").is_err());
+        assert!(read(b"set -1 1 1\n").is_err());
+        assert!(read(b"jump\n").is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! Hosting or joining a shared board
+
+use std::io::{self, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use grid::Grid;
+
+use super::Message;
+use crate::{period, rle, CellState};
+
+/// Peers which don't take a message within this time are disconnected
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Messages waiting to be written to a peer, once that many are waiting it
+/// counts as stuck and is disconnected
+const QUEUE_LEN: usize = 64;
+
+pub enum Event {
+    Message(Message),
+
+    /// A client left, or on a client the connection to the host was lost
+    Disconnected,
+}
+
+/// A connection written to by its own thread
+struct Peer {
+    stream: TcpStream,
+    outgoing: SyncSender<Arc<[u8]>>,
+}
+
+enum Role {
+    Host { clients: Arc<Mutex<Vec<Peer>>> },
+    Client { host: Peer },
+}
+
+/// Connection to the other players, read and written on background threads
+/// so the window never waits for the network
+pub struct Session {
+    role: Role,
+    events: Receiver<Event>,
+
+    /// Generation, hash and rule of the board both sides last had
+    shared: Option<(u64, u64, String)>,
+
+    /// Whether the clients were last told the board is playing
+    shared_playing: Option<bool>,
+}
+
+impl Session {
+    /// Accepts players on the address, e.g. `0.0.0.0:7777`
+    pub fn host(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let (sender, events) = mpsc::channel();
+
+        let accepted = Arc::clone(&clients);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let Ok(peer) = prepare(&stream).and_then(|_| Peer::new(&stream)) else {
+                    continue;
+                };
+
+                spawn_reader(stream, sender.clone());
+                if let Ok(mut clients) = accepted.lock() {
+                    clients.push(peer);
+                }
+            }
+        });
+
+        Ok(Self::new(Role::Host { clients }, events))
+    }
+
+    /// Connects to a host and asks it for its board
+    pub fn join(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        prepare(&stream)?;
+
+        let host = Peer::new(&stream)?;
+        host.send(Message::Hello.encode().into());
+
+        let (sender, events) = mpsc::channel();
+        spawn_reader(stream, sender);
+
+        Ok(Self::new(Role::Client { host }, events))
+    }
+
+    fn new(role: Role, events: Receiver<Event>) -> Self {
+        Self {
+            role,
+            events,
+            shared: None,
+            shared_playing: None,
+        }
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self.role, Role::Host { .. })
+    }
+
+    /// Number of connected clients, on a client `1` for the host
+    pub fn peers(&self) -> usize {
+        match &self.role {
+            Role::Host { clients } => clients.lock().map_or(0, |clients| clients.len()),
+            Role::Client { .. } => 1,
+        }
+    }
+
+    /// Everything received since the last call
+    pub fn receive(&self) -> Vec<Event> {
+        self.events.try_iter().collect()
+    }
+
+    /// Queues the message for the host, or from the host for every client,
+    /// dropping the clients which stopped taking messages
+    pub fn send(&self, message: &Message) {
+        let bytes: Arc<[u8]> = message.encode().into();
+
+        match &self.role {
+            Role::Host { clients } => {
+                if let Ok(mut clients) = clients.lock() {
+                    clients.retain(|client| client.send(Arc::clone(&bytes)));
+                }
+            }
+            // a lost connection is noticed by the reader
+            Role::Client { host } => {
+                host.send(bytes);
+            }
+        }
+    }
+
+    /// Sends the board to the other side if it changed since both last had
+    /// the same one, a board from a client replaces the host's
+    pub fn share_board(&mut self, grid: &Grid<CellState>, rule: &str, generation: u64) {
+        // a client has nothing to send before the host's board arrived
+        if !self.is_host() && self.shared.is_none() {
+            return;
+        }
+
+        let board = Some((generation, period::hash(grid), rule.to_string()));
+        if self.shared != board {
+            self.shared = board;
+            self.send(&Message::Sync {
+                generation,
+                pattern: rle::encode(grid, rule, &rle::Info::default()),
+            });
+        }
+    }
+
+    /// Remembers the board as the one the other side has too, after it was
+    /// received or the other side was sent the edit that made it
+    pub fn mark_shared(&mut self, grid: &Grid<CellState>, rule: &str, generation: u64) {
+        self.shared = Some((generation, period::hash(grid), rule.to_string()));
+    }
+
+    /// Sends the host's board and whether it is playing to the clients, if
+    /// either changed since they were last sent
+    pub fn share(&mut self, grid: &Grid<CellState>, rule: &str, generation: u64, playing: bool) {
+        self.share_board(grid, rule, generation);
+
+        if self.shared_playing != Some(playing) {
+            self.shared_playing = Some(playing);
+            self.send(if playing {
+                &Message::Play
+            } else {
+                &Message::Pause
+            });
+        }
+    }
+
+    /// Makes the next [`Session::share`] send everything again, for a client
+    /// which just joined
+    pub fn reshare(&mut self) {
+        self.shared = None;
+        self.shared_playing = None;
+    }
+}
+
+// the reader threads hold clones of the streams, which would keep the
+// connections open
+impl Drop for Session {
+    fn drop(&mut self) {
+        match &self.role {
+            Role::Host { clients } => {
+                if let Ok(clients) = clients.lock() {
+                    for client in clients.iter() {
+                        let _ = client.stream.shutdown(Shutdown::Both);
+                    }
+                }
+            }
+            Role::Client { host } => {
+                let _ = host.stream.shutdown(Shutdown::Both);
+            }
+        }
+    }
+}
+
+impl Peer {
+    /// Starts the thread writing to the connection, which closes it once a
+    /// write fails
+    fn new(stream: &TcpStream) -> io::Result<Self> {
+        let (outgoing, queue) = mpsc::sync_channel::<Arc<[u8]>>(QUEUE_LEN);
+        let mut writer = stream.try_clone()?;
+
+        thread::spawn(move || {
+            for bytes in queue {
+                if writer.write_all(&bytes).is_err() {
+                    let _ = writer.shutdown(Shutdown::Both);
+                    return;
+                }
+            }
+        });
+
+        Ok(Self {
+            stream: stream.try_clone()?,
+            outgoing,
+        })
+    }
+
+    /// Queues the bytes, closing the connection and returning `false` if
+    /// the peer stopped taking them
+    fn send(&self, bytes: Arc<[u8]>) -> bool {
+        let queued = self.outgoing.try_send(bytes).is_ok();
+        if !queued {
+            let _ = self.stream.shutdown(Shutdown::Both);
+        }
+        queued
+    }
+}
+
+fn prepare(stream: &TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))
+}
+
+/// Forwards the messages of the connection until it closes or sends
+/// something invalid
+fn spawn_reader(stream: TcpStream, sender: Sender<Event>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(&stream);
+
+        while let Ok(Some(message)) = Message::read(&mut reader) {
+            if sender.send(Event::Message(message)).is_err() {
+                return;
+            }
+        }
+
+        let _ = stream.shutdown(Shutdown::Both);
+        let _ = sender.send(Event::Disconnected);
+    });
+}
@@ -0,0 +1,63 @@
+//! Births and survivals failing at random, to see how robust patterns are
+//!
+//! The failures only depend on the seed and the grid being stepped, so a run
+//! with the same seed fails the same cells again and a generation recreated
+//! from the history is the one the game went through.
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::rng::Rng;
+use crate::{period, CellState};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Noise {
+    /// Probability of every birth and survival failing, no noise at 0
+    pub level: f64,
+    pub seed: u64,
+}
+
+/// The level is always between 0 and 1, never NaN
+impl Eq for Noise {}
+
+impl Noise {
+    pub fn is_enabled(&self) -> bool {
+        self.level > 0.0
+    }
+
+    /// Fails births and survivals of the step from the grid before into the
+    /// grid. A failed birth leaves the cell dead, a failed survival lets it
+    /// die or start decaying with more than two states. Only Life-like,
+    /// Generations and Larger than Life rules are noisy.
+    pub fn apply(
+        &self,
+        automaton: &Automaton,
+        before: &Grid<CellState>,
+        grid: &mut Grid<CellState>,
+    ) {
+        if !self.is_enabled()
+            || before.size() != grid.size()
+            || !matches!(automaton, Automaton::Life(_) | Automaton::LargerThanLife(_))
+        {
+            return;
+        }
+
+        let decayed = if automaton.states() > 2 {
+            CellState(2)
+        } else {
+            CellState::DEAD
+        };
+
+        let mut rng = Rng::new(self.seed ^ period::hash(before));
+        for (cell, before) in grid.iter_mut().zip(before.iter()) {
+            if *cell == CellState::ALIVE && rng.chance(self.level) {
+                *cell = if *before == CellState::ALIVE {
+                    decayed
+                } else {
+                    CellState::DEAD
+                };
+            }
+        }
+    }
+}
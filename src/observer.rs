@@ -0,0 +1,217 @@
+//! Callbacks for code which reacts to the simulation instead of polling it
+//!
+//! An [`Observer`] is told about every step the game takes: the generation
+//! it stepped into, every cell the step changed and, once, that the grid
+//! settled into a still life or an oscillator. Editing the grid or jumping
+//! to another generation in between two steps starts watching for it
+//! settling again.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use grid::Grid;
+
+use crate::engine::naive::Bounds;
+use crate::period::PeriodDetector;
+use crate::CellState;
+
+/// Every method does nothing by default, so observers only implement the
+/// events they care about
+pub trait Observer {
+    /// After every step, with the grid of the generation it stepped into
+    fn on_generation(&mut self, _generation: u64, _grid: &Grid<CellState>) {}
+
+    /// For every cell a step changed, before [`Observer::on_generation`]
+    fn on_cell_changed(&mut self, _row: usize, _col: usize, _before: CellState, _after: CellState) {
+    }
+
+    /// The grid of the generation is the same as the one `period`
+    /// generations before, a period of 1 is a still life
+    fn on_stabilized(&mut self, _generation: u64, _period: u64) {}
+
+    /// Whether [`Observer::on_stabilized`] is of any use to the observer,
+    /// the generations are only hashed to detect it if one wants it
+    fn wants_stabilized(&self) -> bool {
+        false
+    }
+}
+
+/// Lets the observer be shared with whoever reads what it saw
+impl<T: Observer> Observer for Rc<RefCell<T>> {
+    fn on_generation(&mut self, generation: u64, grid: &Grid<CellState>) {
+        self.borrow_mut().on_generation(generation, grid);
+    }
+
+    fn on_cell_changed(&mut self, row: usize, col: usize, before: CellState, after: CellState) {
+        self.borrow_mut().on_cell_changed(row, col, before, after);
+    }
+
+    fn on_stabilized(&mut self, generation: u64, period: u64) {
+        self.borrow_mut().on_stabilized(generation, period);
+    }
+
+    fn wants_stabilized(&self) -> bool {
+        self.borrow().wants_stabilized()
+    }
+}
+
+#[derive(Default)]
+pub struct Observers {
+    observers: Vec<Box<dyn Observer>>,
+
+    /// Generations stepped into since the grid was last edited
+    detector: PeriodDetector,
+    stabilized: bool,
+}
+
+impl Observers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, observer: impl Observer + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    /// Tells every observer about a step from the grid before it into the
+    /// grid of the generation. Only the cells within the bounds can have
+    /// been changed by the step, `edited` is set if the grid before isn't
+    /// the one the previous step stepped into.
+    pub fn stepped(
+        &mut self,
+        generation: u64,
+        before: &Grid<CellState>,
+        grid: &Grid<CellState>,
+        changed: &[Bounds],
+        edited: bool,
+    ) {
+        if self.observers.is_empty() {
+            return;
+        }
+
+        if before.size() == grid.size() {
+            for (idx, bounds) in changed.iter().enumerate() {
+                for row in bounds.top..=bounds.bottom {
+                    for col in bounds.left..=bounds.right {
+                        let (before, after) = (before[row][col], grid[row][col]);
+
+                        if before != after
+                            && !changed[..idx]
+                                .iter()
+                                .any(|bounds| bounds.contains(row, col))
+                        {
+                            for observer in &mut self.observers {
+                                observer.on_cell_changed(row, col, before, after);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for observer in &mut self.observers {
+            observer.on_generation(generation, grid);
+        }
+
+        if !self
+            .observers
+            .iter()
+            .any(|observer| observer.wants_stabilized())
+        {
+            return;
+        }
+
+        if edited {
+            self.detector = PeriodDetector::new();
+            self.detector.observe(generation.saturating_sub(1), before);
+            self.stabilized = false;
+        }
+
+        match self.detector.observe(generation, grid) {
+            Some(period) if !self.stabilized => {
+                self.stabilized = true;
+                for observer in &mut self.observers {
+                    observer.on_stabilized(generation, period);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ant::Ant;
+    use crate::game::Game;
+
+    #[derive(Default)]
+    struct Seen {
+        changed: Vec<(usize, usize)>,
+        stabilized: Option<(u64, u64)>,
+    }
+
+    impl Observer for Seen {
+        fn on_cell_changed(
+            &mut self,
+            row: usize,
+            col: usize,
+            _before: CellState,
+            _after: CellState,
+        ) {
+            self.changed.push((row, col));
+        }
+
+        fn on_stabilized(&mut self, generation: u64, period: u64) {
+            self.stabilized = Some((generation, period));
+        }
+
+        fn wants_stabilized(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn reports_the_cells_the_step_and_the_ants_changed() {
+        let mut game = Game::new(0);
+        game.grid = Grid::new(32, 32);
+        (7..=9).for_each(|col| game.grid[8][col] = CellState::ALIVE);
+        game.ants = vec![Ant::new(24, 24)];
+
+        let seen = Rc::new(RefCell::new(Seen::default()));
+        game.observers.add(Rc::clone(&seen));
+
+        let before = game.grid.clone();
+        game.step();
+
+        let mut changed = seen.borrow().changed.clone();
+        changed.sort();
+        let expected = before
+            .indexed_iter()
+            .filter(|((row, col), cell)| game.grid[*row][*col] != **cell)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+        assert_eq!(changed, expected);
+        assert_eq!(changed.len(), 5);
+    }
+
+    #[test]
+    fn reports_settling_again_after_edits() {
+        let mut game = Game::new(0);
+        game.grid = Grid::new(16, 16);
+        (7..=9).for_each(|col| game.grid[8][col] = CellState::ALIVE);
+
+        let seen = Rc::new(RefCell::new(Seen::default()));
+        game.observers.add(Rc::clone(&seen));
+
+        game.step();
+        game.step();
+        assert_eq!(seen.borrow_mut().stabilized.take(), Some((2, 2)));
+        game.step();
+        assert_eq!(seen.borrow().stabilized, None);
+
+        game.grid[2][2] = CellState::ALIVE;
+        (0..3).for_each(|_| game.step());
+        assert_eq!(seen.borrow().stabilized, Some((6, 2)));
+    }
+}
@@ -0,0 +1,150 @@
+//! Colors the cells are drawn in
+//!
+//! Besides the classic colors there are palettes for deuteranopia and
+//! protanopia, built from the Okabe-Ito colors. Simulated for either of them,
+//! the states a rule tells apart and the cells being born and dying while
+//! steps are explained stay clearly different, unlike the classic yellow and
+//! orange or green and red.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::automaton::Automaton;
+use crate::color::Color;
+use crate::locale::fill;
+use crate::CellState;
+
+pub const PALETTES: [Palette; 3] = [Palette::Classic, Palette::Deuteranopia, Palette::Protanopia];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Palette {
+    #[default]
+    Classic,
+    Deuteranopia,
+    Protanopia,
+}
+
+/// Colors of the states every automaton has in common
+struct Colors {
+    alive: Color,
+
+    /// Dying cells fade from alive towards this the closer they get to death
+    faded: Color,
+    electron_head: Color,
+    electron_tail: Color,
+    second_player: Color,
+
+    /// Cells about to be born or die, drawn over the grid
+    birth: Color,
+    death: Color,
+}
+
+static CLASSIC: Colors = Colors {
+    alive: Color::rgb(200, 200, 0),
+    faded: Color::rgb(60, 20, 80),
+    electron_head: Color::rgb(0, 100, 255),
+    electron_tail: Color::rgb(255, 60, 0),
+    second_player: Color::rgb(0, 200, 255),
+    birth: Color::rgba(0, 255, 0, 140),
+    death: Color::rgba(255, 0, 0, 140),
+};
+
+static DEUTERANOPIA: Colors = Colors {
+    alive: Color::rgb(240, 228, 66),
+    faded: Color::rgb(0, 60, 110),
+    electron_head: Color::rgb(0, 114, 178),
+    electron_tail: Color::rgb(213, 94, 0),
+    second_player: Color::rgb(86, 180, 233),
+    birth: Color::rgba(86, 180, 233, 160),
+    death: Color::rgba(213, 94, 0, 160),
+};
+
+/// Reds look dark with protanopia, so none of the colors rely on them
+static PROTANOPIA: Colors = Colors {
+    alive: Color::rgb(240, 228, 66),
+    faded: Color::rgb(30, 50, 130),
+    electron_head: Color::rgb(86, 180, 233),
+    electron_tail: Color::rgb(230, 230, 230),
+    second_player: Color::rgb(0, 114, 178),
+    birth: Color::rgba(86, 180, 233, 160),
+    death: Color::rgba(230, 159, 0, 160),
+};
+
+impl Palette {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Deuteranopia => "deuteranopia",
+            Self::Protanopia => "protanopia",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let idx = PALETTES.iter().position(|palette| *palette == self);
+        PALETTES[idx.map_or(0, |idx| (idx + 1) % PALETTES.len())]
+    }
+
+    fn colors(self) -> &'static Colors {
+        match self {
+            Self::Classic => &CLASSIC,
+            Self::Deuteranopia => &DEUTERANOPIA,
+            Self::Protanopia => &PROTANOPIA,
+        }
+    }
+
+    /// Fill color of a cell of the automaton in the given state
+    pub fn color(self, automaton: &Automaton, cell: CellState) -> Color {
+        let colors = self.colors();
+
+        match (automaton, cell.0) {
+            (_, 0) => Color::BLACK,
+            (Automaton::Wireworld, 1) => colors.electron_head,
+            (Automaton::Wireworld, 2) => colors.electron_tail,
+            (Automaton::Immigration, 2) => colors.second_player,
+            (Automaton::Wireworld, _) | (_, 1) => colors.alive,
+            (_, state) => {
+                let t = (state - 1) as f32 / (automaton.states() - 1) as f32;
+                let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+                let (alive, faded) = (colors.alive, colors.faded);
+
+                Color::rgb(
+                    lerp(alive.r, faded.r),
+                    lerp(alive.g, faded.g),
+                    lerp(alive.b, faded.b),
+                )
+            }
+        }
+    }
+
+    /// Translucent highlight of a cell about to be born
+    pub fn birth(self) -> Color {
+        self.colors().birth
+    }
+
+    /// Translucent highlight of a cell about to die
+    pub fn death(self) -> Color {
+        self.colors().death
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PALETTES
+            .into_iter()
+            .find(|palette| palette.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                fill(
+                    "Unknown palette '{}', expected classic, deuteranopia or protanopia",
+                    &[&s],
+                )
+            })
+    }
+}
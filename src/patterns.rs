@@ -0,0 +1,44 @@
+//! Built-in patterns which can be loaded by name
+
+use crate::plaintext;
+use crate::rle::{self, Pattern};
+
+/// Names and RLE of the built-in patterns, all of them for B3/S23
+pub const PATTERNS: &[(&str, &str)] = &[
+    ("glider", "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!"),
+    ("lwss", "x = 5, y = 4, rule = B3/S23\nbo2bo$o4b$o3bo$4o!"),
+    ("blinker", "x = 3, y = 1, rule = B3/S23\n3o!"),
+    ("pulsar", "x = 13, y = 13, rule = B3/S23\n2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    ("r-pentomino", "x = 3, y = 3, rule = B3/S23\nb2o$2o$bo!"),
+    ("acorn", "x = 7, y = 3, rule = B3/S23\nbo5b$3bo3b$2o2b3o!"),
+    ("diehard", "x = 8, y = 3, rule = B3/S23\n6bob$2o6b$bo3b3o!"),
+    ("gosper-gun", "x = 36, y = 9, rule = B3/S23\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!"),
+];
+
+pub fn find(name: &str) -> Option<Pattern> {
+    PATTERNS
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .and_then(|(_, rle)| rle::parse(rle).ok())
+}
+
+/// A built-in pattern, or else an RLE file with that path
+pub fn load(name: &str) -> Result<Pattern, String> {
+    if let Some(pattern) = find(name) {
+        return Ok(pattern);
+    }
+
+    let text = std::fs::read_to_string(name)
+        .map_err(|_| format!("'{name}' is neither a built-in pattern nor a readable file"))?;
+    parse(name, &text)
+}
+
+/// Parses the contents of a pattern file, plaintext if the name ends in
+/// `.cells` or it starts with a `!` comment and RLE otherwise
+pub fn parse(name: &str, text: &str) -> Result<Pattern, String> {
+    if name.ends_with(".cells") || text.trim_start().starts_with('!') {
+        plaintext::parse(text)
+    } else {
+        rle::parse(text)
+    }
+}
@@ -0,0 +1,44 @@
+//! Detects when a pattern has settled into a still life or an oscillator by
+//! remembering a hash of every generation it went through
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use grid::Grid;
+
+use crate::CellState;
+
+pub fn hash(grid: &Grid<CellState>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    grid.cols().hash(&mut hasher);
+    grid.flatten().hash(&mut hasher);
+
+    hasher.finish()
+}
+
+#[derive(Default)]
+pub struct PeriodDetector {
+    /// Generation each hash was first seen in
+    seen: HashMap<u64, u64>,
+}
+
+impl PeriodDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the grid of a generation, if the same grid was seen before
+    /// the pattern repeats and the period is returned
+    pub fn observe(&mut self, generation: u64, grid: &Grid<CellState>) -> Option<u64> {
+        let hash = hash(grid);
+
+        match self.seen.get(&hash) {
+            Some(&first) => Some(generation - first),
+            None => {
+                self.seen.insert(hash, generation);
+                None
+            }
+        }
+    }
+}
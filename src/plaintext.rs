@@ -0,0 +1,89 @@
+//! The plaintext `.cells` format of LifeWiki
+//!
+//! Lines starting with `!` are comments, every other line is a row of the
+//! pattern with `.` for dead and `O` for alive cells. Rows shorter than the
+//! longest one are padded with dead cells. `!Name:` and `!Author:` comments
+//! name the pattern and its author.
+
+use grid::Grid;
+
+use crate::rle::{Info, Pattern};
+use crate::CellState;
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut info = Info::default();
+    for comment in text.lines().filter_map(|line| line.strip_prefix('!')) {
+        let comment = comment.trim();
+
+        if let Some(name) = comment.strip_prefix("Name:") {
+            info.name = Some(name.trim().to_string());
+        } else if let Some(author) = comment.strip_prefix("Author:") {
+            info.author = Some(author.trim().to_string());
+        } else {
+            info.comments.push(comment.to_string());
+        }
+    }
+
+    let mut rows = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.starts_with('!'))
+        .collect::<Vec<_>>();
+
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    let cols = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0);
+    if cols == 0 {
+        return Err(String::from("The pattern is empty"));
+    }
+
+    let mut cells = Grid::new(rows.len(), cols);
+    for (row_idx, row) in rows.iter().enumerate() {
+        for (col_idx, cell) in row.chars().enumerate() {
+            match cell {
+                '.' => {}
+                'O' | '*' => cells[row_idx][col_idx] = CellState::ALIVE,
+                _ => return Err(format!("Unexpected '{cell}' in row {}", row_idx + 1)),
+            }
+        }
+    }
+
+    Ok(Pattern {
+        cells,
+        rule: None,
+        info,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_short_rows_and_reads_the_comments() {
+        let pattern = parse("!Name: Glider\n!Author: Richard K. Guy\n!\n.O\n..O\nOOO\n\n").unwrap();
+
+        assert_eq!(pattern.info.name.as_deref(), Some("Glider"));
+        assert_eq!(pattern.info.author.as_deref(), Some("Richard K. Guy"));
+        assert_eq!(pattern.info.comments, [""]);
+        assert_eq!(pattern.cells.size(), (3, 3));
+        assert_eq!(
+            pattern.cells.iter().filter(|cell| cell.is_alive()).count(),
+            5
+        );
+        assert!(pattern.cells[1][2].is_alive());
+        assert!(!pattern.cells[0][2].is_alive());
+    }
+
+    #[test]
+    fn refuses_empty_patterns_and_other_cells() {
+        assert!(parse("!Name: Nothing\n\n").is_err());
+        assert!(parse(".O\nOX").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+//! Searches for a grid which steps into the given one, a grid without any is
+//! a Garden of Eden
+//!
+//! The cells of the predecessor are decided one by one in reading order,
+//! trying the state the cell has in the target first. After every decision
+//! the cells whose neighbourhood includes it are checked: with the decided
+//! neighbours alive and the undecided ones either way, the range of counts
+//! they can still see must contain one which gives their state in the
+//! target. Once a decision can't be kept the search backtracks.
+//!
+//! Most grids are largely empty, so first only the cells near the live ones
+//! are searched with every cell further away dead. Only if that has no
+//! predecessor the search goes through the whole grid.
+//!
+//! The edges are glued together by the topology of the grid. On the plane
+//! the cells beyond them are dead, so a grid can be a Garden of Eden there
+//! only because nothing outside may help, even if it has predecessors on a
+//! larger grid.
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::engine::naive::Bounds;
+use crate::locale::fill;
+use crate::rule::Rule;
+use crate::topology::Topology;
+use crate::CellState;
+
+/// Grids with more cells are refused, the search takes exponential time
+pub const MAX_CELLS: usize = 64 * 64;
+
+/// Cells decided before a search gives up
+pub const BUDGET: u64 = 20_000_000;
+
+/// Cells around the live ones which may be alive in the first search
+const MARGIN: usize = 2;
+
+pub enum Predecessor {
+    Found(Grid<CellState>),
+    GardenOfEden,
+
+    /// The budget ran out before the search was done
+    Unknown,
+}
+
+impl Predecessor {
+    /// What the search found, to show once the predecessor is on the grid
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::Found(_) => {
+                "Found a predecessor and put it onto the grid, one step gives back the grid"
+            }
+            Self::GardenOfEden => "The grid is a Garden of Eden, no grid of its size steps into it",
+            Self::Unknown => "Gave up searching for a predecessor, the grid is too large",
+        }
+    }
+}
+
+pub fn find(
+    grid: &Grid<CellState>,
+    automaton: Automaton,
+    topology: Topology,
+    budget: u64,
+) -> Result<Predecessor, String> {
+    let rule = match automaton {
+        Automaton::Life(rule) if rule.states == 2 => rule,
+        _ => {
+            return Err(fill(
+                "Predecessors can only be searched for two state rules, not {}",
+                &[&automaton],
+            ))
+        }
+    };
+
+    if grid.rows() * grid.cols() > MAX_CELLS {
+        return Err(fill(
+            "The grid is too large to search for a predecessor, at most {} cells",
+            &[&MAX_CELLS],
+        ));
+    }
+
+    // with B0 the empty cells far away have to be alive before, and on
+    // other topologies there is no far away
+    let near = Bounds::live(grid)
+        .filter(|_| topology == Topology::Plane && !rule.next_state(CellState::DEAD, 0).is_alive());
+
+    if let Some(bounds) = near {
+        let (rows, cols) = grid.size();
+        let radius = rule.neighbourhood.radius() as usize;
+        let near = bounds.expanded(MARGIN + radius, rows, cols);
+
+        if near.rows() < rows || near.cols() < cols {
+            let cells = (near.top..=near.bottom)
+                .flat_map(|row| (near.left..=near.right).map(move |col| grid[row][col]))
+                .collect();
+            let mut search = Search::new(&Grid::from_vec(cells, near.cols()), rule, topology);
+
+            // the cells outside have to stay dead, which they only do if
+            // nothing next to them is alive
+            for row in 0..near.rows() {
+                for col in 0..near.cols() {
+                    let outside_near = (row < radius && near.top > 0)
+                        || (col < radius && near.left > 0)
+                        || (row + radius >= near.rows() && near.bottom + 1 < rows)
+                        || (col + radius >= near.cols() && near.right + 1 < cols);
+
+                    if outside_near {
+                        search.decide(row * near.cols() + col, false);
+                    }
+                }
+            }
+
+            if let Predecessor::Found(found) = search.run(budget) {
+                let mut predecessor = Grid::new(rows, cols);
+                for ((row, col), cell) in found.indexed_iter() {
+                    predecessor[near.top + row][near.left + col] = *cell;
+                }
+                return Ok(Predecessor::Found(predecessor));
+            }
+        }
+    }
+
+    Ok(Search::new(grid, rule, topology).run(budget))
+}
+
+struct Search {
+    cols: usize,
+    target: Vec<bool>,
+
+    /// Neighbour counts of every cell which give its state in the target,
+    /// bit `n` set for `n` alive neighbours, for the cell being dead and
+    /// being alive in the predecessor
+    allowed: Vec<[u64; 2]>,
+
+    /// Inside the grid, by cell, a cell which is glued to another one
+    /// several times is there as often
+    neighbours: Vec<Vec<usize>>,
+
+    /// Cells whose next state depends on the cell, including itself
+    dependents: Vec<Vec<usize>>,
+
+    decided: Vec<Option<bool>>,
+
+    /// Decided alive and undecided cells among the neighbours of every cell
+    alive: Vec<usize>,
+    undecided: Vec<usize>,
+}
+
+impl Search {
+    fn new(grid: &Grid<CellState>, rule: Rule, topology: Topology) -> Self {
+        let (rows, cols) = grid.size();
+        let count = rows * cols;
+
+        let neighbours: Vec<Vec<usize>> = (0..count)
+            .map(|idx| {
+                let (row, col) = (idx / cols, idx % cols);
+
+                rule.neighbourhood
+                    .offsets(row)
+                    .into_iter()
+                    .filter_map(|(dr, dc)| {
+                        let (row, col) =
+                            topology.cell(row as i32 + dr, col as i32 + dc, rows, cols)?;
+                        Some(row * cols + col)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut dependents: Vec<Vec<usize>> = (0..count).map(|idx| vec![idx]).collect();
+        for (idx, neighbours) in neighbours.iter().enumerate() {
+            for &neighbour in neighbours {
+                dependents[neighbour].push(idx);
+            }
+        }
+
+        let target: Vec<bool> = grid.iter().map(|cell| cell.is_alive()).collect();
+        let allowed = target
+            .iter()
+            .zip(&neighbours)
+            .map(|(&alive, neighbours)| {
+                [CellState::DEAD, CellState::ALIVE].map(|state| {
+                    (0..=neighbours.len())
+                        .filter(|&n| rule.next_state(state, n).is_alive() == alive)
+                        .fold(0, |mask, n| mask | 1 << n)
+                })
+            })
+            .collect();
+
+        Self {
+            cols,
+            target,
+            allowed,
+            undecided: neighbours.iter().map(Vec::len).collect(),
+            alive: vec![0; count],
+            neighbours,
+            dependents,
+            decided: vec![None; count],
+        }
+    }
+
+    /// Decides the cells which weren't decided before one after another
+    fn run(mut self, budget: u64) -> Predecessor {
+        let order: Vec<usize> = (0..self.target.len())
+            .filter(|&idx| self.decided[idx].is_none())
+            .collect();
+
+        // how many of the two states were tried for every cell in the order
+        let mut tried = vec![0u8; order.len()];
+        let mut decisions = 0;
+        let mut idx = 0;
+
+        loop {
+            if idx == order.len() {
+                let cells = self.decided.iter().map(|alive| {
+                    if *alive == Some(true) {
+                        CellState::ALIVE
+                    } else {
+                        CellState::DEAD
+                    }
+                });
+                return Predecessor::Found(Grid::from_vec(cells.collect(), self.cols));
+            }
+
+            if tried[idx] == 2 {
+                tried[idx] = 0;
+                if idx == 0 {
+                    return Predecessor::GardenOfEden;
+                }
+
+                idx -= 1;
+                self.undecide(order[idx]);
+                continue;
+            }
+
+            decisions += 1;
+            if decisions > budget {
+                return Predecessor::Unknown;
+            }
+
+            let cell = order[idx];
+            let alive = self.target[cell] == (tried[idx] == 0);
+            tried[idx] += 1;
+
+            self.decide(cell, alive);
+            if self.is_possible(cell) {
+                idx += 1;
+            } else {
+                self.undecide(cell);
+            }
+        }
+    }
+
+    fn decide(&mut self, idx: usize, alive: bool) {
+        self.decided[idx] = Some(alive);
+
+        for &neighbour in &self.neighbours[idx] {
+            self.undecided[neighbour] -= 1;
+            self.alive[neighbour] += alive as usize;
+        }
+    }
+
+    fn undecide(&mut self, idx: usize) {
+        let alive = self.decided[idx].take() == Some(true);
+
+        for &neighbour in &self.neighbours[idx] {
+            self.undecided[neighbour] += 1;
+            self.alive[neighbour] -= alive as usize;
+        }
+    }
+
+    /// Whether every cell depending on the cell can still get its state in
+    /// the target
+    fn is_possible(&self, idx: usize) -> bool {
+        self.dependents[idx].iter().all(|&cell| {
+            let (low, high) = (self.alive[cell], self.alive[cell] + self.undecided[cell]);
+            let counts = (u64::MAX >> (63 - high)) & (u64::MAX << low);
+
+            let allowed = match self.decided[cell] {
+                Some(alive) => self.allowed[cell][alive as usize],
+                None => self.allowed[cell][0] | self.allowed[cell][1],
+            };
+
+            allowed & counts != 0
+        })
+    }
+}
@@ -0,0 +1,249 @@
+//! Drawing the grid without depending on a particular graphics library
+//!
+//! The game only describes what goes where through [`Renderer`], so another
+//! backend (a GPU, a terminal, an image writer) only has to implement the
+//! trait. [`SfmlRenderer`] draws into the window.
+//!
+//! The cells of the square grid are kept by the renderer as a board between
+//! frames, so only the ones which changed have to be sent again.
+
+use crate::camera::Camera;
+use crate::color::Color;
+
+/// Outline of something drawn over the grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// Rectangle with its top left corner at the position
+    Rect { width: f32, height: f32 },
+
+    /// Regular polygon centered on the position, used for the hexagonal
+    /// cells and the ants
+    Polygon {
+        radius: f32,
+        sides: usize,
+
+        /// Clockwise in degrees
+        rotation: f32,
+    },
+}
+
+/// A filled shape in window coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub shape: Shape,
+    pub position: (f32, f32),
+    pub color: Color,
+}
+
+/// Arrangement of a board of equally sized rectangles in world coordinates,
+/// the cells are numbered row by row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    pub rows: usize,
+    pub cols: usize,
+    pub cell_size: (f32, f32),
+
+    /// Distance between the top left corners of neighbouring cells
+    pub spacing: (f32, f32),
+}
+
+pub trait Renderer {
+    /// Recolors cells of the board by their number, when the layout differs
+    /// from the last call the board is rebuilt with every cell black
+    fn update_board(&mut self, layout: BoardLayout, changes: &[(usize, Color)]);
+
+    /// Draws the board as seen through the camera
+    fn draw_board(&mut self, camera: &Camera);
+
+    /// Draws the cells in order, later ones on top of earlier ones
+    fn draw_cells(&mut self, cells: &[Cell]);
+
+    /// Draws a line of text with its top left corner at the position
+    fn draw_text(&mut self, text: &str, position: (f32, f32), size: u32, color: Color);
+
+    /// Draws the cells into a texture of the given size once, which is kept
+    /// under the key for [`Renderer::draw_thumbnail`]
+    fn render_thumbnail(&mut self, key: usize, size: (u32, u32), cells: &[Cell]);
+
+    /// Draws the texture kept under the key with its top left corner at the
+    /// position, `false` if there is none yet
+    fn draw_thumbnail(&mut self, key: usize, position: (f32, f32)) -> bool;
+
+    /// Shows everything drawn since the last call and starts the next frame
+    /// on a black background
+    fn present(&mut self);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use sfml_renderer::SfmlRenderer;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod sfml_renderer {
+    use std::collections::HashMap;
+
+    use sfml::graphics::{
+        CircleShape, Color, FloatRect, Font, PrimitiveType, RectangleShape, RenderStates,
+        RenderTarget, RenderTexture, RenderWindow, Shape as _, Sprite, Text, Transformable, Vertex,
+        View,
+    };
+
+    use super::{BoardLayout, Cell, Renderer, Shape};
+    use crate::camera::Camera;
+    use crate::{HEIGHT, STATUS_BAR_HEIGHT};
+
+    pub struct SfmlRenderer<'a> {
+        pub window: RenderWindow,
+        font: &'a Font,
+        rect: RectangleShape<'static>,
+        polygon: CircleShape<'static>,
+
+        /// Four corners of every cell of the board, drawn in one go
+        board: Vec<Vertex>,
+        board_layout: Option<BoardLayout>,
+
+        thumbnails: HashMap<usize, RenderTexture>,
+    }
+
+    impl<'a> SfmlRenderer<'a> {
+        pub fn new(mut window: RenderWindow, font: &'a Font) -> Self {
+            window.clear(Color::BLACK);
+
+            Self {
+                window,
+                font,
+                rect: RectangleShape::new(),
+                polygon: CircleShape::new(1.0, 6),
+                board: Vec::new(),
+                board_layout: None,
+                thumbnails: HashMap::new(),
+            }
+        }
+    }
+
+    fn draw_cell(
+        target: &mut impl RenderTarget,
+        rect: &mut RectangleShape,
+        polygon: &mut CircleShape,
+        cell: &Cell,
+    ) {
+        match cell.shape {
+            Shape::Rect { width, height } => {
+                rect.set_size((width, height));
+                rect.set_position(cell.position);
+                rect.set_fill_color(cell.color);
+                target.draw(rect);
+            }
+            Shape::Polygon {
+                radius,
+                sides,
+                rotation,
+            } => {
+                polygon.set_radius(radius);
+                polygon.set_point_count(sides);
+                polygon.set_origin((radius, radius));
+                polygon.set_rotation(rotation);
+                polygon.set_position(cell.position);
+                polygon.set_fill_color(cell.color);
+                target.draw(polygon);
+            }
+        }
+    }
+
+    impl Renderer for SfmlRenderer<'_> {
+        fn update_board(&mut self, layout: BoardLayout, changes: &[(usize, Color)]) {
+            if self.board_layout != Some(layout) {
+                let (width, height) = layout.cell_size;
+
+                self.board.clear();
+                for row in 0..layout.rows {
+                    for col in 0..layout.cols {
+                        let x = col as f32 * layout.spacing.0;
+                        let y = row as f32 * layout.spacing.1;
+
+                        for corner in [
+                            (x, y),
+                            (x + width, y),
+                            (x + width, y + height),
+                            (x, y + height),
+                        ] {
+                            self.board
+                                .push(Vertex::with_pos_color(corner.into(), Color::BLACK));
+                        }
+                    }
+                }
+                self.board_layout = Some(layout);
+            }
+
+            for &(idx, color) in changes {
+                if let Some(corners) = self.board.get_mut(idx * 4..idx * 4 + 4) {
+                    corners.iter_mut().for_each(|corner| corner.color = color);
+                }
+            }
+        }
+
+        fn draw_board(&mut self, camera: &Camera) {
+            let (left, top, width, height) = camera.viewport();
+            let mut view = View::new(
+                (left + width / 2.0, top + height / 2.0).into(),
+                (width, height).into(),
+            );
+            // keep the board out of the status bar
+            view.set_viewport(FloatRect::new(
+                0.0,
+                0.0,
+                1.0,
+                HEIGHT as f32 / (HEIGHT + STATUS_BAR_HEIGHT) as f32,
+            ));
+
+            self.window.set_view(&view);
+            self.window
+                .draw_primitives(&self.board, PrimitiveType::QUADS, &RenderStates::DEFAULT);
+
+            let default_view = self.window.default_view().to_owned();
+            self.window.set_view(&default_view);
+        }
+
+        fn draw_cells(&mut self, cells: &[Cell]) {
+            for cell in cells {
+                draw_cell(&mut self.window, &mut self.rect, &mut self.polygon, cell);
+            }
+        }
+
+        fn draw_text(&mut self, text: &str, position: (f32, f32), size: u32, color: Color) {
+            let mut text = Text::new(text, self.font, size);
+            text.set_position(position);
+            text.set_fill_color(color);
+            self.window.draw(&text);
+        }
+
+        fn render_thumbnail(&mut self, key: usize, (width, height): (u32, u32), cells: &[Cell]) {
+            let Some(mut texture) = RenderTexture::new(width, height) else {
+                return;
+            };
+
+            texture.clear(Color::TRANSPARENT);
+            for cell in cells {
+                draw_cell(&mut texture, &mut self.rect, &mut self.polygon, cell);
+            }
+            texture.display();
+
+            self.thumbnails.insert(key, texture);
+        }
+
+        fn draw_thumbnail(&mut self, key: usize, position: (f32, f32)) -> bool {
+            let Some(texture) = self.thumbnails.get(&key) else {
+                return false;
+            };
+
+            let mut sprite = Sprite::with_texture(texture.texture());
+            sprite.set_position(position);
+            self.window.draw(&sprite);
+            true
+        }
+
+        fn present(&mut self) {
+            self.window.display();
+            self.window.clear(Color::BLACK);
+        }
+    }
+}
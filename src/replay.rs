@@ -0,0 +1,322 @@
+//! Recording the inputs of a session and playing them back
+//!
+//! A recording starts with the seed and the commands given on the command
+//! line, followed by every frame: its length and the events which happened
+//! during it. Replaying feeds the same events in the same frames to a game
+//! started from the same seed, so it does exactly the same.
+//!
+//! ```text
+//! game-of-life replay
+//! seed 24301
+//! command load glider
+//! frame 16667
+//! key Space -
+//! press Left 400 380
+//! ```
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use sfml::system::Time;
+use sfml::window::{mouse, Event, Key};
+
+const HEADER: &str = "game-of-life replay";
+
+/// Every key SFML knows, to look keys up by name
+const KEYS: &[Key] = &[
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::Num0,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::Escape,
+    Key::LControl,
+    Key::LShift,
+    Key::LAlt,
+    Key::LSystem,
+    Key::RControl,
+    Key::RShift,
+    Key::RAlt,
+    Key::RSystem,
+    Key::Menu,
+    Key::LBracket,
+    Key::RBracket,
+    Key::Semicolon,
+    Key::Comma,
+    Key::Period,
+    Key::Quote,
+    Key::Slash,
+    Key::Backslash,
+    Key::Tilde,
+    Key::Equal,
+    Key::Hyphen,
+    Key::Space,
+    Key::Enter,
+    Key::Backspace,
+    Key::Tab,
+    Key::PageUp,
+    Key::PageDown,
+    Key::End,
+    Key::Home,
+    Key::Insert,
+    Key::Delete,
+    Key::Add,
+    Key::Subtract,
+    Key::Multiply,
+    Key::Divide,
+    Key::Left,
+    Key::Right,
+    Key::Up,
+    Key::Down,
+    Key::Numpad0,
+    Key::Numpad1,
+    Key::Numpad2,
+    Key::Numpad3,
+    Key::Numpad4,
+    Key::Numpad5,
+    Key::Numpad6,
+    Key::Numpad7,
+    Key::Numpad8,
+    Key::Numpad9,
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+    Key::F13,
+    Key::F14,
+    Key::F15,
+    Key::Pause,
+];
+
+const BUTTONS: &[mouse::Button] = &[
+    mouse::Button::Left,
+    mouse::Button::Right,
+    mouse::Button::Middle,
+    mouse::Button::XButton1,
+    mouse::Button::XButton2,
+];
+
+pub struct Recorder {
+    out: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, seed: u64, commands: &[String]) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{HEADER}\nseed {seed}")?;
+        for command in commands {
+            writeln!(out, "command {command}")?;
+        }
+
+        Ok(Self { out })
+    }
+
+    /// Records a frame, the events which don't change the game are left out
+    pub fn frame(&mut self, dt: Time, events: &[Event]) -> io::Result<()> {
+        writeln!(self.out, "frame {}", dt.as_microseconds())?;
+
+        for event in events {
+            if let Some(line) = encode(event) {
+                writeln!(self.out, "{line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Replay {
+    pub seed: u64,
+    pub commands: Vec<String>,
+    frames: VecDeque<(Time, Vec<Event>)>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Couldn't read {}: {err}", path.display()))?;
+        let mut lines = text.lines();
+
+        if lines.next() != Some(HEADER) {
+            return Err(format!("{} isn't a replay", path.display()));
+        }
+
+        let mut replay = Self {
+            seed: 0,
+            commands: Vec::new(),
+            frames: VecDeque::new(),
+        };
+
+        for (idx, line) in lines.enumerate() {
+            let invalid = || format!("Invalid line {} of the replay: '{line}'", idx + 2);
+            let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+            match kind {
+                "seed" => replay.seed = rest.parse().map_err(|_| invalid())?,
+                "command" => replay.commands.push(rest.to_string()),
+                "frame" => {
+                    let dt = rest.parse().map_err(|_| invalid())?;
+                    replay
+                        .frames
+                        .push_back((Time::microseconds(dt), Vec::new()));
+                }
+                _ => {
+                    let event = decode(line).ok_or_else(invalid)?;
+                    let (_, events) = replay.frames.back_mut().ok_or_else(invalid)?;
+                    events.push(event);
+                }
+            }
+        }
+
+        Ok(replay)
+    }
+
+    /// Length and events of the next frame, `None` once the replay is over
+    pub fn next_frame(&mut self) -> Option<(Time, Vec<Event>)> {
+        self.frames.pop_front()
+    }
+}
+
+fn modifiers(alt: bool, ctrl: bool, shift: bool, system: bool) -> String {
+    let flags = [(alt, 'a'), (ctrl, 'c'), (shift, 's'), (system, 'm')]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, flag)| *flag)
+        .collect::<String>();
+
+    if flags.is_empty() {
+        String::from("-")
+    } else {
+        flags
+    }
+}
+
+fn encode(event: &Event) -> Option<String> {
+    Some(match *event {
+        Event::KeyPressed {
+            code,
+            alt,
+            ctrl,
+            shift,
+            system,
+            ..
+        } => format!("key {code:?} {}", modifiers(alt, ctrl, shift, system)),
+        Event::KeyReleased {
+            code,
+            alt,
+            ctrl,
+            shift,
+            system,
+        } => format!("release {code:?} {}", modifiers(alt, ctrl, shift, system)),
+        Event::TextEntered { unicode } => format!("text {}", unicode as u32),
+        Event::MouseButtonPressed { button, x, y } => format!("press {button:?} {x} {y}"),
+        Event::MouseButtonReleased { button, x, y } => format!("unpress {button:?} {x} {y}"),
+        Event::MouseWheelScrolled {
+            wheel: mouse::Wheel::VerticalWheel,
+            delta,
+            x,
+            y,
+        } => format!("wheel {delta} {x} {y}"),
+        Event::MouseMoved { x, y } => format!("move {x} {y}"),
+        Event::MouseLeft => String::from("leave"),
+        _ => return None,
+    })
+}
+
+fn decode(line: &str) -> Option<Event> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let key = |name: &str| KEYS.iter().copied().find(|key| format!("{key:?}") == name);
+    let button = |name: &str| {
+        BUTTONS
+            .iter()
+            .copied()
+            .find(|button| format!("{button:?}") == name)
+    };
+    let flag = |flag: char| words.get(2).is_some_and(|flags| flags.contains(flag));
+
+    Some(match words.as_slice() {
+        ["key", code, _] => Event::KeyPressed {
+            code: key(code)?,
+            // SFML doesn't expose its scancodes, zero is a valid one and the
+            // game only looks at the key codes
+            scan: unsafe { std::mem::zeroed() },
+            alt: flag('a'),
+            ctrl: flag('c'),
+            shift: flag('s'),
+            system: flag('m'),
+        },
+        ["release", code, _] => Event::KeyReleased {
+            code: key(code)?,
+            alt: flag('a'),
+            ctrl: flag('c'),
+            shift: flag('s'),
+            system: flag('m'),
+        },
+        ["text", unicode] => Event::TextEntered {
+            unicode: char::from_u32(unicode.parse().ok()?)?,
+        },
+        ["press", name, x, y] => Event::MouseButtonPressed {
+            button: button(name)?,
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        },
+        ["unpress", name, x, y] => Event::MouseButtonReleased {
+            button: button(name)?,
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        },
+        ["wheel", delta, x, y] => Event::MouseWheelScrolled {
+            wheel: mouse::Wheel::VerticalWheel,
+            delta: delta.parse().ok()?,
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        },
+        ["move", x, y] => Event::MouseMoved {
+            x: x.parse().ok()?,
+            y: y.parse().ok()?,
+        },
+        ["leave"] => Event::MouseLeft,
+        _ => return None,
+    })
+}
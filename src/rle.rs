@@ -0,0 +1,331 @@
+//! The run length encoded pattern format used by Golly and most pattern
+//! collections
+//!
+//! `b` and `o` are dead and alive cells, patterns with more states write `.`
+//! for dead cells and `A` to `X` for the other states. Runs are prefixed with
+//! their length, `$` ends a row and `!` ends the pattern. The `#N` name, `#O`
+//! author and `#C` comment lines before the header are kept as [`Info`].
+
+use std::fmt::Write as _;
+
+use grid::Grid;
+
+use crate::CellState;
+
+/// Encoded lines are wrapped at this length
+const LINE_LENGTH: usize = 70;
+
+/// Patterns come from the clipboard and the network too, so their headers
+/// can't make us allocate more than this many cells on each side
+pub const MAX_SIZE: usize = 4096;
+
+#[derive(Clone)]
+pub struct Pattern {
+    pub cells: Grid<CellState>,
+
+    /// The rule from the header, if there was any
+    pub rule: Option<String>,
+
+    pub info: Info,
+}
+
+/// What a pattern is called, who found it and anything else said about it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Info {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+}
+
+impl Info {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.author.is_none() && self.comments.is_empty()
+    }
+
+    /// Parses a comment line of a pattern file, which is ignored if it
+    /// isn't a name, an author or a comment
+    fn parse_line(&mut self, line: &str) {
+        let text = |rest: &str| rest.trim().to_string();
+        let some_text = |rest: &str| Some(text(rest)).filter(|text| !text.is_empty());
+
+        if let Some(rest) = line.strip_prefix("#N") {
+            self.name = some_text(rest);
+        } else if let Some(rest) = line.strip_prefix("#O") {
+            self.author = some_text(rest);
+        } else if let Some(rest) = line.strip_prefix("#C").or(line.strip_prefix("#c")) {
+            self.comments.push(text(rest));
+        }
+    }
+}
+
+impl Pattern {
+    /// Copies the cells onto the grid with their top left corner at the
+    /// given cell, clipping whatever doesn't fit. States past the given
+    /// number of states become alive.
+    pub fn stamp(&self, grid: &mut Grid<CellState>, top: i64, left: i64, states: u8) {
+        for ((row, col), cell) in self.cells.indexed_iter() {
+            let (Ok(row), Ok(col)) = (
+                usize::try_from(top + row as i64),
+                usize::try_from(left + col as i64),
+            ) else {
+                continue;
+            };
+
+            if let Some(target) = grid.get_mut(row, col) {
+                *target = if cell.0 < states {
+                    *cell
+                } else {
+                    CellState::ALIVE
+                };
+            }
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Pattern, String> {
+    let mut info = Info::default();
+    text.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .for_each(|line| info.parse_line(line));
+
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let header = lines
+        .next()
+        .ok_or_else(|| String::from("The pattern is empty"))?;
+
+    let mut size = (None, None);
+    let mut rule = None;
+
+    let mut parts = header.split(',');
+    while let Some(part) = parts.next() {
+        let (key, value) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid header '{header}'"))?;
+        let value = value.trim();
+
+        match key.trim() {
+            "x" => size.1 = value.parse::<usize>().ok(),
+            "y" => size.0 = value.parse::<usize>().ok(),
+            // HROT rulestrings have commas of their own, the rule is the
+            // rest of the header
+            "rule" => {
+                rule = Some(
+                    parts
+                        .by_ref()
+                        .fold(value.to_string(), |rule, part| rule + "," + part.trim()),
+                )
+            }
+            _ => {}
+        }
+    }
+
+    let (Some(rows), Some(cols)) = size else {
+        return Err(format!("Invalid header '{header}'"));
+    };
+    if rows > MAX_SIZE || cols > MAX_SIZE {
+        return Err(format!(
+            "The pattern is larger than {MAX_SIZE} cells on each side"
+        ));
+    }
+
+    let mut cells = Grid::new(rows, cols);
+    let (mut row, mut col) = (0usize, 0usize);
+    let mut count = 0usize;
+    let mut prefix = None;
+
+    'body: for line in lines {
+        for c in line.chars() {
+            let run = count.max(1);
+
+            match c {
+                '0'..='9' => {
+                    count = count
+                        .checked_mul(10)
+                        .and_then(|count| count.checked_add(c.to_digit(10).unwrap() as usize))
+                        .ok_or_else(|| String::from("A run of the pattern is too long"))?;
+                    continue;
+                }
+                'p'..='y' if prefix.is_none() => {
+                    prefix = Some(c as u8 - b'p' + 1);
+                    continue;
+                }
+                '$' => {
+                    row = row.saturating_add(run);
+                    col = 0;
+                }
+                '!' => break 'body,
+                'b' | '.' => col = col.saturating_add(run),
+                'o' | 'A'..='X' => {
+                    let state = match c {
+                        'o' => 1,
+                        _ => u32::from(prefix.unwrap_or(0)) * 24 + u32::from(c as u8 - b'A' + 1),
+                    };
+                    let state = u8::try_from(state)
+                        .map_err(|_| format!("State {state} is out of range"))?;
+
+                    for _ in 0..run {
+                        match cells.get_mut(row, col) {
+                            Some(cell) => *cell = CellState(state),
+                            None => {
+                                return Err(String::from("The pattern is larger than its header"))
+                            }
+                        }
+                        col += 1;
+                    }
+                }
+                c if c.is_whitespace() => continue,
+                _ => return Err(format!("Unexpected '{c}' in the pattern")),
+            }
+
+            count = 0;
+            prefix = None;
+        }
+    }
+
+    Ok(Pattern { cells, rule, info })
+}
+
+pub fn encode(cells: &Grid<CellState>, rule: &str, info: &Info) -> String {
+    let multi_state = cells.iter().any(|cell| cell.0 > 1);
+    let symbol = |cell: CellState| match cell.0 {
+        0 if multi_state => String::from("."),
+        0 => String::from("b"),
+        _ if !multi_state => String::from("o"),
+        state => {
+            let prefix = match (state - 1) / 24 {
+                0 => String::new(),
+                n => char::from(b'p' + n - 1).to_string(),
+            };
+            format!("{prefix}{}", char::from(b'A' + (state - 1) % 24))
+        }
+    };
+
+    // runs of the whole pattern, trailing dead cells of every row are left out
+    let mut runs: Vec<(usize, String)> = Vec::new();
+    fn push(runs: &mut Vec<(usize, String)>, count: usize, symbol: String) {
+        match runs.last_mut() {
+            Some((n, last)) if *last == symbol => *n += count,
+            _ => runs.push((count, symbol)),
+        }
+    }
+
+    for (idx, row) in cells.iter_rows().enumerate() {
+        if idx > 0 {
+            push(&mut runs, 1, String::from("$"));
+        }
+
+        let row = row.copied().collect::<Vec<_>>();
+        let len = row
+            .iter()
+            .rposition(|cell| *cell != CellState::DEAD)
+            .map_or(0, |last| last + 1);
+
+        for cell in &row[..len] {
+            push(&mut runs, 1, symbol(*cell));
+        }
+    }
+
+    // neither trailing empty rows nor a trailing row end are needed
+    while runs.last().is_some_and(|(_, symbol)| symbol == "$") {
+        runs.pop();
+    }
+
+    let mut text = String::new();
+    if let Some(name) = &info.name {
+        let _ = writeln!(text, "#N {name}");
+    }
+    if let Some(author) = &info.author {
+        let _ = writeln!(text, "#O {author}");
+    }
+    for comment in &info.comments {
+        let _ = writeln!(text, "{}", format!("#C {comment}").trim_end());
+    }
+
+    let _ = writeln!(
+        text,
+        "x = {}, y = {}, rule = {rule}",
+        cells.cols(),
+        cells.rows()
+    );
+    let mut line = String::new();
+
+    for (count, symbol) in runs
+        .iter()
+        .map(|(count, symbol)| (*count, symbol.as_str()))
+        .chain([(1, "!")])
+    {
+        let token = match count {
+            1 => symbol.to_string(),
+            count => format!("{count}{symbol}"),
+        };
+
+        if line.len() + token.len() > LINE_LENGTH {
+            let _ = writeln!(text, "{line}");
+            line.clear();
+        }
+        line.push_str(&token);
+    }
+
+    let _ = writeln!(text, "{line}");
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&[u8]]) -> Grid<CellState> {
+        Grid::from_vec(
+            rows.iter()
+                .flat_map(|row| row.iter().map(|&state| CellState(state)))
+                .collect(),
+            rows[0].len(),
+        )
+    }
+
+    #[test]
+    fn reads_back_what_it_writes() {
+        let info = Info {
+            name: Some(String::from("Glider")),
+            author: Some(String::from("Richard K. Guy")),
+            comments: vec![String::from("The smallest spaceship"), String::new()],
+        };
+        let glider = grid(&[&[0, 1, 0, 0], &[0, 0, 1, 0], &[1, 1, 1, 0], &[0, 0, 0, 0]]);
+
+        let pattern = parse(&encode(&glider, "B3/S23", &info)).unwrap();
+        assert!(pattern.cells == glider);
+        assert_eq!(pattern.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(pattern.info, info);
+    }
+
+    #[test]
+    fn reads_back_every_state() {
+        let states = (0..=255).collect::<Vec<u8>>();
+        let cells = grid(&[&states, &[0; 256], &states]);
+
+        let text = encode(&cells, "R1,C255,S2-3,B3,NM", &Info::default());
+        assert!(text.lines().all(|line| line.len() <= LINE_LENGTH));
+        let pattern = parse(&text).unwrap();
+        assert!(pattern.cells == cells);
+        assert_eq!(pattern.rule.as_deref(), Some("R1,C255,S2-3,B3,NM"));
+    }
+
+    #[test]
+    fn refuses_sizes_and_states_it_cannot_hold() {
+        let too_large = format!("x = {}, y = 1\n!", MAX_SIZE + 1);
+        assert!(parse(&too_large).is_err());
+        assert!(parse("x = 1, y = 99999999999999999999\n!").is_err());
+        assert!(parse("x = 2, y = 1\n99999999999999999999999o!").is_err());
+        assert!(parse("x = 2, y = 1\n3o!").is_err());
+        assert!(parse("x = 2, y = 2\no$$o!").is_err());
+        assert!(parse("x = 1, y = 1\nyX!").is_err());
+        assert!(parse("x = 1, y = 1\nz!").is_err());
+        assert!(parse("x = 1\no!").is_err());
+        assert!(parse("").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::hrot::Hrot;
+use crate::CellState;
+
+/// Largest neighbourhood radius a [`Rule`] supports, the neighbour counts of
+/// a radius 2 Moore neighbourhood still fit in the birth/survival masks
+pub const MAX_RADIUS: u8 = 2;
+
+/// Which cells around a cell count as its neighbours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Neighbourhood {
+    /// Every cell in the surrounding square of the given radius, the usual 8
+    /// cells for radius 1
+    Moore(u8),
+
+    /// Every cell within the given manhattan distance, the 4 orthogonally
+    /// adjacent cells for radius 1
+    VonNeumann(u8),
+
+    /// The 6 surrounding cells of a hexagonal tiling, odd rows are shifted
+    /// half a cell to the right
+    Hexagonal,
+}
+
+impl Neighbourhood {
+    /// Neighbourhoods which can be cycled through at runtime
+    pub const ALL: [Self; 5] = [
+        Self::Moore(1),
+        Self::VonNeumann(1),
+        Self::Moore(2),
+        Self::VonNeumann(2),
+        Self::Hexagonal,
+    ];
+
+    /// Row and column offsets of the neighbours of a cell in the given row
+    pub fn offsets(&self, row: usize) -> Vec<(i32, i32)> {
+        match *self {
+            Self::Moore(radius) | Self::VonNeumann(radius) => {
+                let radius = radius as i32;
+                let mut offsets = Vec::new();
+
+                for dr in -radius..=radius {
+                    for dc in -radius..=radius {
+                        let in_range = match self {
+                            Self::VonNeumann(_) => dr.abs() + dc.abs() <= radius,
+                            _ => true,
+                        };
+
+                        if in_range && (dr, dc) != (0, 0) {
+                            offsets.push((dr, dc));
+                        }
+                    }
+                }
+
+                offsets
+            }
+            Self::Hexagonal if row & 1 == 0 => {
+                vec![(-1, -1), (-1, 0), (0, -1), (0, 1), (1, -1), (1, 0)]
+            }
+            Self::Hexagonal => vec![(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)],
+        }
+    }
+
+    pub fn radius(&self) -> u8 {
+        match *self {
+            Self::Moore(radius) | Self::VonNeumann(radius) => radius,
+            Self::Hexagonal => 1,
+        }
+    }
+}
+
+impl Default for Neighbourhood {
+    fn default() -> Self {
+        Self::Moore(1)
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Moore(radius) => write!(f, "Moore, radius {radius}"),
+            Self::VonNeumann(radius) => write!(f, "von Neumann, radius {radius}"),
+            Self::Hexagonal => f.write_str("hexagonal"),
+        }
+    }
+}
+
+/// A life-like or Generations rule
+///
+/// State `0` is dead, state `1` is alive and every state above that is a
+/// "dying" state which can neither be born nor survive, it just decays
+/// towards death one generation at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rule {
+    /// Bit `n` is set if a dead cell with `n` alive neighbours is born
+    pub birth: u32,
+
+    /// Bit `n` is set if an alive cell with `n` alive neighbours survives
+    pub survival: u32,
+
+    /// Total number of states, `2` for plain life-like rules
+    pub states: u8,
+
+    pub neighbourhood: Neighbourhood,
+}
+
+impl Rule {
+    /// B3/S23
+    pub const CONWAY: Self = Self {
+        birth: 1 << 3,
+        survival: 1 << 2 | 1 << 3,
+        states: 2,
+        neighbourhood: Neighbourhood::Moore(1),
+    };
+
+    /// B2/S/3
+    pub const BRIANS_BRAIN: Self = Self {
+        birth: 1 << 2,
+        survival: 0,
+        states: 3,
+        neighbourhood: Neighbourhood::Moore(1),
+    };
+
+    /// B2/S34H
+    pub const HEX_LIFE: Self = Self {
+        birth: 1 << 2,
+        survival: 1 << 3 | 1 << 4,
+        states: 2,
+        neighbourhood: Neighbourhood::Hexagonal,
+    };
+
+    pub fn next_state(&self, cell: CellState, alive_neighbours: usize) -> CellState {
+        match cell.0 {
+            0 if self.birth & 1 << alive_neighbours != 0 => CellState::ALIVE,
+            0 => CellState::DEAD,
+            1 if self.survival & 1 << alive_neighbours != 0 => CellState::ALIVE,
+            state if state + 1 < self.states => CellState(state + 1),
+            _ => CellState::DEAD,
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.neighbourhood.radius() > 1 {
+            return Hrot::from(self).fmt(f);
+        }
+
+        let digits = |mask: u32| {
+            (0..=8)
+                .filter(|n| mask & 1 << n != 0)
+                .map(|n| n.to_string())
+                .collect::<String>()
+        };
+
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))?;
+
+        if self.states > 2 {
+            write!(f, "/{}", self.states)?;
+        }
+
+        match self.neighbourhood {
+            Neighbourhood::VonNeumann(_) => f.write_str("V"),
+            Neighbourhood::Hexagonal => f.write_str("H"),
+            Neighbourhood::Moore(_) => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    /// Parses rulestrings of the form `B3/S23` or `B2/S/3`, a trailing `V`
+    /// or `H` selects the von Neumann or hexagonal neighbourhood. Larger
+    /// neighbourhoods are given in the HROT notation, see [`Hrot`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let mut rule = Self {
+            birth: 0,
+            survival: 0,
+            states: 2,
+            neighbourhood: Neighbourhood::Moore(1),
+        };
+
+        let s = if let Some(s) = s.strip_suffix(['V', 'v']) {
+            rule.neighbourhood = Neighbourhood::VonNeumann(1);
+            s
+        } else if let Some(s) = s.strip_suffix(['H', 'h']) {
+            rule.neighbourhood = Neighbourhood::Hexagonal;
+            s
+        } else {
+            s
+        };
+
+        let parse_digits = |part: &str| {
+            part.chars().try_fold(0u32, |mask, c| match c.to_digit(10) {
+                Some(n) if n <= 8 => Ok(mask | 1 << n),
+                _ => Err(format!("Invalid neighbour count '{c}'")),
+            })
+        };
+
+        for part in s.split('/') {
+            match part.chars().next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') => rule.birth = parse_digits(&part[1..])?,
+                Some('S') => rule.survival = parse_digits(&part[1..])?,
+                Some(c) if c.is_ascii_digit() => {
+                    rule.states = part
+                        .parse()
+                        .ok()
+                        .filter(|states| *states >= 2)
+                        .ok_or_else(|| format!("Invalid number of states '{part}'"))?;
+                }
+                _ => return Err(format!("Invalid rule '{s}'")),
+            }
+        }
+
+        Ok(rule)
+    }
+}
@@ -0,0 +1,293 @@
+//! Rhai scripts which build patterns and run experiments on the grid
+//!
+//! Cells are addressed as `x` (column) and `y` (row) from the top left
+//! corner. The functions available to scripts are
+//!
+//! - `set_cell(x, y)`, `set_cell(x, y, state)`, `get_cell(x, y)` and `clear()`
+//! - `load_pattern(name)` and `load_pattern(name, x, y)`, either a built-in
+//!   pattern or an RLE file, placed in the middle or with its top left
+//!   corner at the cell
+//! - `step()` and `step(n)`, at most 100000 generations at once
+//! - `run_until_stable()` and `run_until_stable(max)`, which return the
+//!   period the grid settled into or `0` if it didn't within `max`
+//!   generations (10000 by default, at most 100000)
+//!
+//! Scripts run on the window's thread, one still running after
+//! [`MAX_DURATION`] is stopped.
+//! - `random_fill(density)` and `set_rule(rule)`
+//! - `population()`, `generation()`, `rows()` and `cols()`
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use grid::Grid;
+use rhai::{Dynamic, Engine, EvalAltResult, INT};
+
+use crate::ant::Ant;
+use crate::automaton::Automaton;
+use crate::command::MAX_STEPS;
+use crate::engine::naive::Stepper;
+use crate::noise::Noise;
+use crate::period::PeriodDetector;
+use crate::rng::Rng;
+use crate::topology::Topology;
+use crate::{engine, history, patterns, CellState};
+
+/// Generations `run_until_stable()` gives up after
+const MAX_STABLE_GENERATIONS: INT = 10_000;
+
+/// Longest a script may run before it is stopped
+pub const MAX_DURATION: Duration = Duration::from_secs(10);
+
+/// Operations between two checks whether a script ran out of time
+const OPERATIONS_PER_CHECK: u64 = 1024;
+
+type Fallible<T> = Result<T, Box<EvalAltResult>>;
+
+/// The part of the game a script can change
+#[derive(Clone)]
+pub struct World {
+    pub grid: Grid<CellState>,
+    pub ants: Vec<Ant>,
+    pub automaton: Automaton,
+    pub automaton_enabled: bool,
+    pub topology: Topology,
+    pub noise: Noise,
+    pub generation: u64,
+    pub rng: Rng,
+    pub stepper: Stepper,
+}
+
+impl World {
+    fn cell(&self, x: INT, y: INT) -> Fallible<(usize, usize)> {
+        let (rows, cols) = self.grid.size();
+
+        match (usize::try_from(y), usize::try_from(x)) {
+            (Ok(row), Ok(col)) if row < rows && col < cols => Ok((row, col)),
+            _ => Err(format!("({x}, {y}) is outside of the {cols}x{rows} grid").into()),
+        }
+    }
+
+    fn set_cell(&mut self, x: INT, y: INT, state: INT) -> Fallible<()> {
+        let (row, col) = self.cell(x, y)?;
+
+        match u8::try_from(state) {
+            Ok(state) if state < self.automaton.states() => {
+                self.grid[row][col] = CellState(state);
+                Ok(())
+            }
+            _ => Err(format!("The rule has no state {state}").into()),
+        }
+    }
+
+    fn load_pattern(&mut self, name: &str, x: Option<INT>, y: Option<INT>) -> Fallible<()> {
+        let pattern = patterns::load(name)?;
+        let (rows, cols) = self.grid.size();
+
+        let top = y.unwrap_or((rows as INT - pattern.cells.rows() as INT) / 2);
+        let left = x.unwrap_or((cols as INT - pattern.cells.cols() as INT) / 2);
+
+        pattern.stamp(&mut self.grid, top, left, self.automaton.states());
+        Ok(())
+    }
+
+    /// Fails once the script ran past the deadline, the generations stepped
+    /// until then are kept
+    fn step(&mut self, count: INT, deadline: Instant) -> Fallible<()> {
+        if count > MAX_STEPS as INT {
+            return Err(format!("At most {MAX_STEPS} generations can be stepped at once").into());
+        }
+
+        for _ in 0..count {
+            if Instant::now() > deadline {
+                return Err(timed_out().into());
+            }
+
+            history::advance(
+                &mut self.grid,
+                &mut self.ants,
+                &self.automaton,
+                self.automaton_enabled,
+                self.topology,
+                self.noise,
+                &mut self.stepper,
+            );
+            self.generation += 1;
+        }
+
+        Ok(())
+    }
+
+    fn run_until_stable(&mut self, max: INT, deadline: Instant) -> Fallible<INT> {
+        if max > MAX_STEPS as INT {
+            return Err(format!("At most {MAX_STEPS} generations can be run until stable").into());
+        }
+
+        let mut detector = PeriodDetector::new();
+        detector.observe(self.generation, &self.grid);
+
+        for _ in 0..max {
+            self.step(1, deadline)?;
+
+            if let Some(period) = detector.observe(self.generation, &self.grid) {
+                return Ok(period as INT);
+            }
+        }
+
+        Ok(0)
+    }
+
+    fn random_fill(&mut self, density: f64) -> Fallible<()> {
+        if !(0.0..=1.0).contains(&density) {
+            return Err("The density must be between 0 and 1".into());
+        }
+
+        let (rows, cols) = self.grid.size();
+        self.grid = engine::soup(rows, cols, density, &mut self.rng);
+        Ok(())
+    }
+
+    fn set_rule(&mut self, rule: &str) -> Fallible<()> {
+        self.automaton = rule.parse::<Automaton>()?;
+
+        let states = self.automaton.states();
+        self.grid
+            .iter_mut()
+            .filter(|cell| cell.0 >= states)
+            .for_each(|cell| *cell = CellState::DEAD);
+
+        Ok(())
+    }
+}
+
+/// What a script is read from
+pub enum Source<'a> {
+    File(&'a Path),
+    Code(&'a str),
+}
+
+/// Runs the script against the world, which is only changed if it succeeds.
+/// Returns everything the script printed followed by the value it ended
+/// with.
+pub fn run(world: &mut World, source: Source) -> Result<String, String> {
+    let shared = Rc::new(RefCell::new(world.clone()));
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let deadline = Instant::now() + MAX_DURATION;
+    let mut engine = create_engine(&shared, &output, deadline);
+
+    engine.on_progress(move |operations| {
+        (operations % OPERATIONS_PER_CHECK == 0 && Instant::now() > deadline)
+            .then_some(Dynamic::UNIT)
+    });
+
+    let result = match source {
+        Source::File(path) => engine.run_file(path.to_path_buf()).map(|_| Dynamic::UNIT),
+        Source::Code(code) => engine.eval::<Dynamic>(code),
+    }
+    .map_err(|err| {
+        if Instant::now() > deadline {
+            timed_out()
+        } else {
+            err.to_string()
+        }
+    })?;
+
+    let mut output = output.take();
+    if !result.is_unit() {
+        output.push(result.to_string());
+    }
+
+    *world = shared.borrow().clone();
+    Ok(output.join("\n"))
+}
+
+fn timed_out() -> String {
+    format!(
+        "Stopped the script after {} seconds",
+        MAX_DURATION.as_secs()
+    )
+}
+
+fn create_engine(
+    world: &Rc<RefCell<World>>,
+    output: &Rc<RefCell<Vec<String>>>,
+    deadline: Instant,
+) -> Engine {
+    let mut engine = Engine::new();
+
+    let printed = Rc::clone(output);
+    engine.on_print(move |text| printed.borrow_mut().push(text.to_string()));
+
+    let w = Rc::clone(world);
+    engine.register_fn("set_cell", move |x: INT, y: INT| {
+        w.borrow_mut().set_cell(x, y, 1)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("set_cell", move |x: INT, y: INT, state: INT| {
+        w.borrow_mut().set_cell(x, y, state)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("get_cell", move |x: INT, y: INT| -> Fallible<INT> {
+        let world = w.borrow();
+        let (row, col) = world.cell(x, y)?;
+        Ok(world.grid[row][col].0 as INT)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("clear", move || {
+        w.borrow_mut()
+            .grid
+            .iter_mut()
+            .for_each(|cell| *cell = CellState::DEAD)
+    });
+
+    let w = Rc::clone(world);
+    engine.register_fn("load_pattern", move |name: &str| {
+        w.borrow_mut().load_pattern(name, None, None)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("load_pattern", move |name: &str, x: INT, y: INT| {
+        w.borrow_mut().load_pattern(name, Some(x), Some(y))
+    });
+
+    let w = Rc::clone(world);
+    engine.register_fn("step", move || w.borrow_mut().step(1, deadline));
+    let w = Rc::clone(world);
+    engine.register_fn("step", move |count: INT| {
+        w.borrow_mut().step(count, deadline)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("run_until_stable", move || {
+        w.borrow_mut()
+            .run_until_stable(MAX_STABLE_GENERATIONS, deadline)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("run_until_stable", move |max: INT| {
+        w.borrow_mut().run_until_stable(max, deadline)
+    });
+
+    let w = Rc::clone(world);
+    engine.register_fn("random_fill", move |density: f64| {
+        w.borrow_mut().random_fill(density)
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("set_rule", move |rule: &str| w.borrow_mut().set_rule(rule));
+
+    let w = Rc::clone(world);
+    engine.register_fn("population", move || {
+        w.borrow()
+            .grid
+            .iter()
+            .filter(|cell| **cell != CellState::DEAD)
+            .count() as INT
+    });
+    let w = Rc::clone(world);
+    engine.register_fn("generation", move || w.borrow().generation as INT);
+    let w = Rc::clone(world);
+    engine.register_fn("rows", move || w.borrow().grid.rows() as INT);
+    let w = Rc::clone(world);
+    engine.register_fn("cols", move || w.borrow().grid.cols() as INT);
+
+    engine
+}
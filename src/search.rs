@@ -0,0 +1,235 @@
+//! The headless `search` subcommand, runs lots of random soups and reports
+//! the ones which don't settle down the usual way
+
+use std::fmt::Write as _;
+use std::fs;
+use std::time::Instant;
+
+use grid::Grid;
+
+use crate::apgcode::{Classifier, Objects};
+use crate::automaton::Automaton;
+use crate::cli::SearchOptions;
+use crate::engine::{self, naive::Stepper};
+use crate::period::PeriodDetector;
+use crate::rng::Rng;
+use crate::topology::Topology;
+use crate::CellState;
+
+/// Cells this close to the edge of the universe end the run, before they
+/// get the chance to crash into the edge
+const ESCAPE_MARGIN: usize = 2;
+
+/// A soup only counts as escaping if no more than a glider's worth of cells
+/// is this close to the edge, otherwise the whole pattern outgrew the universe
+const ESCAPE_BAND: usize = 8;
+const GLIDER_CELLS: usize = 5;
+
+/// How many standard deviations away from the mean a final population has to
+/// be to be reported
+const UNUSUAL_POPULATION: f64 = 3.0;
+
+/// What happened to a single soup
+struct Outcome {
+    seed: u64,
+    soup: Grid<CellState>,
+
+    /// Generations until the pattern started repeating, escaped or the
+    /// search gave up on it
+    lifespan: u64,
+    period: Option<u64>,
+    population: usize,
+    escaped: bool,
+
+    /// Apgcodes of what the soup settled into, for the rules they exist for
+    objects: Option<Objects>,
+}
+
+pub fn run(options: &SearchOptions) -> Result<(), String> {
+    let automaton = options.rule;
+    let mut rng = Rng::new(options.seed);
+    let mut classifier = match automaton {
+        Automaton::Life(rule) => Classifier::new(rule),
+        _ => None,
+    };
+
+    let start = Instant::now();
+    let outcomes = (0..options.soups)
+        .map(|_| {
+            let seed = rng.next_u64();
+            let soup = engine::soup(
+                options.soup_size,
+                options.soup_size,
+                options.density,
+                &mut Rng::new(seed),
+            );
+
+            run_soup(options, &automaton, classifier.as_mut(), seed, soup)
+        })
+        .collect::<Vec<_>>();
+    let seconds = start.elapsed().as_secs_f64();
+
+    let count = outcomes.len() as f64;
+    let mean = outcomes.iter().map(|o| o.population as f64).sum::<f64>() / count;
+    let deviation = (outcomes
+        .iter()
+        .map(|o| (o.population as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count)
+        .sqrt();
+
+    let interesting = outcomes
+        .iter()
+        .filter_map(|outcome| {
+            let mut reasons = Vec::new();
+
+            if outcome.escaped {
+                reasons.push(String::from("escaping"));
+            }
+            if outcome.lifespan >= options.min_lifespan {
+                reasons.push(String::from("long-lived"));
+            }
+            if let Some(period) = outcome.period.filter(|period| *period > 2) {
+                reasons.push(format!("period {period}"));
+            }
+            if deviation > 0.0
+                && (outcome.population as f64 - mean).abs() > UNUSUAL_POPULATION * deviation
+            {
+                reasons.push(String::from("unusual population"));
+            }
+
+            (!reasons.is_empty()).then_some((outcome, reasons.join(", ")))
+        })
+        .collect::<Vec<_>>();
+
+    println!(
+        "searched {} soups of {rule} in {seconds:.1}s, seed {seed}, mean final population {mean:.1}",
+        outcomes.len(),
+        rule = options.rule,
+        seed = options.seed,
+    );
+
+    for (outcome, reasons) in &interesting {
+        println!(
+            "soup {}: lifespan {}, {}, population {} ({reasons})",
+            outcome.seed,
+            outcome.lifespan,
+            outcome
+                .period
+                .map_or(String::from("no period"), |period| format!(
+                    "period {period}"
+                )),
+            outcome.population,
+        );
+
+        if let Some(objects) = &outcome.objects {
+            println!("  objects: {objects}");
+        }
+    }
+
+    println!("{} interesting soups", interesting.len());
+
+    if classifier.is_some() {
+        let mut census = Objects::default();
+        for objects in outcomes
+            .iter()
+            .filter_map(|outcome| outcome.objects.as_ref())
+        {
+            census.add(objects);
+        }
+        println!("census: {census}");
+    }
+
+    if let Some(path) = &options.output {
+        let mut file = String::new();
+
+        for (outcome, reasons) in &interesting {
+            let _ = writeln!(file, "!Name: soup {}", outcome.seed);
+            let _ = writeln!(file, "!Rule: {}", options.rule);
+            let _ = writeln!(file, "!{reasons}, lifespan {}", outcome.lifespan);
+            if let Some(objects) = &outcome.objects {
+                let _ = writeln!(file, "!Objects: {objects}");
+            }
+            file.push_str(&to_plaintext(&outcome.soup));
+            file.push('\n');
+        }
+
+        fs::write(path, file).map_err(|err| format!("Couldn't write {path}: {err}"))?;
+        println!("saved them to {path}");
+    }
+
+    Ok(())
+}
+
+/// Runs the soup placed in the middle of an otherwise empty universe
+fn run_soup(
+    options: &SearchOptions,
+    automaton: &Automaton,
+    classifier: Option<&mut Classifier>,
+    seed: u64,
+    soup: Grid<CellState>,
+) -> Outcome {
+    let size = options.universe_size;
+    let offset = (size - options.soup_size) / 2;
+
+    let mut grid = Grid::new(size, size);
+    for ((row, col), cell) in soup.indexed_iter() {
+        grid[row + offset][col + offset] = *cell;
+    }
+
+    let mut outcome = Outcome {
+        seed,
+        soup,
+        lifespan: options.generations,
+        period: None,
+        population: 0,
+        escaped: false,
+        objects: None,
+    };
+
+    let mut detector = PeriodDetector::new();
+    let mut stepper = Stepper::new();
+    for generation in 0..options.generations {
+        if let Some(period) = detector.observe(generation, &grid) {
+            outcome.period = Some(period);
+            outcome.lifespan = generation - period;
+            break;
+        }
+
+        stepper.step(automaton, Topology::Plane, &mut grid);
+
+        if edge_population(&grid, ESCAPE_MARGIN) > 0 {
+            outcome.escaped = edge_population(&grid, ESCAPE_BAND) <= GLIDER_CELLS;
+            outcome.lifespan = generation + 1;
+            break;
+        }
+    }
+
+    outcome.population = grid.iter().filter(|cell| cell.is_alive()).count();
+    outcome.objects = classifier.map(|classifier| classifier.census(&grid));
+    outcome
+}
+
+/// Number of alive cells at most `width` cells away from the edge
+fn edge_population(grid: &Grid<CellState>, width: usize) -> usize {
+    let (rows, cols) = grid.size();
+
+    grid.indexed_iter()
+        .filter(|((row, col), cell)| {
+            cell.is_alive()
+                && (*row < width || *col < width || *row >= rows - width || *col >= cols - width)
+        })
+        .count()
+}
+
+/// The plaintext pattern format, `O` for alive and `.` for dead cells
+fn to_plaintext(grid: &Grid<CellState>) -> String {
+    let mut text = String::new();
+
+    for row in grid.iter_rows() {
+        text.extend(row.map(|cell| if cell.is_alive() { 'O' } else { '.' }));
+        text.push('\n');
+    }
+
+    text
+}
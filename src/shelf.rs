@@ -0,0 +1,324 @@
+//! Sidebar listing the patterns of the session, to put them onto the grid
+//!
+//! It starts with the built-in patterns and every loaded or pasted one is
+//! added below them. Clicking one selects it so every click on the grid
+//! stamps it there, dragging one out of the panel drops it where the mouse
+//! button is released.
+
+use grid::Grid;
+use sfml::graphics::FloatRect;
+use sfml::window::{mouse, Event};
+
+use crate::color::Color;
+use crate::minimap::Minimap;
+use crate::render::{self, Renderer};
+use crate::rle::Pattern;
+use crate::widget::Panel;
+use crate::{patterns, CellState, WIDTH};
+
+const PANEL_WIDTH: f32 = 120.0;
+const PADDING: f32 = 10.0;
+
+/// Width and height of the thumbnails in pixels
+const THUMBNAIL_SIZE: u32 = 80;
+
+/// Thumbnail and name of a pattern
+const ITEM_HEIGHT: f32 = THUMBNAIL_SIZE as f32 + 28.0;
+
+const FONT_SIZE: u32 = 12;
+
+/// Longest name shown in full, longer ones are cut off
+const NAME_LENGTH: usize = 14;
+
+/// Cells of tiny patterns don't get larger than this in the thumbnails
+const MAX_THUMBNAIL_CELL_SIZE: f32 = 16.0;
+
+static SELECTED: Color = Color::rgba(80, 120, 255, 120);
+static HOVERED: Color = Color::rgb(50, 50, 50);
+static OTHER_STATE: Color = Color::rgb(140, 140, 140);
+
+struct Item {
+    name: String,
+    pattern: Pattern,
+}
+
+pub enum ShelfResponse {
+    /// The event is for the grid
+    Ignored,
+    Handled,
+
+    /// The item was dragged off the panel and released over these window
+    /// coordinates
+    Dropped {
+        item: usize,
+        x: i32,
+        y: i32,
+    },
+}
+
+pub struct Shelf {
+    panel: Panel,
+    items: Vec<Item>,
+
+    /// Index of the topmost item shown
+    scroll: usize,
+
+    /// Item stamped by clicking on the grid
+    selected: Option<usize>,
+
+    /// Item the mouse button went down on, dragged while it is held
+    pressed: Option<usize>,
+    hovered: Option<usize>,
+}
+
+impl Shelf {
+    /// A closed shelf holding the built-in patterns
+    pub fn new() -> Self {
+        let height = Minimap::top();
+        let mut shelf = Self {
+            panel: Panel::new(
+                FloatRect::new(WIDTH as f32 - PANEL_WIDTH, 0.0, PANEL_WIDTH, height),
+                false,
+            ),
+            items: Vec::new(),
+            scroll: 0,
+            selected: None,
+            pressed: None,
+            hovered: None,
+        };
+
+        for (name, _) in patterns::PATTERNS {
+            if let Some(pattern) = patterns::find(name) {
+                shelf.add(name, &pattern);
+            }
+        }
+
+        shelf
+    }
+
+    /// Adds the pattern at the bottom unless the shelf already has one with
+    /// the same cells
+    pub fn add(&mut self, name: &str, pattern: &Pattern) {
+        if pattern.cells.is_empty()
+            || self
+                .items
+                .iter()
+                .any(|item| item.pattern.cells == pattern.cells)
+        {
+            return;
+        }
+
+        self.items.push(Item {
+            name: name.to_string(),
+            pattern: pattern.clone(),
+        });
+    }
+
+    pub fn toggle(&mut self) {
+        self.panel.open = !self.panel.open;
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn deselect(&mut self) {
+        self.selected = None;
+    }
+
+    pub fn pattern(&self, item: usize) -> Option<&Pattern> {
+        self.items.get(item).map(|item| &item.pattern)
+    }
+
+    /// The pattern following the mouse, the one being dragged or else the
+    /// selected one
+    pub fn picked(&self) -> Option<&Pattern> {
+        self.pressed
+            .or(self.selected)
+            .and_then(|item| self.pattern(item))
+    }
+
+    /// Number of items which fit into the panel at once
+    fn visible_count(&self) -> usize {
+        ((self.panel.bounds.height - PADDING) / ITEM_HEIGHT).max(1.0) as usize
+    }
+
+    /// Top left corner of the thumbnail of an item, `None` if it is scrolled
+    /// out of view
+    fn item_position(&self, item: usize) -> Option<(f32, f32)> {
+        let row = item.checked_sub(self.scroll)?;
+        if row >= self.visible_count() {
+            return None;
+        }
+
+        let bounds = self.panel.bounds;
+        Some((
+            bounds.left + (bounds.width - THUMBNAIL_SIZE as f32) / 2.0,
+            bounds.top + PADDING + row as f32 * ITEM_HEIGHT,
+        ))
+    }
+
+    /// Item under the window coordinates on the open panel
+    fn item_at(&self, x: i32, y: i32) -> Option<usize> {
+        let (x, y) = (x as f32, y as f32);
+        let bounds = self.panel.bounds;
+        if !self.panel.open || !bounds.contains2(x, y) {
+            return None;
+        }
+
+        let row = (y - bounds.top - PADDING) / ITEM_HEIGHT;
+        if row < 0.0 || row as usize >= self.visible_count() {
+            return None;
+        }
+
+        Some(self.scroll + row as usize).filter(|&item| item < self.items.len())
+    }
+
+    pub fn handle_event(&mut self, event: &Event) -> ShelfResponse {
+        if self.panel.handle_event(event) {
+            return ShelfResponse::Handled;
+        }
+
+        match *event {
+            Event::MouseMoved { x, y } => {
+                self.hovered = self.item_at(x, y);
+                ShelfResponse::Ignored
+            }
+
+            Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } if self.panel.contains(x as f32, y as f32) => {
+                self.pressed = self.item_at(x, y);
+                ShelfResponse::Handled
+            }
+
+            Event::MouseButtonReleased {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } => match self.pressed.take() {
+                Some(item) if !self.panel.contains(x as f32, y as f32) => {
+                    ShelfResponse::Dropped { item, x, y }
+                }
+                Some(item) => {
+                    if self.item_at(x, y) == Some(item) {
+                        self.selected = (self.selected != Some(item)).then_some(item);
+                    }
+                    ShelfResponse::Handled
+                }
+                None => ShelfResponse::Ignored,
+            },
+
+            Event::MouseWheelScrolled { delta, x, y, .. }
+                if self.panel.contains(x as f32, y as f32) =>
+            {
+                let last = self.items.len().saturating_sub(self.visible_count());
+                self.scroll = if delta > 0.0 {
+                    self.scroll.saturating_sub(1)
+                } else {
+                    (self.scroll + 1).min(last)
+                };
+                self.hovered = self.item_at(x, y);
+                ShelfResponse::Handled
+            }
+
+            // the other buttons mustn't reach the grid under the panel
+            Event::MouseButtonPressed { x, y, .. } | Event::MouseButtonReleased { x, y, .. }
+                if self.panel.contains(x as f32, y as f32) =>
+            {
+                ShelfResponse::Handled
+            }
+
+            _ => ShelfResponse::Ignored,
+        }
+    }
+
+    pub fn draw(&self, renderer: &mut impl Renderer) {
+        self.panel.draw(renderer);
+        if !self.panel.open {
+            return;
+        }
+
+        for (idx, item) in self.items.iter().enumerate() {
+            let Some((left, top)) = self.item_position(idx) else {
+                continue;
+            };
+
+            let highlight = if self.selected == Some(idx) {
+                Some(SELECTED)
+            } else {
+                Some(HOVERED).filter(|_| self.hovered == Some(idx))
+            };
+
+            if let Some(color) = highlight {
+                renderer.draw_cells(&[render::Cell {
+                    shape: render::Shape::Rect {
+                        width: PANEL_WIDTH - PADDING,
+                        height: ITEM_HEIGHT - PADDING / 2.0,
+                    },
+                    position: (self.panel.bounds.left + PADDING / 2.0, top - PADDING / 2.0),
+                    color,
+                }]);
+            }
+
+            // the texture is only drawn the first time the item is shown
+            if !renderer.draw_thumbnail(idx, (left, top)) {
+                renderer.render_thumbnail(
+                    idx,
+                    (THUMBNAIL_SIZE, THUMBNAIL_SIZE),
+                    &thumbnail(&item.pattern.cells),
+                );
+                renderer.draw_thumbnail(idx, (left, top));
+            }
+
+            let name = if item.name.chars().count() > NAME_LENGTH {
+                format!(
+                    "{}~",
+                    item.name.chars().take(NAME_LENGTH - 1).collect::<String>()
+                )
+            } else {
+                item.name.clone()
+            };
+
+            renderer.draw_text(
+                &name,
+                (
+                    self.panel.bounds.left + PADDING / 2.0 + 2.0,
+                    top + THUMBNAIL_SIZE as f32 + 2.0,
+                ),
+                FONT_SIZE,
+                Color::WHITE,
+            );
+        }
+    }
+}
+
+/// The cells scaled to fit into a thumbnail and centered in it
+fn thumbnail(cells: &Grid<CellState>) -> Vec<render::Cell> {
+    let (rows, cols) = cells.size();
+    let size = (THUMBNAIL_SIZE as f32 / rows.max(cols) as f32).min(MAX_THUMBNAIL_CELL_SIZE);
+    let left = (THUMBNAIL_SIZE as f32 - cols as f32 * size) / 2.0;
+    let top = (THUMBNAIL_SIZE as f32 - rows as f32 * size) / 2.0;
+
+    // leave a gap between cells once there is room for it
+    let gap = if size >= 4.0 { 1.0 } else { 0.0 };
+
+    cells
+        .indexed_iter()
+        .filter(|(_, cell)| **cell != CellState::DEAD)
+        .map(|((row, col), cell)| render::Cell {
+            shape: render::Shape::Rect {
+                width: size - gap,
+                height: size - gap,
+            },
+            position: (left + col as f32 * size, top + row as f32 * size),
+            color: if *cell == CellState::ALIVE {
+                Color::WHITE
+            } else {
+                OTHER_STATE
+            },
+        })
+        .collect()
+}
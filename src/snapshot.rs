@@ -0,0 +1,172 @@
+//! Saving the whole session into a JSON or RON file and restoring it
+//! exactly, built with the `serde` feature
+//!
+//! Continuous (Lenia) mode isn't part of a snapshot.
+
+use std::fs;
+use std::path::Path;
+
+use grid::Grid;
+use serde::{Deserialize, Serialize};
+
+use crate::automaton::Automaton;
+use crate::camera::Camera;
+use crate::history::Snapshot;
+use crate::rng::Rng;
+use crate::CellState;
+
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    /// The active layer, its ants and the rule
+    pub board: Snapshot,
+
+    /// Every layer, the slot of the active one is left empty
+    pub layers: Vec<Grid<CellState>>,
+    pub active_layer: usize,
+
+    /// Milliseconds between steps
+    pub speed: i32,
+
+    /// Index into [`crate::STEPS_PER_FRAME`]
+    pub steps_per_frame: usize,
+    pub camera: Camera,
+
+    pub seed: u64,
+    pub rng: Rng,
+    pub soup_density: f64,
+}
+
+impl Session {
+    /// Saves RON if the file ends in `.ron` and JSON otherwise
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = if is_ron(path) {
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                .map_err(|err| err.to_string())
+        } else {
+            serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+        };
+
+        text.and_then(|text| fs::write(path, text).map_err(|err| err.to_string()))
+            .map_err(|err| format!("Couldn't save {}: {err}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("Couldn't read {}: {err}", path.display()))?;
+
+        let mut session: Self = if is_ron(path) {
+            ron::from_str(&text).map_err(|err| err.to_string())
+        } else {
+            serde_json::from_str(&text).map_err(|err| err.to_string())
+        }
+        .map_err(|err| format!("Invalid snapshot {}: {err}", path.display()))?;
+
+        let size = session.board.grid.size();
+        let consistent = size.0 > 0
+            && size.1 > 0
+            && session.active_layer < session.layers.len()
+            && session
+                .layers
+                .iter()
+                .enumerate()
+                .all(|(idx, layer)| idx == session.active_layer || layer.size() == size);
+
+        if !consistent {
+            return Err(format!(
+                "Invalid snapshot {}: the layers don't match",
+                path.display()
+            ));
+        }
+
+        // rules the rulestrings can't describe could overflow the neighbour
+        // counts, a rule has to come back the same from its rulestring
+        let automaton = session.board.automaton;
+        if automaton.to_string().parse::<Automaton>() != Ok(automaton) {
+            return Err(format!(
+                "Invalid snapshot {}: unsupported rule {automaton}",
+                path.display()
+            ));
+        }
+
+        session.camera.sanitize();
+        Ok(session)
+    }
+}
+
+fn is_ron(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ron"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::Rule;
+
+    fn session(automaton: Automaton) -> Session {
+        let mut grid = Grid::new(8, 16);
+        grid[1][2] = CellState::ALIVE;
+
+        Session {
+            board: Snapshot {
+                grid: grid.clone(),
+                ants: Vec::new(),
+                generation: 3,
+                automaton,
+                automaton_enabled: true,
+                topology: Default::default(),
+                noise: Default::default(),
+            },
+            layers: vec![Grid::new(0, 0), grid],
+            active_layer: 0,
+            speed: 100,
+            steps_per_frame: 0,
+            camera: Camera::default(),
+            seed: 7,
+            rng: Rng::new(7),
+            soup_density: 0.5,
+        }
+    }
+
+    /// Saves the session and loads it back from a file of the given extension
+    fn reload(session: &Session, extension: &str) -> Result<Session, String> {
+        let path = std::env::temp_dir().join(format!(
+            "game-of-life-snapshot-{}.{extension}",
+            std::process::id()
+        ));
+
+        session.save(&path)?;
+        let loaded = Session::load(&path);
+        let _ = fs::remove_file(&path);
+        loaded
+    }
+
+    #[test]
+    fn loads_what_it_saves() {
+        for extension in ["json", "ron"] {
+            let loaded = reload(&session(Automaton::Life(Rule::CONWAY)), extension).unwrap();
+
+            assert!(loaded.board.grid == session(Automaton::default()).board.grid);
+            assert_eq!(loaded.board.generation, 3);
+            assert_eq!(loaded.board.automaton, Automaton::Life(Rule::CONWAY));
+            assert_eq!(loaded.layers.len(), 2);
+        }
+    }
+
+    #[test]
+    fn refuses_snapshots_it_cannot_restore() {
+        let mut mismatched = session(Automaton::default());
+        mismatched.layers[1] = Grid::new(4, 4);
+        assert!(reload(&mismatched, "json").is_err());
+
+        let mut no_layer = session(Automaton::default());
+        no_layer.active_layer = 2;
+        assert!(reload(&no_layer, "json").is_err());
+
+        let unsupported = Automaton::Life(Rule {
+            states: 1,
+            ..Rule::CONWAY
+        });
+        assert!(reload(&session(unsupported), "json").is_err());
+    }
+}
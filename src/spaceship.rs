@@ -0,0 +1,129 @@
+//! Finds objects which reappear translated a few generations later
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use grid::Grid;
+
+use crate::cluster::{self, Cluster};
+use crate::CellState;
+
+/// Longest period of a spaceship which can be detected
+pub const MAX_PERIOD: usize = 16;
+
+pub struct Spaceship {
+    pub cluster: Cluster,
+    pub period: u32,
+
+    /// Rows and columns moved every period
+    pub displacement: (i32, i32),
+}
+
+impl Spaceship {
+    /// The speed in the usual notation, `c/4 diagonal`
+    pub fn speed(&self) -> String {
+        let (dr, dc) = (self.displacement.0.abs(), self.displacement.1.abs());
+        let distance = dr.max(dc) as u32;
+
+        let gcd = gcd(distance, self.period);
+        let (distance, period) = (distance / gcd, self.period / gcd);
+
+        let direction = if dr == 0 || dc == 0 {
+            "orthogonal"
+        } else if dr == dc {
+            "diagonal"
+        } else {
+            "oblique"
+        };
+
+        match (distance, period) {
+            (1, 1) => format!("c {direction}"),
+            (1, period) => format!("c/{period} {direction}"),
+            (distance, period) => format!("{distance}c/{period} {direction}"),
+        }
+    }
+}
+
+impl fmt::Display for Spaceship {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.speed())
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Shape hashes and positions of the objects of the last few generations
+#[derive(Default)]
+pub struct SpaceshipTracker {
+    history: VecDeque<Vec<(u64, (usize, usize))>>,
+}
+
+impl SpaceshipTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the next generation and returns the objects in it which were
+    /// seen translated by at most the speed of light some generations ago,
+    /// provided they weren't also sitting at their current position back then
+    pub fn observe(&mut self, grid: &Grid<CellState>) -> Vec<Spaceship> {
+        let clusters = cluster::clusters(grid);
+        let shapes = clusters
+            .iter()
+            .map(|cluster| (cluster.shape_hash(), (cluster.top, cluster.left)))
+            .collect::<Vec<_>>();
+
+        let mut spaceships = Vec::new();
+
+        for (cluster, (hash, (top, left))) in clusters.into_iter().zip(&shapes) {
+            let matched = (1..=self.history.len()).find_map(|period| {
+                let earlier = &self.history[self.history.len() - period];
+                let matching = earlier.iter().filter(|(other, _)| other == hash);
+
+                let mut displacement = None;
+                for (_, (other_top, other_left)) in matching {
+                    let (dr, dc) = (
+                        *top as i32 - *other_top as i32,
+                        *left as i32 - *other_left as i32,
+                    );
+
+                    // the same object at the same place, it's not going anywhere
+                    if (dr, dc) == (0, 0) {
+                        return None;
+                    }
+
+                    if dr.abs().max(dc.abs()) <= period as i32 {
+                        displacement = Some((dr, dc));
+                    }
+                }
+
+                displacement.map(|displacement| (period as u32, displacement))
+            });
+
+            if let Some((period, displacement)) = matched {
+                spaceships.push(Spaceship {
+                    cluster,
+                    period,
+                    displacement,
+                });
+            }
+        }
+
+        if self.history.len() == MAX_PERIOD {
+            self.history.pop_front();
+        }
+        self.history.push_back(shapes);
+
+        spaceships
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
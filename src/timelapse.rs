@@ -0,0 +1,117 @@
+//! The headless `timelapse` subcommand, writes generations of a run as PNG
+//! frames which can be assembled into a video, e.g. with
+//! `ffmpeg -i frames/%06d.png video.mp4`
+
+use std::fs;
+use std::path::Path;
+
+use grid::Grid;
+
+use crate::automaton::Automaton;
+use crate::cli::TimelapseOptions;
+use crate::engine::{self, naive::Stepper};
+use crate::noise::Noise;
+use crate::rng::Rng;
+use crate::{history, patterns, png, CellState};
+
+pub fn run(options: &TimelapseOptions) -> Result<(), String> {
+    let (mut grid, automaton) = match &options.pattern {
+        Some(name) => {
+            let pattern = patterns::load(name)?;
+            let automaton = match (&options.rule, &pattern.rule) {
+                (Some(automaton), _) => *automaton,
+                (None, Some(rule)) => rule.parse()?,
+                (None, None) => Automaton::default(),
+            };
+
+            let mut grid = Grid::new(options.rows, options.cols);
+            pattern.stamp(
+                &mut grid,
+                (options.rows as i64 - pattern.cells.rows() as i64) / 2,
+                (options.cols as i64 - pattern.cells.cols() as i64) / 2,
+                automaton.states(),
+            );
+            (grid, automaton)
+        }
+        None => (
+            engine::soup(
+                options.rows,
+                options.cols,
+                options.density,
+                &mut Rng::new(options.seed),
+            ),
+            options.rule.unwrap_or_default(),
+        ),
+    };
+
+    fs::create_dir_all(&options.output)
+        .map_err(|err| format!("Couldn't create {}: {err}", options.output.display()))?;
+
+    let noise = Noise {
+        level: options.noise,
+        seed: options.seed,
+    };
+    let mut stepper = Stepper::new();
+    let mut frames = 0u64;
+
+    for generation in 0..=options.generations {
+        if generation % options.every == 0 {
+            write_frame(&options.output, frames, &grid, &automaton, options.scale)?;
+            frames += 1;
+        }
+
+        if generation < options.generations {
+            history::advance(
+                &mut grid,
+                &mut [],
+                &automaton,
+                true,
+                options.topology,
+                noise,
+                &mut stepper,
+            );
+        }
+    }
+
+    println!(
+        "Wrote {frames} frames of {}x{} pixels to {}",
+        options.cols * options.scale as usize,
+        options.rows * options.scale as usize,
+        options.output.display()
+    );
+    Ok(())
+}
+
+/// Writes the grid as the numbered frame into the directory, every cell
+/// `scale` pixels wide and high. The numbers have no gaps so video tools
+/// find every frame.
+fn write_frame(
+    dir: &Path,
+    frame: u64,
+    grid: &Grid<CellState>,
+    automaton: &Automaton,
+    scale: u32,
+) -> Result<(), String> {
+    let scale = scale as usize;
+    let (rows, cols) = grid.size();
+    let (width, height) = (cols * scale, rows * scale);
+
+    let mut rgb = Vec::with_capacity(width * height * 3);
+    for row in 0..rows {
+        let mut line = Vec::with_capacity(width * 3);
+        for cell in grid.iter_row(row) {
+            let color = automaton.color(*cell);
+            for _ in 0..scale {
+                line.extend_from_slice(&[color.r, color.g, color.b]);
+            }
+        }
+
+        for _ in 0..scale {
+            rgb.extend_from_slice(&line);
+        }
+    }
+
+    let path = dir.join(format!("{frame:06}.png"));
+    fs::write(&path, png::encode(width as u32, height as u32, &rgb))
+        .map_err(|err| format!("Couldn't write {}: {err}", path.display()))
+}
@@ -0,0 +1,144 @@
+//! How the edges of the bounded grid are glued together
+//!
+//! The neighbours of a cell at the edge which lie outside the grid are
+//! either dead or the cells some other edge is glued to.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale::fill;
+
+/// Every topology in the order they are cycled through
+pub const TOPOLOGIES: [Topology; 5] = [
+    Topology::Plane,
+    Topology::Torus,
+    Topology::KleinBottle,
+    Topology::CrossSurface,
+    Topology::Mirror,
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// Everything beyond the edges is dead
+    #[default]
+    Plane,
+
+    /// Leaving at one edge enters at the opposite one
+    Torus,
+
+    /// Like the torus, but crossing the top or the bottom edge mirrors the
+    /// columns
+    KleinBottle,
+
+    /// Crossing any edge mirrors the other direction, the real projective
+    /// plane
+    CrossSurface,
+
+    /// Every edge reflects, the cells beyond it are the ones just inside it
+    Mirror,
+}
+
+impl Topology {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Plane => "Plane",
+            Self::Torus => "Torus",
+            Self::KleinBottle => "Klein bottle",
+            Self::CrossSurface => "Cross-surface",
+            Self::Mirror => "Mirrored edges",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let idx = TOPOLOGIES.iter().position(|topology| *topology == self);
+        TOPOLOGIES[idx.map_or(0, |idx| (idx + 1) % TOPOLOGIES.len())]
+    }
+
+    /// Cell of a grid of the size at a position which may be outside of it,
+    /// `None` if it is beyond a dead edge
+    pub fn cell(self, row: i32, col: i32, rows: usize, cols: usize) -> Option<(usize, usize)> {
+        let (rows, cols) = (rows as i32, cols as i32);
+        if (0..rows).contains(&row) && (0..cols).contains(&col) {
+            return Some((row as usize, col as usize));
+        }
+
+        // times the position crossed an edge and where it ended up
+        let wrap = |value: i32, size: i32| (value.div_euclid(size), value.rem_euclid(size));
+        let mirror = |value: i32, size: i32| size - 1 - value;
+
+        let (row, col) = match self {
+            Self::Plane => return None,
+            Self::Torus => (row.rem_euclid(rows), col.rem_euclid(cols)),
+            Self::KleinBottle => {
+                let (crossed, row) = wrap(row, rows);
+                let col = col.rem_euclid(cols);
+                (
+                    row,
+                    if crossed % 2 != 0 {
+                        mirror(col, cols)
+                    } else {
+                        col
+                    },
+                )
+            }
+            Self::CrossSurface => {
+                let ((crossed_rows, row), (crossed_cols, col)) = (wrap(row, rows), wrap(col, cols));
+                (
+                    if crossed_cols % 2 != 0 {
+                        mirror(row, rows)
+                    } else {
+                        row
+                    },
+                    if crossed_rows % 2 != 0 {
+                        mirror(col, cols)
+                    } else {
+                        col
+                    },
+                )
+            }
+            Self::Mirror => {
+                let reflect = |value: i32, size: i32| {
+                    let value = if value < 0 { -1 - value } else { value };
+                    let value = if value >= size {
+                        2 * size - 1 - value
+                    } else {
+                        value
+                    };
+                    value.clamp(0, size - 1)
+                };
+                (reflect(row, rows), reflect(col, cols))
+            }
+        };
+
+        Some((row as usize, col as usize))
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Plane => "plane",
+            Self::Torus => "torus",
+            Self::KleinBottle => "klein",
+            Self::CrossSurface => "cross",
+            Self::Mirror => "mirror",
+        })
+    }
+}
+
+impl FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TOPOLOGIES
+            .into_iter()
+            .find(|topology| topology.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                fill(
+                    "Unknown topology '{}', expected plane, torus, klein, cross or mirror",
+                    &[&s],
+                )
+            })
+    }
+}
@@ -0,0 +1,265 @@
+//! Frontend drawing the grid into the terminal, for running over SSH or
+//! without a display server
+//!
+//! Every cell is two block characters wide so it comes out roughly square.
+//! The keys are the same as in the window wherever the terminal can tell
+//! them apart, the arrow keys move the cursor instead of seeking and Enter
+//! toggles the cell under it.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+
+use crate::command::Command;
+use crate::game::Game;
+
+/// Time between redraws while nothing happens
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+/// Lines below the grid, for the status and the command line
+const STATUS_LINES: u16 = 2;
+
+struct Tui {
+    game: Game,
+
+    /// Row and column of the cell under the cursor
+    cursor: (usize, usize),
+
+    /// Top left cell shown when the grid doesn't fit into the terminal
+    scroll: (usize, usize),
+
+    /// Text typed after `:`, while the command line is open
+    command_line: Option<String>,
+    message: Option<String>,
+    quit: bool,
+}
+
+/// Runs the game in the terminal until `q` is pressed, the commands run
+/// first as if typed into the command line
+pub fn run(seed: u64, commands: Vec<Command>) -> io::Result<()> {
+    let mut tui = Tui {
+        game: Game::new(seed),
+        cursor: (0, 0),
+        scroll: (0, 0),
+        command_line: None,
+        message: None,
+        quit: false,
+    };
+
+    for command in commands {
+        tui.message = tui.game.run(command);
+    }
+
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(
+        stdout,
+        terminal::EnterAlternateScreen,
+        cursor::Hide,
+        event::EnableMouseCapture
+    )?;
+
+    let result = tui.main_loop(&mut stdout);
+
+    // the terminal has to be restored even if drawing failed
+    execute!(
+        stdout,
+        event::DisableMouseCapture,
+        ResetColor,
+        cursor::Show,
+        terminal::LeaveAlternateScreen
+    )?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+impl Tui {
+    fn main_loop(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let mut last_step = Instant::now();
+
+        while !self.quit {
+            self.draw(out)?;
+
+            let timeout = if self.game.playing {
+                self.game
+                    .time_bw_steps
+                    .saturating_sub(last_step.elapsed())
+                    .min(FRAME_TIME)
+            } else {
+                FRAME_TIME
+            };
+
+            if event::poll(timeout)? {
+                self.handle_event(event::read()?);
+            }
+
+            if self.game.playing && last_step.elapsed() >= self.game.time_bw_steps {
+                last_step = Instant::now();
+                for _ in 0..self.game.steps_per_frame() {
+                    self.game.step();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            }) => {
+                if self.command_line.is_some() {
+                    self.handle_command_key(code);
+                } else {
+                    self.message = self.handle_key(code, modifiers);
+                }
+            }
+
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => {
+                let cell = (
+                    self.scroll.0 + row as usize,
+                    self.scroll.1 + column as usize / 2,
+                );
+
+                if cell.0 < self.game.grid.rows() && cell.1 < self.game.grid.cols() {
+                    self.cursor = cell;
+                    self.game.toggle(cell.0, cell.1);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    /// Optionally a message to show in the status line
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<String> {
+        let (rows, cols) = self.game.grid.size();
+
+        match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(':') => self.command_line = Some(String::new()),
+            KeyCode::Char(key) => return self.game.key(key),
+            KeyCode::Backspace => return self.game.step_back(),
+
+            KeyCode::Up => self.cursor.0 = self.cursor.0.saturating_sub(1),
+            KeyCode::Down => self.cursor.0 = (self.cursor.0 + 1).min(rows - 1),
+            KeyCode::Left => self.cursor.1 = self.cursor.1.saturating_sub(1),
+            KeyCode::Right => self.cursor.1 = (self.cursor.1 + 1).min(cols - 1),
+            KeyCode::Enter => self.game.toggle(self.cursor.0, self.cursor.1),
+
+            _ => {}
+        }
+
+        None
+    }
+
+    fn handle_command_key(&mut self, code: KeyCode) {
+        let Some(line) = &mut self.command_line else {
+            return;
+        };
+
+        match code {
+            KeyCode::Char(c) => line.push(c),
+            KeyCode::Backspace => {
+                line.pop();
+            }
+            KeyCode::Esc => self.command_line = None,
+            KeyCode::Enter => {
+                let line = self.command_line.take().unwrap_or_default();
+                if !line.trim().is_empty() {
+                    self.message = self.game.run_line(&line);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let grid = &self.game.grid;
+        let (width, height) = terminal::size()?;
+        let visible_rows = (height.saturating_sub(STATUS_LINES) as usize).min(grid.rows());
+        let visible_cols = (width as usize / 2).min(grid.cols());
+
+        // the grid may have shrunk since the cursor moved
+        self.cursor = (
+            self.cursor.0.min(grid.rows() - 1),
+            self.cursor.1.min(grid.cols() - 1),
+        );
+
+        // keep the cursor in view
+        for (scroll, cursor, visible) in [
+            (&mut self.scroll.0, self.cursor.0, visible_rows),
+            (&mut self.scroll.1, self.cursor.1, visible_cols),
+        ] {
+            if cursor < *scroll {
+                *scroll = cursor;
+            } else if visible > 0 && cursor >= *scroll + visible {
+                *scroll = cursor + 1 - visible;
+            }
+        }
+
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+
+        for line in 0..visible_rows {
+            let row = self.scroll.0 + line;
+            queue!(out, cursor::MoveTo(0, line as u16))?;
+
+            for col in self.scroll.1..self.scroll.1 + visible_cols {
+                let color = self
+                    .game
+                    .palette
+                    .color(&self.game.automaton, grid[row][col]);
+                let symbol = if (row, col) == self.cursor {
+                    "[]"
+                } else {
+                    "  "
+                };
+
+                queue!(
+                    out,
+                    SetBackgroundColor(Color::Rgb {
+                        r: color.r,
+                        g: color.g,
+                        b: color.b,
+                    }),
+                    SetForegroundColor(Color::Grey),
+                    Print(symbol)
+                )?;
+            }
+
+            queue!(out, ResetColor)?;
+        }
+
+        let bottom = match &self.command_line {
+            Some(line) => format!(":{line}_"),
+            None => self.message.clone().unwrap_or_default(),
+        };
+
+        for (idx, text) in [self.game.status(), bottom].iter().enumerate() {
+            queue!(
+                out,
+                cursor::MoveTo(0, visible_rows as u16 + idx as u16),
+                Print(text.chars().take(width as usize).collect::<String>())
+            )?;
+        }
+
+        out.flush()
+    }
+}
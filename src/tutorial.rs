@@ -0,0 +1,188 @@
+//! Guided tour through the rules and the controls
+//!
+//! A scenario is a list of lessons, each setting the grid up, prompting the
+//! user over the top of the grid and waiting for them to do what the prompt
+//! asks before the next lesson starts.
+
+use crate::locale::{fill, tr};
+
+/// What a lesson sees of the game, sampled every frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Progress {
+    pub generation: u64,
+    pub playing: bool,
+
+    /// Number of cells toggled by hand
+    pub edits: u64,
+}
+
+/// The grid a lesson starts from
+#[derive(Debug, Clone, Copy)]
+pub enum Setup {
+    Keep,
+    Clear,
+
+    /// A built-in pattern in the middle of the cleared grid, paused
+    Place(&'static str),
+}
+
+/// What a lesson waits for
+#[derive(Debug, Clone, Copy)]
+pub enum Goal {
+    /// Pressing Enter after reading the prompt
+    Read,
+
+    /// Stepping this many generations further
+    Step(u64),
+
+    /// Playing for at least this many generations
+    Play(u64),
+    Pause,
+
+    /// Stepping back to an earlier generation
+    StepBack,
+
+    /// Toggling this many cells by hand
+    Edit(u64),
+}
+
+pub struct Lesson {
+    pub prompt: &'static str,
+    pub setup: Setup,
+    pub goal: Goal,
+}
+
+/// The rules of Conway's life and the basic controls, started with Ctrl + T
+pub const INTRODUCTION: &[Lesson] = &[
+    Lesson {
+        prompt: "Welcome! Every cell is alive or dead and every step decides its fate by its 8 neighbours. Press Enter to continue, Ctrl + T leaves the tutorial",
+        setup: Setup::Clear,
+        goal: Goal::Read,
+    },
+    Lesson {
+        prompt: "This is a blinker. Press Space to step it once",
+        setup: Setup::Place("blinker"),
+        goal: Goal::Step(1),
+    },
+    Lesson {
+        prompt: "A live cell with 2 or 3 live neighbours survives and a dead cell with exactly 3 is born, every other cell dies. Step once more",
+        setup: Setup::Keep,
+        goal: Goal::Step(1),
+    },
+    Lesson {
+        prompt: "Back where it started, the blinker oscillates with a period of 2. Press Enter to continue",
+        setup: Setup::Keep,
+        goal: Goal::Read,
+    },
+    Lesson {
+        prompt: "This is a glider. Press P to play and watch it",
+        setup: Setup::Place("glider"),
+        goal: Goal::Play(12),
+    },
+    Lesson {
+        prompt: "Every 4 generations the glider moves a cell diagonally. Press P again to pause",
+        setup: Setup::Keep,
+        goal: Goal::Pause,
+    },
+    Lesson {
+        prompt: "Every generation is recorded, press Backspace to step back",
+        setup: Setup::Keep,
+        goal: Goal::StepBack,
+    },
+    Lesson {
+        prompt: "Click cells of the empty grid to bring 3 of them to life",
+        setup: Setup::Clear,
+        goal: Goal::Edit(3),
+    },
+    Lesson {
+        prompt: "Press Space to see what your cells do",
+        setup: Setup::Keep,
+        goal: Goal::Step(1),
+    },
+    Lesson {
+        prompt: "F fills the grid with a random soup, M changes the rule, = and - change the speed, + and - on the numpad resize the grid and : opens the command line, where help lists the commands. Press Enter to finish",
+        setup: Setup::Keep,
+        goal: Goal::Read,
+    },
+];
+
+/// What the game does after a frame of the tutorial
+pub enum Advance {
+    Stay,
+
+    /// Set up the next lesson and [`Tutorial::begin`] it
+    Start(Setup),
+    Finish,
+}
+
+pub struct Tutorial {
+    scenario: &'static [Lesson],
+    lesson: usize,
+
+    /// The game as the lesson began
+    start: Progress,
+    confirmed: bool,
+}
+
+impl Tutorial {
+    /// The scenario's first lesson, which has to be set up and begun
+    pub fn new(scenario: &'static [Lesson]) -> Self {
+        Self {
+            scenario,
+            lesson: 0,
+            start: Progress::default(),
+            confirmed: false,
+        }
+    }
+
+    pub fn setup(&self) -> Setup {
+        self.scenario[self.lesson].setup
+    }
+
+    /// Starts waiting for the goal of the lesson from the set up game
+    pub fn begin(&mut self, progress: Progress) {
+        self.start = progress;
+        self.confirmed = false;
+    }
+
+    /// Enter was pressed while the lesson's prompt was shown
+    pub fn confirm(&mut self) {
+        self.confirmed = true;
+    }
+
+    pub fn prompt(&self) -> String {
+        fill(
+            "Tutorial {}/{}: {}",
+            &[
+                &(self.lesson + 1),
+                &self.scenario.len(),
+                &tr(self.scenario[self.lesson].prompt),
+            ],
+        )
+    }
+
+    /// Moves on to the next lesson once the goal of this one is reached
+    pub fn update(&mut self, progress: Progress) -> Advance {
+        let start = self.start;
+        let reached = match self.scenario[self.lesson].goal {
+            Goal::Read => self.confirmed,
+            Goal::Step(count) => progress.generation >= start.generation + count,
+            Goal::Play(count) => {
+                progress.playing && progress.generation >= start.generation + count
+            }
+            Goal::Pause => !progress.playing,
+            Goal::StepBack => progress.generation < start.generation,
+            Goal::Edit(count) => progress.edits >= start.edits + count,
+        };
+
+        if !reached {
+            return Advance::Stay;
+        }
+
+        self.lesson += 1;
+        match self.scenario.get(self.lesson) {
+            Some(lesson) => Advance::Start(lesson.setup),
+            None => Advance::Finish,
+        }
+    }
+}
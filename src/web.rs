@@ -0,0 +1,199 @@
+//! Frontend drawing into a canvas of a web page, built for wasm32 with the
+//! `web` feature
+//!
+//! The grid is drawn into the `<canvas id="game-of-life">` of the page (see
+//! `index.html`), or a new canvas appended to the body. The keys are the
+//! ones of the window where the browser lets them through, a click toggles
+//! a cell and `:` asks for a command in a prompt.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, KeyboardEvent, MouseEvent};
+
+use crate::game::Game;
+use crate::{HEIGHT, STATUS_BAR_HEIGHT, WIDTH};
+
+const CANVAS_ID: &str = "game-of-life";
+const FONT: &str = "14px monospace";
+
+struct Web {
+    game: Game,
+    context: CanvasRenderingContext2d,
+
+    /// Timestamp of the last step in milliseconds
+    last_step: f64,
+    message: Option<String>,
+}
+
+pub fn run() {
+    if let Err(err) = start() {
+        web_sys::console::error_1(&err);
+    }
+}
+
+fn start() -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("No window")?;
+    let document = window.document().ok_or("No document")?;
+
+    let canvas = match document.get_element_by_id(CANVAS_ID) {
+        Some(element) => element.dyn_into::<HtmlCanvasElement>()?,
+        None => {
+            let canvas = document
+                .create_element("canvas")?
+                .dyn_into::<HtmlCanvasElement>()?;
+            document.body().ok_or("No body")?.append_child(&canvas)?;
+            canvas
+        }
+    };
+    canvas.set_width(WIDTH);
+    canvas.set_height(HEIGHT + STATUS_BAR_HEIGHT);
+
+    let context = canvas
+        .get_context("2d")?
+        .ok_or("No 2d context")?
+        .dyn_into::<CanvasRenderingContext2d>()?;
+
+    let web = Rc::new(RefCell::new(Web {
+        game: Game::new(js_sys::Date::now() as u64),
+        context,
+        last_step: 0.0,
+        message: None,
+    }));
+
+    let on_click = {
+        let web = Rc::clone(&web);
+        Closure::<dyn FnMut(MouseEvent)>::new(move |event: MouseEvent| {
+            web.borrow_mut().click(event.offset_x(), event.offset_y());
+        })
+    };
+    canvas.add_event_listener_with_callback("mousedown", on_click.as_ref().unchecked_ref())?;
+    on_click.forget();
+
+    let on_key = {
+        let web = Rc::clone(&web);
+        Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+            // leave the browser's shortcuts alone
+            if event.ctrl_key() || event.meta_key() || event.alt_key() {
+                return;
+            }
+
+            if web.borrow_mut().key(&event.key()) {
+                event.prevent_default();
+            }
+        })
+    };
+    document.add_event_listener_with_callback("keydown", on_key.as_ref().unchecked_ref())?;
+    on_key.forget();
+
+    // every frame requests the next one, so the closure has to refer to itself
+    let frame = Rc::new(RefCell::new(None::<Closure<dyn FnMut(f64)>>));
+    let next_frame = Rc::clone(&frame);
+    *frame.borrow_mut() = Some(Closure::new(move |time: f64| {
+        web.borrow_mut().frame(time);
+
+        if let Some(callback) = next_frame.borrow().as_ref() {
+            request_animation_frame(callback);
+        }
+    }));
+
+    if let Some(callback) = frame.borrow().as_ref() {
+        request_animation_frame(callback);
+    }
+
+    Ok(())
+}
+
+fn request_animation_frame(callback: &Closure<dyn FnMut(f64)>) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+    }
+}
+
+impl Web {
+    /// Returns `true` if the key was used, so the browser doesn't act on it
+    /// as well
+    fn key(&mut self, key: &str) -> bool {
+        self.message = match key {
+            "Backspace" => self.game.step_back(),
+            ":" => {
+                let line = web_sys::window()
+                    .and_then(|window| window.prompt_with_message(":").ok().flatten());
+
+                match line {
+                    Some(line) if !line.trim().is_empty() => self.game.run_line(&line),
+                    _ => None,
+                }
+            }
+            _ => {
+                let mut chars = key.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(key), None) => self.game.key(key.to_ascii_lowercase()),
+                    _ => return false,
+                }
+            }
+        };
+
+        true
+    }
+
+    fn click(&mut self, x: i32, y: i32) {
+        let (rows, cols) = self.game.grid.size();
+        let row = y as f64 / (HEIGHT as f64 / rows as f64);
+        let col = x as f64 / (WIDTH as f64 / cols as f64);
+
+        if row >= 0.0 && col >= 0.0 {
+            self.game.toggle(row as usize, col as usize);
+        }
+    }
+
+    fn frame(&mut self, time: f64) {
+        let time_bw_steps = self.game.time_bw_steps.as_secs_f64() * 1000.0;
+
+        if self.game.playing && time - self.last_step >= time_bw_steps {
+            self.last_step = time;
+            for _ in 0..self.game.steps_per_frame() {
+                self.game.step();
+            }
+        }
+
+        self.draw();
+    }
+
+    fn draw(&self) {
+        let context = &self.context;
+        let (rows, cols) = self.game.grid.size();
+        let cell_height = HEIGHT as f64 / rows as f64;
+        let cell_width = WIDTH as f64 / cols as f64;
+
+        context.set_fill_style_str("black");
+        context.fill_rect(0.0, 0.0, WIDTH as f64, (HEIGHT + STATUS_BAR_HEIGHT) as f64);
+
+        for ((row, col), cell) in self.game.grid.indexed_iter() {
+            if cell.0 == 0 {
+                continue;
+            }
+
+            let color = self.game.palette.color(&self.game.automaton, *cell);
+            context.set_fill_style_str(&format!("rgb({}, {}, {})", color.r, color.g, color.b));
+            context.fill_rect(
+                col as f64 * cell_width,
+                row as f64 * cell_height,
+                cell_width,
+                cell_height,
+            );
+        }
+
+        context.set_fill_style_str("white");
+        context.fill_rect(0.0, HEIGHT as f64, WIDTH as f64, 2.0);
+        context.set_font(FONT);
+
+        let lines = [Some(self.game.status()), self.message.clone()];
+        for (idx, line) in lines.iter().flatten().enumerate() {
+            let _ = context.fill_text(line, 10.0, HEIGHT as f64 + 24.0 + idx as f64 * 18.0);
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! Minimal clickable widgets drawn with plain SFML shapes
+
+use sfml::graphics::*;
+use sfml::window::*;
+
+use crate::render::{self, Renderer};
+
+const FONT_SIZE: u32 = 14;
+static BORDER: Color = Color::rgb(120, 120, 120);
+static HOVERED: Color = Color::rgb(50, 50, 50);
+static PANEL_BACKGROUND: Color = Color::rgba(20, 20, 20, 230);
+
+/// Size of the tab which opens and closes a panel
+const HANDLE_WIDTH: f32 = 16.0;
+const HANDLE_HEIGHT: f32 = 48.0;
+
+pub struct Button {
+    pub bounds: FloatRect,
+    pub label: String,
+    hovered: bool,
+}
+
+impl Button {
+    pub fn new(bounds: FloatRect, label: impl Into<String>) -> Self {
+        Self {
+            bounds,
+            label: label.into(),
+            hovered: false,
+        }
+    }
+
+    /// Returns `true` if the button was clicked
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::MouseMoved { x, y } => {
+                self.hovered = self.bounds.contains2(x as f32, y as f32);
+                false
+            }
+
+            Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } => self.bounds.contains2(x as f32, y as f32),
+
+            _ => false,
+        }
+    }
+
+    pub fn draw(&self, window: &mut RenderWindow, font: &Font) {
+        let mut rect = RectangleShape::new();
+        rect.set_position(self.bounds.position());
+        rect.set_size(self.bounds.size());
+        rect.set_outline_thickness(1.0);
+        rect.set_outline_color(BORDER);
+        rect.set_fill_color(if self.hovered { HOVERED } else { Color::BLACK });
+        window.draw(&rect);
+
+        let mut text = Text::new(&self.label, font, FONT_SIZE);
+        let text_bounds = text.local_bounds();
+        text.set_position((
+            self.bounds.left + (self.bounds.width - text_bounds.width) / 2.0 - text_bounds.left,
+            self.bounds.top + (self.bounds.height - text_bounds.height) / 2.0 - text_bounds.top,
+        ));
+        text.set_fill_color(Color::WHITE);
+        window.draw(&text);
+    }
+}
+
+/// A horizontal slider which can be clicked or dragged
+pub struct Slider {
+    pub bounds: FloatRect,
+    pub min: f32,
+    pub max: f32,
+    pub value: f32,
+    dragging: bool,
+}
+
+impl Slider {
+    pub fn new(bounds: FloatRect, min: f32, max: f32, value: f32) -> Self {
+        Self {
+            bounds,
+            min,
+            max,
+            value,
+            dragging: false,
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Returns the new value if the user moved the slider
+    pub fn handle_event(&mut self, event: &Event) -> Option<f32> {
+        match *event {
+            Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } if self.bounds.contains2(x as f32, y as f32) => {
+                self.dragging = true;
+                Some(self.value_at(x as f32))
+            }
+
+            Event::MouseMoved { x, .. } if self.dragging => Some(self.value_at(x as f32)),
+
+            Event::MouseButtonReleased {
+                button: mouse::Button::Left,
+                ..
+            } => {
+                self.dragging = false;
+                None
+            }
+
+            _ => None,
+        }
+    }
+
+    fn value_at(&mut self, x: f32) -> f32 {
+        let t = ((x - self.bounds.left) / self.bounds.width).clamp(0.0, 1.0);
+        self.value = self.min + t * (self.max - self.min);
+        self.value
+    }
+
+    pub fn draw(&self, window: &mut RenderWindow) {
+        let mut track = RectangleShape::new();
+        track.set_position((
+            self.bounds.left,
+            self.bounds.top + self.bounds.height / 2.0 - 1.0,
+        ));
+        track.set_size((self.bounds.width, 2.0));
+        track.set_fill_color(BORDER);
+        window.draw(&track);
+
+        let t = (self.value - self.min) / (self.max - self.min);
+        let mut handle = RectangleShape::new();
+        handle.set_size((8.0, self.bounds.height));
+        handle.set_origin((4.0, 0.0));
+        handle.set_position((self.bounds.left + t * self.bounds.width, self.bounds.top));
+        handle.set_fill_color(Color::WHITE);
+        window.draw(&handle);
+    }
+}
+
+/// A single line of text typed in while it is focused, earlier lines can be
+/// recalled with the up and down arrows
+pub struct TextInput {
+    pub bounds: FloatRect,
+    pub prompt: String,
+    pub text: String,
+    focused: bool,
+
+    /// Character of the key which focused the input, its text event arrives
+    /// right after and mustn't be typed
+    skip: Option<char>,
+
+    /// Submitted lines, oldest first
+    history: Vec<String>,
+    recalled: Option<usize>,
+}
+
+impl TextInput {
+    pub fn new(bounds: FloatRect, prompt: impl Into<String>) -> Self {
+        Self {
+            bounds,
+            prompt: prompt.into(),
+            text: String::new(),
+            focused: false,
+            skip: None,
+            history: Vec::new(),
+            recalled: None,
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// `skip` is the character typed by the key which focused the input
+    pub fn focus(&mut self, skip: Option<char>) {
+        self.focused = true;
+        self.skip = skip;
+        self.text.clear();
+        self.recalled = None;
+    }
+
+    /// Returns the line once Enter is pressed, Escape leaves without one
+    pub fn handle_event(&mut self, event: &Event) -> Option<String> {
+        if !self.focused {
+            return None;
+        }
+
+        match *event {
+            Event::TextEntered { unicode } => {
+                let skipped = self.skip.take() == Some(unicode);
+
+                if !skipped && !unicode.is_control() {
+                    self.text.push(unicode);
+                }
+            }
+
+            Event::KeyPressed { code, .. } => {
+                self.skip = None;
+
+                match code {
+                    Key::Enter => {
+                        self.focused = false;
+                        let line = std::mem::take(&mut self.text);
+
+                        if !line.trim().is_empty() {
+                            if self.history.last() != Some(&line) {
+                                self.history.push(line.clone());
+                            }
+                            return Some(line);
+                        }
+                    }
+                    Key::Escape => self.focused = false,
+                    Key::Backspace => {
+                        self.text.pop();
+                    }
+                    Key::Up if !self.history.is_empty() => {
+                        let idx = self
+                            .recalled
+                            .map_or(self.history.len() - 1, |idx| idx.saturating_sub(1));
+                        self.recalled = Some(idx);
+                        self.text = self.history[idx].clone();
+                    }
+                    Key::Down => {
+                        self.recalled = self
+                            .recalled
+                            .map(|idx| idx + 1)
+                            .filter(|&idx| idx < self.history.len());
+                        self.text = self
+                            .recalled
+                            .map_or_else(String::new, |idx| self.history[idx].clone());
+                    }
+                    _ => {}
+                }
+            }
+
+            _ => {}
+        }
+
+        None
+    }
+
+    pub fn draw(&self, window: &mut RenderWindow, font: &Font) {
+        let mut rect = RectangleShape::new();
+        rect.set_position(self.bounds.position());
+        rect.set_size(self.bounds.size());
+        rect.set_outline_thickness(1.0);
+        rect.set_outline_color(BORDER);
+        rect.set_fill_color(Color::BLACK);
+        window.draw(&rect);
+
+        let mut text = Text::new(&format!("{}{}_", self.prompt, self.text), font, FONT_SIZE);
+        text.set_position((
+            self.bounds.left + 4.0,
+            self.bounds.top + (self.bounds.height - FONT_SIZE as f32) / 2.0 - 2.0,
+        ));
+        text.set_fill_color(Color::WHITE);
+        window.draw(&text);
+    }
+}
+
+/// A box along the right edge of the window which folds away behind a tab,
+/// what it holds is drawn over it by its owner
+pub struct Panel {
+    pub bounds: FloatRect,
+    pub open: bool,
+    handle_hovered: bool,
+}
+
+impl Panel {
+    pub fn new(bounds: FloatRect, open: bool) -> Self {
+        Self {
+            bounds,
+            open,
+            handle_hovered: false,
+        }
+    }
+
+    /// The tab left of the panel, at the edge of the window while it is
+    /// closed
+    pub fn handle(&self) -> FloatRect {
+        let right = if self.open {
+            self.bounds.left
+        } else {
+            self.bounds.left + self.bounds.width
+        };
+
+        FloatRect::new(
+            right - HANDLE_WIDTH,
+            self.bounds.top,
+            HANDLE_WIDTH,
+            HANDLE_HEIGHT,
+        )
+    }
+
+    /// Whether the point is on the tab or on the open panel
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.handle().contains2(x, y) || (self.open && self.bounds.contains2(x, y))
+    }
+
+    /// Returns `true` if the tab was clicked, which opens or closes the
+    /// panel
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::MouseMoved { x, y } => {
+                self.handle_hovered = self.handle().contains2(x as f32, y as f32);
+                false
+            }
+
+            Event::MouseButtonPressed {
+                button: mouse::Button::Left,
+                x,
+                y,
+            } if self.handle().contains2(x as f32, y as f32) => {
+                self.open = !self.open;
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    pub fn draw(&self, renderer: &mut impl Renderer) {
+        let rect = |bounds: FloatRect, color: Color| render::Cell {
+            shape: render::Shape::Rect {
+                width: bounds.width,
+                height: bounds.height,
+            },
+            position: (bounds.left, bounds.top),
+            color,
+        };
+
+        let handle = self.handle();
+        let mut cells = vec![rect(
+            handle,
+            if self.handle_hovered {
+                HOVERED
+            } else {
+                PANEL_BACKGROUND
+            },
+        )];
+
+        if self.open {
+            cells.push(rect(self.bounds, PANEL_BACKGROUND));
+            cells.push(rect(
+                FloatRect::new(self.bounds.left, self.bounds.top, 1.0, self.bounds.height),
+                BORDER,
+            ));
+        }
+        renderer.draw_cells(&cells);
+
+        renderer.draw_text(
+            if self.open { ">" } else { "<" },
+            (
+                handle.left + 3.0,
+                handle.top + (handle.height - FONT_SIZE as f32) / 2.0 - 2.0,
+            ),
+            FONT_SIZE,
+            Color::WHITE,
+        );
+    }
+}